@@ -1,5 +1,6 @@
 use criterion::{Criterion, criterion_group, criterion_main};
 use ical::generator::{Emitter, IcalCalendar};
+use ical::parser::BorrowedComponent;
 
 fn parse_ical() -> IcalCalendar {
     let input = include_str!("../tests/resources/ical_everything.ics");
@@ -7,6 +8,12 @@ fn parse_ical() -> IcalCalendar {
     reader.into_iter().next().unwrap().unwrap()
 }
 
+fn parse_ical_borrowed() -> BorrowedComponent<'static> {
+    let input = include_str!("../tests/resources/ical_everything.ics");
+    let reader = ical::BorrowedIcalParser::new(input);
+    reader.into_iter().next().unwrap().unwrap()
+}
+
 fn benchmark(c: &mut Criterion) {
     c.bench_function("line parse ical_everything.ics", |b| {
         b.iter(|| {
@@ -16,7 +23,18 @@ fn benchmark(c: &mut Criterion) {
             for _ in reader {}
         })
     });
+    c.bench_function("borrowed line parse ical_everything.ics", |b| {
+        b.iter(|| {
+            let input = include_str!("../tests/resources/ical_everything.ics");
+            let reader = ical::BorrowedLineReader::new(input);
+            // Consume reader
+            for _ in reader {}
+        })
+    });
     c.bench_function("ics parse ical_everything.ics", |b| b.iter(parse_ical));
+    c.bench_function("borrowed ics parse ical_everything.ics", |b| {
+        b.iter(parse_ical_borrowed)
+    });
     let cal = parse_ical();
     c.bench_function("ics serialise ical_everything.ics", |b| {
         b.iter(|| cal.generate())
@@ -26,6 +44,7 @@ fn benchmark(c: &mut Criterion) {
         b.iter(|| rkyv::to_bytes::<rkyv::rancor::Error>(&cal).unwrap())
     });
 
+    #[cfg(feature = "rkyv")]
     let rkyv_bytes = include_bytes!("ical_everything.rkyv");
     #[cfg(feature = "rkyv")]
     c.bench_function("rkyv deserialise ical_everything.ics", |b| {
@@ -3,10 +3,11 @@ use crate::property::Property;
 use crate::{
     ical_property,
     parser::{
-        ComponentMut, ParserError,
+        ComponentMut, ParserError, VerifyOptions,
         ical::component::{IcalAlarm, IcalJournal, IcalTodo},
     },
 };
+use chrono::{DateTime, Duration, Utc};
 
 pub struct IcalCalendarBuilder {
     cal: IcalCalendar<false>,
@@ -70,11 +71,73 @@ impl Finalizer {
         self.0.cal.verify()
     }
 
+    /// creates a complete IcalCalendar-object verified with `options`.
+    pub fn build_with(self, options: &VerifyOptions) -> Result<IcalCalendar, ParserError> {
+        self.0.cal.verify_with(options)
+    }
+
     pub fn set(mut self, property: Property) -> Self {
         self.0.cal.properties.push(property);
         self
     }
 
+    /// Sets the [NAME](https://www.rfc-editor.org/rfc/rfc7986#section-5.1) of the calendar.
+    pub fn name<S: Into<String>>(mut self, name: S) -> Self {
+        self.0.cal.set_name(name);
+        self
+    }
+
+    /// Sets the [DESCRIPTION](https://www.rfc-editor.org/rfc/rfc7986#section-5.2) of the
+    /// calendar.
+    pub fn description<S: Into<String>>(mut self, description: S) -> Self {
+        self.0.cal.set_description(description);
+        self
+    }
+
+    /// Sets the [UID](https://www.rfc-editor.org/rfc/rfc7986#section-5.3) of the calendar.
+    pub fn uid<S: Into<String>>(mut self, uid: S) -> Self {
+        self.0.cal.set_uid(uid);
+        self
+    }
+
+    /// Sets the [URL](https://www.rfc-editor.org/rfc/rfc7986#section-5.5) of the calendar.
+    pub fn url<S: Into<String>>(mut self, url: S) -> Self {
+        self.0.cal.set_url(url);
+        self
+    }
+
+    /// Sets the [LAST-MODIFIED](https://www.rfc-editor.org/rfc/rfc7986#section-5.4) time of
+    /// the calendar.
+    pub fn last_modified(mut self, last_modified: DateTime<Utc>) -> Self {
+        self.0.cal.set_last_modified(last_modified);
+        self
+    }
+
+    /// Sets the [REFRESH-INTERVAL](https://www.rfc-editor.org/rfc/rfc7986#section-5.7) of the
+    /// calendar.
+    pub fn refresh_interval(mut self, interval: Duration) -> Self {
+        self.0.cal.set_refresh_interval(interval);
+        self
+    }
+
+    /// Sets the [SOURCE](https://www.rfc-editor.org/rfc/rfc7986#section-5.8) of the calendar.
+    pub fn source<S: Into<String>>(mut self, source: S) -> Self {
+        self.0.cal.set_source(source);
+        self
+    }
+
+    /// Sets the [COLOR](https://www.rfc-editor.org/rfc/rfc7986#section-5.9) of the calendar.
+    pub fn color<S: Into<String>>(mut self, color: S) -> Self {
+        self.0.cal.set_color(color);
+        self
+    }
+
+    /// Adds an [IMAGE](https://www.rfc-editor.org/rfc/rfc7986#section-5.10) referenced by URI.
+    pub fn image<S: Into<String>>(mut self, uri: S) -> Self {
+        self.0.cal.add_image(uri);
+        self
+    }
+
     pub fn add_event(self, ev: IcalEvent) -> Self {
         self.add_events([ev])
     }
@@ -129,6 +192,7 @@ mod tests {
         generator::Emitter,
         property::Property,
     };
+    use chrono::{Duration, TimeZone, Utc};
 
     #[test]
     fn test_calendar_builder() {
@@ -173,4 +237,42 @@ mod tests {
             .unwrap();
         insta::assert_snapshot!(cal.generate());
     }
+
+    #[test]
+    fn test_calendar_metadata() {
+        let cal = IcalCalendarBuilder::version("2.0")
+            .gregorian()
+            .prodid("github.com/lennart-k/ical-rs")
+            .name("Team")
+            .description("Meetings")
+            .uid("team")
+            .url("https://example.com/team")
+            .last_modified(Utc.with_ymd_and_hms(2025, 1, 2, 3, 4, 5).unwrap())
+            .refresh_interval(Duration::days(1))
+            .source("https://example.com/team.ics")
+            .color("turquoise")
+            .image("https://example.com/team.png")
+            .build()
+            .unwrap();
+        assert_eq!(
+            cal.generate(),
+            "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+CALSCALE:GREGORIAN\r
+PRODID:github.com/lennart-k/ical-rs\r
+NAME:Team\r
+DESCRIPTION:Meetings\r
+UID:team\r
+URL:https://example.com/team\r
+LAST-MODIFIED:20250102T030405Z\r
+REFRESH-INTERVAL;VALUE=DURATION:P1D\r
+SOURCE;VALUE=URI:https://example.com/team.ics\r
+COLOR:turquoise\r
+IMAGE;VALUE=URI:https://example.com/team.png\r
+END:VCALENDAR\r
+"
+        );
+        assert_eq!(cal.get_name(), Some("Team"));
+        assert_eq!(cal.get_refresh_interval(), Some(Duration::days(1)));
+    }
 }
@@ -1,5 +1,5 @@
 use crate::parser::ical::component::IcalEvent;
-use crate::parser::{ComponentMut, ParserError};
+use crate::parser::{ComponentMut, ParserError, VerifyOptions};
 use crate::property::Property;
 use crate::{ical_param, ical_property};
 
@@ -154,6 +154,11 @@ impl Finalizer {
         self.0.event.verify()
     }
 
+    /// Build the event verified with `options`.
+    pub fn build_with(self, options: &VerifyOptions) -> Result<IcalEvent, ParserError> {
+        self.0.event.verify_with(options)
+    }
+
     /// Setting arbitrary property.
     ///
     /// You can use the [ical_property!-macro](/ical/macro.ical_property.html).
@@ -171,6 +176,39 @@ impl Finalizer {
             .push(ical_property!("RRULE", value.into()));
         Finalizer(self.0)
     }
+
+    /// Color to display the event with, as a CSS3 color name.
+    pub fn color<S: Into<String>>(mut self, color: S) -> Self {
+        self.0
+            .event
+            .properties
+            .push(ical_property!("COLOR", color.into()));
+        self
+    }
+
+    /// Image associated with the event, referenced by URI.
+    pub fn image<S: Into<String>>(mut self, uri: S) -> Self {
+        self.0.event.properties.push(ical_property!(
+            "IMAGE",
+            uri.into(),
+            ical_param!("VALUE", "URI")
+        ));
+        self
+    }
+
+    /// Conference to join the event with, like a video call, with its features such as
+    /// `AUDIO` or `VIDEO`.
+    pub fn conference<S: Into<String>>(mut self, uri: S, features: &[&str]) -> Self {
+        let mut prop = ical_property!("CONFERENCE", uri.into(), ical_param!("VALUE", "URI"));
+        if !features.is_empty() {
+            prop.params.push((
+                "FEATURE".to_owned(),
+                features.iter().map(|feature| feature.to_string()).collect(),
+            ));
+        }
+        self.0.event.properties.push(prop);
+        self
+    }
 }
 
 #[allow(unused)]
@@ -259,4 +297,31 @@ mod should {
             .unwrap();
         assert_eq!(expect, event.generate());
     }
+
+    #[test]
+    fn build_conference_event() {
+        use generator::Emitter;
+        let expect = "BEGIN:VEVENT\r\n\
+       UID:standup\r\n\
+       DTSTAMP:20250101T000000Z\r\n\
+       DTSTART;TZID=Europe/Berlin:20250106T090000\r\n\
+       DTEND;TZID=Europe/Berlin:20250106T091500\r\n\
+       COLOR:red\r\n\
+       IMAGE;VALUE=URI:https://example.com/standup.png\r\n\
+       CONFERENCE;VALUE=URI;FEATURE=AUDIO,VIDEO:https://example.com/standup\r\n\
+       END:VEVENT\r\n\
+      ";
+        let event = IcalEventBuilder::tzid("Europe/Berlin")
+            .uid("standup")
+            .changed_utc("20250101T000000Z")
+            .start("20250106T090000")
+            .end("20250106T091500")
+            .color("red")
+            .image("https://example.com/standup.png")
+            .conference("https://example.com/standup", &["AUDIO", "VIDEO"])
+            .build()
+            .unwrap();
+        assert_eq!(expect, event.generate());
+        assert_eq!(event.get_conferences()[0].features, ["AUDIO", "VIDEO"]);
+    }
 }
@@ -0,0 +1,215 @@
+//! Detect and transcode non UTF-8 input.
+//!
+//! Calendar and contact exports are not always UTF-8. Some start with a byte order mark, some
+//! declare their charset with the vCard `CHARSET` parameter and some just use Latin-1 or
+//! Windows-1252 without saying so.
+//!
+//! Charset detection is opt-in through `LineReader::with_charset_detection`. The `LineReader`
+//! then transcodes every line to UTF-8 before it is parsed and records a `Transcoding` whenever
+//! the input wasn't plain UTF-8. Only the first [`MAX_TRANSCODINGS`] are kept, later ones are
+//! just counted.
+//!
+//! #### Warning
+//!   The `CHARSET` parameter is left in place, so re-emitting a transcoded property still
+//!   declares the original charset.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use ical::charset::{Charset, CharsetDetection, Transcoding};
+//!
+//! let input = b"BEGIN:VCARD\r\nFN:Ren\xe9\r\nEND:VCARD\r\n";
+//! let reader = ical::LineReader::new(input.as_slice())
+//!     .with_charset_detection(CharsetDetection::with_fallback(Charset::Windows1252));
+//! let mut parser = ical::VcardParser::from_line_reader(reader);
+//!
+//! let card = parser.next().unwrap().unwrap();
+//! assert_eq!(card.properties[0].value.as_deref(), Some("René"));
+//! assert_eq!(
+//!     parser.line_reader().transcodings(),
+//!     &[Transcoding::Fallback(2, Charset::Windows1252)]
+//! );
+//! ```
+
+use std::fmt;
+
+/// A character set the input can be transcoded from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Charset {
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+    /// ISO-8859-1
+    Latin1,
+    Windows1252,
+}
+
+/// The characters of Windows-1252 that differ from ISO-8859-1, starting at 0x80.
+///
+/// Unassigned bytes are mapped to the C1 control character of the same value.
+const WINDOWS_1252_C1: [char; 32] = [
+    '\u{20AC}', '\u{0081}', '\u{201A}', '\u{0192}', '\u{201E}', '\u{2026}', '\u{2020}', '\u{2021}',
+    '\u{02C6}', '\u{2030}', '\u{0160}', '\u{2039}', '\u{0152}', '\u{008D}', '\u{017D}', '\u{008F}',
+    '\u{0090}', '\u{2018}', '\u{2019}', '\u{201C}', '\u{201D}', '\u{2022}', '\u{2013}', '\u{2014}',
+    '\u{02DC}', '\u{2122}', '\u{0161}', '\u{203A}', '\u{0153}', '\u{009D}', '\u{017E}', '\u{0178}',
+];
+
+impl Charset {
+    /// Look up a charset by its (case-insensitive) IANA name or alias.
+    pub fn from_label(label: &str) -> Option<Charset> {
+        match label.trim().to_ascii_uppercase().as_str() {
+            "UTF-8" | "UTF8" | "US-ASCII" | "ASCII" => Some(Charset::Utf8),
+            "UTF-16LE" => Some(Charset::Utf16Le),
+            "UTF-16BE" | "UTF-16" => Some(Charset::Utf16Be),
+            "ISO-8859-1" | "ISO8859-1" | "ISO_8859-1" | "LATIN1" | "LATIN-1" | "L1" | "CP819" => {
+                Some(Charset::Latin1)
+            }
+            "WINDOWS-1252" | "CP1252" | "X-CP1252" => Some(Charset::Windows1252),
+            _ => None,
+        }
+    }
+
+    /// Detect a byte order mark at the start of `bytes`.
+    ///
+    /// Returns the charset and the length of the byte order mark.
+    pub fn from_bom(bytes: &[u8]) -> Option<(Charset, usize)> {
+        match bytes {
+            [0xEF, 0xBB, 0xBF, ..] => Some((Charset::Utf8, 3)),
+            [0xFF, 0xFE, ..] => Some((Charset::Utf16Le, 2)),
+            [0xFE, 0xFF, ..] => Some((Charset::Utf16Be, 2)),
+            _ => None,
+        }
+    }
+
+    /// Whether every character is encoded in a single byte.
+    pub fn is_single_byte(&self) -> bool {
+        matches!(self, Charset::Latin1 | Charset::Windows1252)
+    }
+
+    /// Decode `bytes` to a `String`, replacing invalid sequences with U+FFFD.
+    pub fn decode(&self, bytes: &[u8]) -> String {
+        match self {
+            Charset::Utf8 => String::from_utf8_lossy(bytes).into_owned(),
+            Charset::Utf16Le => decode_utf16(bytes, u16::from_le_bytes),
+            Charset::Utf16Be => decode_utf16(bytes, u16::from_be_bytes),
+            Charset::Latin1 => bytes.iter().map(|&b| char::from(b)).collect(),
+            Charset::Windows1252 => bytes
+                .iter()
+                .map(|&b| match b {
+                    0x80..=0x9F => WINDOWS_1252_C1[usize::from(b - 0x80)],
+                    _ => char::from(b),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl fmt::Display for Charset {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Charset::Utf8 => "UTF-8",
+            Charset::Utf16Le => "UTF-16LE",
+            Charset::Utf16Be => "UTF-16BE",
+            Charset::Latin1 => "ISO-8859-1",
+            Charset::Windows1252 => "WINDOWS-1252",
+        })
+    }
+}
+
+fn decode_utf16(bytes: &[u8], from_bytes: fn([u8; 2]) -> u16) -> String {
+    let units = bytes
+        .chunks_exact(2)
+        .map(|pair| from_bytes([pair[0], pair[1]]));
+    char::decode_utf16(units)
+        .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+        .collect()
+}
+
+/// Options for the charset detection of a `LineReader`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CharsetDetection {
+    /// Charset to decode lines with that are neither valid UTF-8 nor declare a charset.
+    ///
+    /// Without a fallback such lines yield `LineError::InvalidUtf8`.
+    pub fallback: Option<Charset>,
+}
+
+impl CharsetDetection {
+    pub fn with_fallback(fallback: Charset) -> Self {
+        Self {
+            fallback: Some(fallback),
+        }
+    }
+}
+
+/// The number of `Transcoding`s a `LineReader` keeps.
+pub const MAX_TRANSCODINGS: usize = 64;
+
+/// A record of a `LineReader` decoding input other than plain UTF-8.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Transcoding {
+    /// The input started with a byte order mark, which was removed.
+    Bom(Charset),
+    /// The line with the given number was decoded with the charset from its `CHARSET` parameter.
+    Declared(usize, Charset),
+    /// The line with the given number was not valid UTF-8 and decoded with the fallback charset.
+    Fallback(usize, Charset),
+}
+
+impl fmt::Display for Transcoding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Transcoding::Bom(charset) => write!(f, "Removed {charset} byte order mark"),
+            Transcoding::Declared(line, charset) => {
+                write!(f, "Line {line}: Decoded as declared {charset}")
+            }
+            Transcoding::Fallback(line, charset) => {
+                write!(f, "Line {line}: Invalid UTF-8, decoded as {charset}")
+            }
+        }
+    }
+}
+
+/// Find the charset declared by the `CHARSET` parameter of a raw content line.
+pub(crate) fn declared_charset(line: &[u8]) -> Option<Charset> {
+    let end = line.iter().position(|&b| b == b':')?;
+    line[..end]
+        .split(|&b| b == b';')
+        .skip(1)
+        .find_map(|param| {
+            let (key, value) = param.split_at(param.iter().position(|&b| b == b'=')?);
+            key.eq_ignore_ascii_case(b"CHARSET")
+                .then(|| std::str::from_utf8(&value[1..]).ok())
+                .flatten()
+        })
+        .and_then(Charset::from_label)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Charset, declared_charset};
+
+    #[test]
+    fn decode() {
+        assert_eq!(Charset::Latin1.decode(b"Ren\xe9 \x80"), "René \u{80}");
+        assert_eq!(Charset::Windows1252.decode(b"Ren\xe9 \x80"), "René €");
+        assert_eq!(Charset::Utf16Le.decode(b"R\0\xe9\0"), "Ré");
+        assert_eq!(Charset::Utf16Be.decode(b"\0R\0\xe9"), "Ré");
+        assert_eq!(Charset::Utf8.decode(b"Ren\xc3\xa9"), "René");
+    }
+
+    #[test]
+    fn declared() {
+        assert_eq!(
+            declared_charset(b"N;CHARSET=ISO-8859-1:M\xfcller"),
+            Some(Charset::Latin1)
+        );
+        assert_eq!(
+            declared_charset(b"N;ENCODING=8BIT;charset=windows-1252:a"),
+            Some(Charset::Windows1252)
+        );
+        assert_eq!(declared_charset(b"N:CHARSET=ISO-8859-1"), None);
+        assert_eq!(declared_charset(b"N;CHARSET=KOI8-R:a"), None);
+        assert_eq!(declared_charset(b"N;CHARSET:a"), None);
+    }
+}
@@ -1,7 +1,7 @@
 use crate::generator::Emitter;
 use crate::parser::ical::component::{
-    IcalAlarm, IcalCalendar, IcalEvent, IcalFreeBusy, IcalJournal, IcalTimeZone,
-    IcalTimeZoneTransition, IcalTodo,
+    IcalAlarm, IcalAvailability, IcalAvailable, IcalCalendar, IcalEvent, IcalFreeBusy, IcalJournal,
+    IcalLocation, IcalParticipant, IcalResource, IcalTimeZone, IcalTimeZoneTransition, IcalTodo,
 };
 
 impl Emitter for IcalTimeZoneTransition {
@@ -42,9 +42,36 @@ generate_emitter!(VcardContact, "VCARD", properties);
 
 generate_emitter!(IcalAlarm, "VALARM", properties);
 generate_emitter!(IcalFreeBusy, "VFREEBUSY", properties);
+generate_emitter!(IcalAvailable, "AVAILABLE", properties);
+generate_emitter!(IcalAvailability, "VAVAILABILITY", properties, available);
 generate_emitter!(IcalJournal, "VJOURNAL", properties);
-generate_emitter!(IcalEvent, "VEVENT", properties, alarms);
-generate_emitter!(IcalTodo, "VTODO", properties, alarms);
+generate_emitter!(IcalLocation, "VLOCATION", properties);
+generate_emitter!(IcalResource, "VRESOURCE", properties);
+generate_emitter!(
+    IcalParticipant,
+    "PARTICIPANT",
+    properties,
+    locations,
+    resources
+);
+generate_emitter!(
+    IcalEvent,
+    "VEVENT",
+    properties,
+    alarms,
+    locations,
+    resources,
+    participants
+);
+generate_emitter!(
+    IcalTodo,
+    "VTODO",
+    properties,
+    alarms,
+    locations,
+    resources,
+    participants
+);
 generate_emitter!(IcalTimeZone<true>, "VTIMEZONE", properties, transitions);
 generate_emitter!(
     IcalCalendar,
@@ -55,5 +82,6 @@ generate_emitter!(
     alarms,
     todos,
     journals,
-    free_busys
+    free_busys,
+    availabilities
 );
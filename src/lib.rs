@@ -39,11 +39,16 @@ const PARAM_QUOTE: char = '"';
 
 pub mod parser;
 pub use crate::parser::ical::*;
-pub use crate::parser::vcard::VcardParser;
+pub use crate::parser::vcard::{BorrowedVcardParser, VcardParser};
 pub mod property;
-pub use crate::property::PropertyParser;
+pub use crate::property::{BorrowedPropertyParser, PropertyParser};
+pub mod charset;
 pub mod line;
-pub use crate::line::LineReader;
+pub use crate::line::{BorrowedLineReader, LineReader};
+#[cfg(feature = "tokio")]
+pub use crate::{
+    line::AsyncLineReader, parser::vcard::AsyncVcardParser, property::AsyncPropertyParser,
+};
 pub mod builder;
 pub mod generator;
 
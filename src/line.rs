@@ -36,13 +36,17 @@
 //! let reader = ical::LineReader::new(buf);
 //!
 //! for line in reader {
-//!     println!("{}", line);
+//!     println!("{}", line.unwrap());
 //! }
 //! ```
 
+use std::borrow::Cow;
 use std::fmt;
-use std::io::{BufRead, Lines};
+
+use crate::charset::{Charset, CharsetDetection, MAX_TRANSCODINGS, Transcoding, declared_charset};
+use std::io::{self, BufRead, Read};
 use std::iter::{Iterator, Peekable};
+use std::str;
 
 /// An unfolded raw line.
 ///
@@ -77,54 +81,678 @@ impl fmt::Display for Line {
     }
 }
 
+#[derive(Debug, Clone, thiserror::Error, PartialEq, Eq)]
+pub enum LineError {
+    #[error("Line {0}: I/O error: {2}")]
+    Io(usize, io::ErrorKind, String),
+    #[error("Line {0}: Invalid UTF-8 at byte offset {1}.")]
+    InvalidUtf8(usize, usize),
+    #[error("Line {0}: Exceeds the maximum line length of {1} bytes.")]
+    LineTooLong(usize, usize),
+    #[error("Line {0}: The input exceeds the maximum size of {1} bytes.")]
+    InputTooLarge(usize, usize),
+}
+
 /// Take a `BufRead` and return the unfolded `Line`.
+///
+/// An I/O error or exceeding the maximum input size ends the iteration after it has been
+/// returned, all other errors only affect the line they occurred in.
 pub struct LineReader<B: BufRead> {
-    lines: Peekable<Lines<B>>,
-    number: usize,
+    reader: B,
+    peeked: Option<Result<RawLine, LineError>>,
+    state: DecoderState,
 }
 
 impl<B: BufRead> LineReader<B> {
     /// Return a new `LineReader` from a `Reader`.
     pub fn new(reader: B) -> LineReader<B> {
         LineReader {
-            lines: reader.lines().peekable(),
-            number: 0,
+            reader,
+            peeked: None,
+            state: DecoderState::default(),
+        }
+    }
+
+    /// Reject unfolded lines longer than `max` bytes instead of reading them into memory.
+    pub fn with_max_line_length(mut self, max: usize) -> LineReader<B> {
+        self.state.max_line_length = Some(max);
+        self
+    }
+
+    /// Stop reading with an error once the input exceeds `max` bytes.
+    pub fn with_max_bytes(mut self, max: usize) -> LineReader<B> {
+        self.state.max_bytes = Some(max);
+        self
+    }
+
+    /// Detect a byte order mark and the charsets declared by `CHARSET` parameters and transcode
+    /// the input to UTF-8.
+    ///
+    /// See the [`charset`](crate::charset) module.
+    pub fn with_charset_detection(mut self, detection: CharsetDetection) -> LineReader<B> {
+        self.state.detection = Some(detection);
+        self
+    }
+
+    /// Everything that had to be transcoded so far, up to [`MAX_TRANSCODINGS`].
+    pub fn transcodings(&self) -> &[Transcoding] {
+        &self.state.transcodings
+    }
+
+    /// The number of transcodings so far, including the ones that weren't kept.
+    pub fn transcoding_count(&self) -> usize {
+        self.state.transcoding_count
+    }
+
+    /// Read the next physical line.
+    fn next_physical(&mut self) -> Option<Result<RawLine, LineError>> {
+        if let Some(line) = self.peeked.take() {
+            return Some(line);
+        }
+        if self.state.failed {
+            return None;
+        }
+
+        let mut buf = Vec::new();
+        let result = self.read_physical(&mut buf);
+        self.state.physical_line(buf, result).transpose()
+    }
+
+    /// Read the bytes of the next physical line into `buf`.
+    ///
+    /// Returns the number of bytes skipped if the line was truncated because it exceeds the
+    /// maximum line length.
+    fn read_physical(&mut self, buf: &mut Vec<u8>) -> io::Result<Option<usize>> {
+        if !self.state.bom_checked {
+            self.state.bom_checked = true;
+            if self.state.detection.is_some()
+                && let Some((charset, len)) = Charset::from_bom(self.reader.fill_buf()?)
+            {
+                self.reader.consume(len);
+                self.state.bom(charset, len);
+            }
+        }
+
+        let limit = self.state.physical_limit();
+        let Some(big_endian) = self.state.utf16_big_endian() else {
+            (&mut self.reader).take(limit).read_until(b'\n', buf)?;
+            if is_truncated(buf, limit) {
+                let limit = self.state.skip_limit(buf.len());
+                return skip_line(&mut self.reader, limit).map(Some);
+            }
+            return Ok(None);
+        };
+
+        while (buf.len() as u64) < limit {
+            let remaining = limit - buf.len() as u64;
+            let read = (&mut self.reader).take(remaining).read_until(b'\n', buf)?;
+            if read == 0 || is_utf16_line_end(buf, big_endian) {
+                return Ok(None);
+            }
+            if !big_endian && buf.len() % 2 == 1 {
+                // The line break might continue with its high byte
+                (&mut self.reader).take(1).read_to_end(buf)?;
+                if is_utf16_line_end(buf, big_endian) {
+                    return Ok(None);
+                }
+            }
+        }
+        let limit = self.state.skip_limit(buf.len());
+        skip_line(&mut self.reader, limit).map(Some)
+    }
+
+    /// Return the next physical line if it continues the current line.
+    fn next_continuation(&mut self) -> Option<RawLine> {
+        match self.next_physical()? {
+            Ok(line) if is_raw_continuation(&line.bytes) => Some(line),
+            other => {
+                self.peeked = Some(other);
+                None
+            }
         }
     }
 }
 
 impl<B: BufRead> Iterator for LineReader<B> {
-    type Item = Line;
+    type Item = Result<Line, LineError>;
+
+    fn next(&mut self) -> Option<Result<Line, LineError>> {
+        let first = loop {
+            match self.next_physical()? {
+                Ok(line) if line.bytes.is_empty() => continue,
+                Ok(line) => break line,
+                Err(err @ LineError::LineTooLong(..)) => {
+                    // Drop the continuations of the skipped line
+                    while self.next_continuation().is_some() {}
+                    return Some(Err(err));
+                }
+                Err(err) => return Some(Err(err)),
+            }
+        };
+
+        let mut continuations = vec![];
+        while let Some(next) = self.next_continuation() {
+            continuations.push(next);
+            if let Err(err) = self.state.check_unfolded_length(&first, &continuations) {
+                // Drop the rest of the line
+                while self.next_continuation().is_some() {}
+                return Some(Err(err));
+            }
+        }
+
+        self.state.unfold(first, continuations).transpose()
+    }
+}
+
+/// A physical line without its line break.
+struct RawLine {
+    bytes: Vec<u8>,
+    number: usize,
+    /// Byte offset of the line in the input.
+    offset: usize,
+}
+
+/// The state of decoding physical lines that is shared by `LineReader` and `AsyncLineReader`.
+#[derive(Default)]
+struct DecoderState {
+    number: usize,
+    offset: usize,
+    max_line_length: Option<usize>,
+    max_bytes: Option<usize>,
+    detection: Option<CharsetDetection>,
+    bom_checked: bool,
+    /// Charset of the whole input if it isn't UTF-8.
+    encoding: Option<Charset>,
+    transcodings: Vec<Transcoding>,
+    transcoding_count: usize,
+    last_transcoding: Option<Transcoding>,
+    failed: bool,
+}
+
+impl DecoderState {
+    /// The number of bytes to read at most for a physical line including its line break.
+    fn physical_limit(&self) -> u64 {
+        let limit = self
+            .max_line_length
+            .map_or(u64::MAX, |max| max.saturating_add(2) as u64);
+        let limit = match self.utf16_big_endian() {
+            Some(_) => limit.saturating_mul(2),
+            None => limit,
+        };
+        // Read one byte past the remaining size to detect oversized input
+        self.max_bytes.map_or(limit, |max| {
+            limit.min(max.saturating_sub(self.offset) as u64 + 1)
+        })
+    }
+
+    /// The number of bytes to skip at most after reading `read` bytes of a physical line.
+    fn skip_limit(&self, read: usize) -> usize {
+        // Skip one byte past the remaining size to detect oversized input
+        self.max_bytes.map_or(usize::MAX, |max| {
+            max.saturating_sub(self.offset + read).saturating_add(1)
+        })
+    }
+
+    fn utf16_big_endian(&self) -> Option<bool> {
+        match self.encoding {
+            Some(Charset::Utf16Le) => Some(false),
+            Some(Charset::Utf16Be) => Some(true),
+            _ => None,
+        }
+    }
+
+    /// Record a byte order mark of `len` bytes that has been skipped.
+    fn bom(&mut self, charset: Charset, len: usize) {
+        self.offset += len;
+        if charset != Charset::Utf8 {
+            self.encoding = Some(charset);
+        }
+        self.record(Transcoding::Bom(charset));
+    }
+
+    /// Record a transcoding unless it continues the last one.
+    fn record(&mut self, transcoding: Transcoding) {
+        if self.last_transcoding.as_ref() == Some(&transcoding) {
+            return;
+        }
+        self.transcoding_count += 1;
+        if self.transcodings.len() < MAX_TRANSCODINGS {
+            self.transcodings.push(transcoding.clone());
+        }
+        self.last_transcoding = Some(transcoding);
+    }
+
+    /// Turn the bytes read for a physical line into a `RawLine`.
+    fn physical_line(
+        &mut self,
+        buf: Vec<u8>,
+        result: io::Result<Option<usize>>,
+    ) -> Result<Option<RawLine>, LineError> {
+        if buf.is_empty() && matches!(result, Ok(None)) {
+            return Ok(None);
+        }
+
+        self.number += 1;
+        let skipped = match result {
+            Ok(skipped) => skipped,
+            Err(err) => {
+                self.failed = true;
+                return Err(LineError::Io(self.number, err.kind(), err.to_string()));
+            }
+        };
+        let truncated = skipped.is_some();
+        let offset = self.offset;
+        self.offset += buf.len() + skipped.unwrap_or_default();
+        if let Some(max) = self.max_bytes
+            && self.offset > max
+        {
+            self.failed = true;
+            return Err(LineError::InputTooLarge(self.number, max));
+        }
+
+        let mut bytes = match self.encoding {
+            Some(charset) => charset.decode(&buf).into_bytes(),
+            None => buf,
+        };
+        if bytes.ends_with(b"\n") {
+            bytes.pop();
+            if bytes.ends_with(b"\r") {
+                bytes.pop();
+            }
+        }
+        if let Some(max) = self.max_line_length
+            && (truncated || bytes.len() > max)
+        {
+            return Err(LineError::LineTooLong(self.number, max));
+        }
+
+        Ok(Some(RawLine {
+            bytes,
+            number: self.number,
+            offset,
+        }))
+    }
+
+    fn check_unfolded_length(
+        &self,
+        first: &RawLine,
+        continuations: &[RawLine],
+    ) -> Result<(), LineError> {
+        let Some(max) = self.max_line_length else {
+            return Ok(());
+        };
+        let length = continuations
+            .iter()
+            .map(|line| line.bytes.len().saturating_sub(1))
+            .sum::<usize>()
+            + first.bytes.len();
+        if length > max {
+            return Err(LineError::LineTooLong(first.number, max));
+        }
+        Ok(())
+    }
+
+    /// Decode and unfold a line from its first physical line and the continuation lines.
+    fn unfold(
+        &mut self,
+        first: RawLine,
+        continuations: Vec<RawLine>,
+    ) -> Result<Option<Line>, LineError> {
+        let number = first.number;
+        let declared = match (&self.detection, self.encoding) {
+            (Some(_), None) => declared_charset(&first.bytes).filter(Charset::is_single_byte),
+            _ => None,
+        };
+
+        let mut new_line = self.decode(first, declared, number)?.trim_end().to_string();
+        for next in continuations {
+            let next = self.decode(next, declared, number)?;
+            if !next.is_empty() {
+                // String cannot be empty so this cannot panic
+                new_line.push_str(next.split_at(1).1);
+            }
+        }
+
+        Ok((!new_line.is_empty()).then(|| Line::new(new_line, number)))
+    }
+
+    /// Decode a physical line belonging to the unfolded line `number`.
+    fn decode(
+        &mut self,
+        line: RawLine,
+        declared: Option<Charset>,
+        number: usize,
+    ) -> Result<String, LineError> {
+        let (decoded, transcoding) = if let Some(charset) = declared {
+            (
+                charset.decode(&line.bytes),
+                Transcoding::Declared(number, charset),
+            )
+        } else {
+            let err = match String::from_utf8(line.bytes) {
+                Ok(decoded) => return Ok(decoded),
+                Err(err) => err,
+            };
+            let Some(charset) = self.detection.as_ref().and_then(|d| d.fallback) else {
+                return Err(LineError::InvalidUtf8(
+                    line.number,
+                    line.offset + err.utf8_error().valid_up_to(),
+                ));
+            };
+            (
+                charset.decode(err.as_bytes()),
+                Transcoding::Fallback(number, charset),
+            )
+        };
+
+        self.record(transcoding);
+        Ok(decoded)
+    }
+}
+
+/// Whether reading a physical line stopped at the limit before reaching the line break.
+fn is_truncated(buf: &[u8], limit: u64) -> bool {
+    buf.len() as u64 == limit && !buf.ends_with(b"\n")
+}
+
+/// Whether `buf` ends with a UTF-16 encoded line break.
+fn is_utf16_line_end(buf: &[u8], big_endian: bool) -> bool {
+    buf.len().is_multiple_of(2)
+        && if big_endian {
+            buf.ends_with(b"\0\n")
+        } else {
+            buf.ends_with(b"\n\0")
+        }
+}
+
+/// Consume the rest of a physical line, but at most `limit` bytes, without keeping it in memory.
+///
+/// Returns the number of bytes consumed.
+fn skip_line<B: BufRead>(reader: &mut B, limit: usize) -> io::Result<usize> {
+    let mut skipped = 0;
+    while skipped < limit {
+        let buf = reader.fill_buf()?;
+        let buf = &buf[..buf.len().min(limit - skipped)];
+        if buf.is_empty() {
+            break;
+        }
+        if let Some(pos) = buf.iter().position(|&b| b == b'\n') {
+            reader.consume(pos + 1);
+            return Ok(skipped + pos + 1);
+        }
+        let len = buf.len();
+        reader.consume(len);
+        skipped += len;
+    }
+    Ok(skipped)
+}
+
+/// Whether a raw physical line continues the previous logical line.
+fn is_raw_continuation(line: &[u8]) -> bool {
+    line.starts_with(b" ") || line.starts_with(b"\t") || line.is_empty()
+}
+
+/// Whether a physical line continues the previous logical line.
+fn is_continuation(line: &str) -> bool {
+    line.starts_with(' ') || line.starts_with('\t') || line.is_empty()
+}
+
+/// An unfolded raw line borrowing from the input.
+///
+/// The line only owns its content if it had to be unfolded.
+#[derive(Debug, Clone, Default)]
+pub struct BorrowedLine<'a> {
+    inner: Cow<'a, str>,
+    number: usize,
+}
+
+impl<'a> BorrowedLine<'a> {
+    pub fn new(line: Cow<'a, str>, line_number: usize) -> BorrowedLine<'a> {
+        BorrowedLine {
+            inner: line,
+            number: line_number,
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.inner
+    }
+
+    pub fn number(&self) -> usize {
+        self.number
+    }
+
+    /// Return the inner content, which borrows from the input if no unfolding was needed.
+    pub fn into_inner(self) -> Cow<'a, str> {
+        self.inner
+    }
+
+    pub fn is_borrowed(&self) -> bool {
+        matches!(self.inner, Cow::Borrowed(_))
+    }
+
+    pub fn into_owned(self) -> Line {
+        Line::new(self.inner.into_owned(), self.number)
+    }
+}
+
+impl fmt::Display for BorrowedLine<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Line {}: {}", self.number, self.inner)
+    }
+}
+
+/// Take a `&str` and return the unfolded `BorrowedLine`.
+///
+/// This behaves like `LineReader` but only allocates for folded lines.
+pub struct BorrowedLineReader<'a> {
+    lines: Peekable<str::Lines<'a>>,
+    number: usize,
+}
+
+impl<'a> BorrowedLineReader<'a> {
+    /// Return a new `BorrowedLineReader` from a `&str`.
+    pub fn new(input: &'a str) -> BorrowedLineReader<'a> {
+        BorrowedLineReader {
+            lines: input.lines().peekable(),
+            number: 0,
+        }
+    }
+}
+
+impl<'a> Iterator for BorrowedLineReader<'a> {
+    type Item = BorrowedLine<'a>;
 
-    fn next(&mut self) -> Option<Line> {
+    fn next(&mut self) -> Option<BorrowedLine<'a>> {
         let (mut new_line, line_number) = loop {
-            let line = self.lines.next()?.ok()?;
+            let line = self.lines.next()?;
             self.number += 1;
             if !line.is_empty() {
-                break (line.trim_end().to_string(), self.number);
+                break (Cow::Borrowed(line.trim_end()), self.number);
             }
         };
 
-        loop {
-            let Some(Ok(next)) = self.lines.next_if(|line| {
-                line.as_ref()
-                    .ok()
-                    .map(|line| line.starts_with(' ') || line.starts_with('\t') || line.is_empty())
-                    .unwrap_or_default()
-            }) else {
-                break;
-            };
+        while let Some(next) = self.lines.next_if(|line| is_continuation(line)) {
             self.number += 1;
             if !next.is_empty() {
                 // String cannot be empty so this cannot panic
-                new_line.push_str(next.split_at(1).1);
+                new_line.to_mut().push_str(next.split_at(1).1);
             }
         }
 
         if new_line.is_empty() {
             None
         } else {
-            Some(Line::new(new_line, line_number))
+            Some(BorrowedLine::new(new_line, line_number))
+        }
+    }
+}
+
+/// Take an `AsyncBufRead` and return the unfolded `Line`.
+///
+/// This is the asynchronous counterpart to `LineReader`.
+#[cfg(feature = "tokio")]
+pub struct AsyncLineReader<B: tokio::io::AsyncBufRead + Unpin> {
+    reader: B,
+    peeked: Option<Result<RawLine, LineError>>,
+    state: DecoderState,
+}
+
+#[cfg(feature = "tokio")]
+impl<B: tokio::io::AsyncBufRead + Unpin> AsyncLineReader<B> {
+    /// Return a new `AsyncLineReader` from a `Reader`.
+    pub fn new(reader: B) -> AsyncLineReader<B> {
+        AsyncLineReader {
+            reader,
+            peeked: None,
+            state: DecoderState::default(),
+        }
+    }
+
+    /// Reject unfolded lines longer than `max` bytes instead of reading them into memory.
+    pub fn with_max_line_length(mut self, max: usize) -> AsyncLineReader<B> {
+        self.state.max_line_length = Some(max);
+        self
+    }
+
+    /// Stop reading with an error once the input exceeds `max` bytes.
+    pub fn with_max_bytes(mut self, max: usize) -> AsyncLineReader<B> {
+        self.state.max_bytes = Some(max);
+        self
+    }
+
+    /// Detect a byte order mark and the charsets declared by `CHARSET` parameters and transcode
+    /// the input to UTF-8.
+    ///
+    /// See the [`charset`](crate::charset) module.
+    pub fn with_charset_detection(mut self, detection: CharsetDetection) -> AsyncLineReader<B> {
+        self.state.detection = Some(detection);
+        self
+    }
+
+    /// Everything that had to be transcoded so far, up to [`MAX_TRANSCODINGS`].
+    pub fn transcodings(&self) -> &[Transcoding] {
+        &self.state.transcodings
+    }
+
+    /// The number of transcodings so far, including the ones that weren't kept.
+    pub fn transcoding_count(&self) -> usize {
+        self.state.transcoding_count
+    }
+
+    /// Read the next physical line.
+    async fn next_physical(&mut self) -> Option<Result<RawLine, LineError>> {
+        if let Some(line) = self.peeked.take() {
+            return Some(line);
+        }
+        if self.state.failed {
+            return None;
+        }
+
+        let mut buf = Vec::new();
+        let result = self.read_physical(&mut buf).await;
+        self.state.physical_line(buf, result).transpose()
+    }
+
+    /// Read the bytes of the next physical line into `buf`.
+    ///
+    /// Returns the number of bytes skipped if the line was truncated because it exceeds the
+    /// maximum line length.
+    async fn read_physical(&mut self, buf: &mut Vec<u8>) -> io::Result<Option<usize>> {
+        use tokio::io::{AsyncBufReadExt, AsyncReadExt};
+
+        if !self.state.bom_checked {
+            self.state.bom_checked = true;
+            if self.state.detection.is_some()
+                && let Some((charset, len)) = Charset::from_bom(self.reader.fill_buf().await?)
+            {
+                self.reader.consume(len);
+                self.state.bom(charset, len);
+            }
+        }
+
+        let limit = self.state.physical_limit();
+        let big_endian = self.state.utf16_big_endian();
+        while (buf.len() as u64) < limit {
+            let remaining = limit - buf.len() as u64;
+            let read = (&mut self.reader)
+                .take(remaining)
+                .read_until(b'\n', buf)
+                .await?;
+            let Some(big_endian) = big_endian else {
+                if read == 0 || buf.ends_with(b"\n") {
+                    return Ok(None);
+                }
+                continue;
+            };
+            if read == 0 || is_utf16_line_end(buf, big_endian) {
+                return Ok(None);
+            }
+            if !big_endian && buf.len() % 2 == 1 {
+                // The line break might continue with its high byte
+                (&mut self.reader).take(1).read_to_end(buf).await?;
+                if is_utf16_line_end(buf, big_endian) {
+                    return Ok(None);
+                }
+            }
+        }
+
+        // Consume the rest of the physical line without keeping it in memory
+        let limit = self.state.skip_limit(buf.len());
+        let mut skipped = 0;
+        while skipped < limit {
+            let rest = self.reader.fill_buf().await?;
+            let rest = &rest[..rest.len().min(limit - skipped)];
+            if rest.is_empty() {
+                break;
+            }
+            if let Some(pos) = rest.iter().position(|&b| b == b'\n') {
+                self.reader.consume(pos + 1);
+                return Ok(Some(skipped + pos + 1));
+            }
+            let len = rest.len();
+            self.reader.consume(len);
+            skipped += len;
         }
+        Ok(Some(skipped))
+    }
+
+    /// Return the next physical line if it continues the current line.
+    async fn next_continuation(&mut self) -> Option<RawLine> {
+        match self.next_physical().await? {
+            Ok(line) if is_raw_continuation(&line.bytes) => Some(line),
+            other => {
+                self.peeked = Some(other);
+                None
+            }
+        }
+    }
+
+    /// Read the next unfolded `Line`.
+    pub async fn next(&mut self) -> Option<Result<Line, LineError>> {
+        let first = loop {
+            match self.next_physical().await? {
+                Ok(line) if line.bytes.is_empty() => continue,
+                Ok(line) => break line,
+                Err(err @ LineError::LineTooLong(..)) => {
+                    // Drop the continuations of the skipped line
+                    while self.next_continuation().await.is_some() {}
+                    return Some(Err(err));
+                }
+                Err(err) => return Some(Err(err)),
+            }
+        };
+
+        let mut continuations = vec![];
+        while let Some(next) = self.next_continuation().await {
+            continuations.push(next);
+            if let Err(err) = self.state.check_unfolded_length(&first, &continuations) {
+                // Drop the rest of the line
+                while self.next_continuation().await.is_some() {}
+                return Some(Err(err));
+            }
+        }
+
+        self.state.unfold(first, continuations).transpose()
     }
 }
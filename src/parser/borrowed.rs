@@ -0,0 +1,117 @@
+use std::borrow::Cow;
+use std::marker::PhantomData;
+
+use crate::{
+    line::BorrowedLineReader,
+    parser::{Component, ParserError},
+    property::{BorrowedProperty, BorrowedPropertyParser},
+};
+
+/// A generic Ical/Vcard component borrowing from the parsed input.
+///
+/// In contrast to the owned components no verification is done, the component only holds the
+/// raw properties and sub-components.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct BorrowedComponent<'a> {
+    pub name: Cow<'a, str>,
+    pub properties: Vec<BorrowedProperty<'a>>,
+    pub components: Vec<BorrowedComponent<'a>>,
+}
+
+impl<'a> BorrowedComponent<'a> {
+    pub fn new(name: Cow<'a, str>) -> Self {
+        Self {
+            name,
+            properties: Vec::new(),
+            components: Vec::new(),
+        }
+    }
+
+    pub fn get_property<'c>(&'c self, name: &str) -> Option<&'c BorrowedProperty<'a>> {
+        self.properties.iter().find(|p| p.name == name)
+    }
+
+    pub fn get_named_properties<'c>(&'c self, name: &str) -> Vec<&'c BorrowedProperty<'a>> {
+        self.properties.iter().filter(|p| p.name == name).collect()
+    }
+
+    pub fn get_named_components<'c>(&'c self, name: &str) -> Vec<&'c BorrowedComponent<'a>> {
+        self.components.iter().filter(|c| c.name == name).collect()
+    }
+
+    /// Parse the content from `line_parser` and fill the component with.
+    fn parse(&mut self, line_parser: &mut BorrowedPropertyParser<'a>) -> Result<(), ParserError> {
+        loop {
+            let line = line_parser.next().ok_or(ParserError::NotComplete)??;
+
+            match line.name.to_uppercase().as_str() {
+                "END" => break,
+                "BEGIN" => match line.value {
+                    Some(v) => {
+                        let mut component = BorrowedComponent::new(v);
+                        component.parse(line_parser)?;
+                        self.components.push(component);
+                    }
+                    None => return Err(ParserError::NotComplete),
+                },
+
+                _ => self.properties.push(line),
+            };
+        }
+        Ok(())
+    }
+}
+
+/// Reader returning `BorrowedComponent` objects from a `&str`.
+///
+/// The component type `T` is only used to check the component names.
+pub struct BorrowedComponentParser<'a, T: Component> {
+    line_parser: BorrowedPropertyParser<'a>,
+    _t: PhantomData<T>,
+}
+
+impl<'a, T: Component> BorrowedComponentParser<'a, T> {
+    /// Return a new `BorrowedComponentParser` from a `&str`.
+    pub fn new(input: &'a str) -> BorrowedComponentParser<'a, T> {
+        let line_reader = BorrowedLineReader::new(input);
+        let line_parser = BorrowedPropertyParser::new(line_reader);
+
+        BorrowedComponentParser {
+            line_parser,
+            _t: Default::default(),
+        }
+    }
+
+    /// Read the next line and check if it's a valid component start.
+    fn check_header(&mut self) -> Result<Option<Cow<'a, str>>, ParserError> {
+        let line = match self.line_parser.next() {
+            Some(val) => val.map_err(ParserError::PropertyError)?,
+            None => return Ok(None),
+        };
+
+        match line.value {
+            Some(name)
+                if line.name.to_uppercase() == "BEGIN"
+                    && T::NAMES.contains(&name.to_uppercase().as_str())
+                    && line.params.is_empty() =>
+            {
+                Ok(Some(name))
+            }
+            _ => Err(ParserError::MissingHeader),
+        }
+    }
+}
+
+impl<'a, T: Component> Iterator for BorrowedComponentParser<'a, T> {
+    type Item = Result<BorrowedComponent<'a>, ParserError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let name = match self.check_header() {
+            Ok(res) => res?,
+            Err(err) => return Some(Err(err)),
+        };
+
+        let mut comp = BorrowedComponent::new(name);
+        Some(comp.parse(&mut self.line_parser).map(|_| comp))
+    }
+}
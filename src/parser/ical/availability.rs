@@ -0,0 +1,203 @@
+//! Evaluate VAVAILABILITY components as defined in
+//! [RFC7953](https://tools.ietf.org/html/rfc7953).
+//!
+//! A VAVAILABILITY blocks its time range with its BUSYTYPE, except for the instances of its
+//! AVAILABLE blocks. Components with a higher PRIORITY override the ones with a lower PRIORITY,
+//! where 1 is the highest and 0 or a missing PRIORITY the lowest.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use chrono::{TimeZone, Utc};
+//! use ical::parser::ical::availability::{Availability, AvailabilityEvaluator};
+//! use ical::parser::ical::component::BusyType;
+//! use ical::types::OlsonResolver;
+//!
+//! let input = "BEGIN:VCALENDAR\r
+//! BEGIN:VAVAILABILITY\r
+//! UID:office\r
+//! DTSTAMP:20250101T000000Z\r
+//! DTSTART:20250101T000000Z\r
+//! BEGIN:AVAILABLE\r
+//! UID:office-hours\r
+//! DTSTAMP:20250101T000000Z\r
+//! DTSTART:20250106T090000Z\r
+//! DTEND:20250106T170000Z\r
+//! RRULE:FREQ=WEEKLY;BYDAY=MO,TU,WE,TH,FR\r
+//! END:AVAILABLE\r
+//! END:VAVAILABILITY\r
+//! END:VCALENDAR\r
+//! ";
+//! let calendar = ical::IcalParser::new(input.as_bytes()).next().unwrap().unwrap();
+//! let evaluator = AvailabilityEvaluator::from_calendar(&calendar, &OlsonResolver);
+//!
+//! let tuesday = Utc.with_ymd_and_hms(2025, 1, 7, 10, 0, 0).unwrap();
+//! assert!(evaluator.is_available(tuesday).unwrap());
+//!
+//! let saturday = Utc.with_ymd_and_hms(2025, 1, 11, 10, 0, 0).unwrap();
+//! assert_eq!(
+//!     evaluator.at(saturday).unwrap(),
+//!     Availability::Busy(BusyType::BusyUnavailable)
+//! );
+//! ```
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, Utc};
+use chrono_tz::Tz;
+use itertools::Itertools;
+
+use crate::{
+    parser::{
+        ParserError,
+        ical::{
+            component::{BusyType, IcalAvailability, IcalCalendar, IcalTimeZone},
+            recurrence::{expand_overlapping, resolve_timezones},
+        },
+    },
+    types::{CalDateOrDateTime, TimezoneResolver},
+};
+
+/// The availability at a point in time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Availability {
+    /// Within an AVAILABLE block.
+    Available,
+    /// Within the time range of a VAVAILABILITY, but outside of its AVAILABLE blocks.
+    Busy(BusyType),
+    /// Outside of the time range of all VAVAILABILITY components.
+    Unknown,
+}
+
+/// Answer whether someone is available according to their VAVAILABILITY components.
+#[derive(Debug, Clone)]
+pub struct AvailabilityEvaluator<'a> {
+    availabilities: Vec<&'a IcalAvailability>,
+    timezones: HashMap<String, Option<Tz>>,
+}
+
+impl<'a> AvailabilityEvaluator<'a> {
+    /// Evaluate `availabilities`, looking up their TZIDs with `resolver` and in `timezones`.
+    pub fn new(
+        availabilities: impl IntoIterator<Item = &'a IcalAvailability>,
+        timezones: &[IcalTimeZone],
+        resolver: &impl TimezoneResolver,
+    ) -> Self {
+        let availabilities: Vec<_> = availabilities
+            .into_iter()
+            .sorted_by_key(|availability| rank(availability))
+            .collect();
+        let timezones = resolve_timezones(
+            availabilities.iter().flat_map(|availability| {
+                availability.properties.iter().chain(
+                    availability
+                        .available
+                        .iter()
+                        .flat_map(|available| &available.properties),
+                )
+            }),
+            timezones,
+            resolver,
+        );
+        Self {
+            availabilities,
+            timezones,
+        }
+    }
+
+    pub fn from_calendar(calendar: &'a IcalCalendar, resolver: &impl TimezoneResolver) -> Self {
+        Self::new(&calendar.availabilities, &calendar.timezones, resolver)
+    }
+
+    /// The availability at `time`.
+    ///
+    /// Only the components with the highest PRIORITY covering `time` are taken into account. If
+    /// several of them do, `time` is available if it lies within an AVAILABLE block of any of
+    /// them, and busy with the BUSYTYPE of the first one otherwise.
+    pub fn at(&self, time: DateTime<Utc>) -> Result<Availability, ParserError> {
+        let mut covering: Vec<&IcalAvailability> = Vec::new();
+        for availability in &self.availabilities {
+            if covering
+                .first()
+                .is_some_and(|first| rank(first) < rank(availability))
+            {
+                break;
+            }
+            if self.covers(availability, time)? {
+                covering.push(*availability);
+            }
+        }
+
+        let Some(first) = covering.first() else {
+            return Ok(Availability::Unknown);
+        };
+        for availability in &covering {
+            if self.is_within_available(availability, time)? {
+                return Ok(Availability::Available);
+            }
+        }
+        Ok(Availability::Busy(first.get_busytype()))
+    }
+
+    /// Whether `time` lies within an AVAILABLE block that isn't overridden.
+    pub fn is_available(&self, time: DateTime<Utc>) -> Result<bool, ParserError> {
+        Ok(self.at(time)? == Availability::Available)
+    }
+
+    /// Whether `time` lies within the time range of `availability`.
+    fn covers(
+        &self,
+        availability: &IcalAvailability,
+        time: DateTime<Utc>,
+    ) -> Result<bool, ParserError> {
+        let start = availability
+            .get_dtstart()
+            .map(|prop| CalDateOrDateTime::parse_prop(prop, &self.timezones))
+            .transpose()?;
+        let end = match (availability.get_dtend(), &start) {
+            (Some(prop), _) => Some(CalDateOrDateTime::parse_prop(prop, &self.timezones)?.utc()),
+            (None, Some(start)) => availability
+                .get_duration()
+                .map(|duration| {
+                    start
+                        .utc()
+                        .checked_add_signed(duration)
+                        .ok_or(ParserError::InvalidValue("DURATION"))
+                })
+                .transpose()?,
+            (None, None) => None,
+        };
+        Ok(start.is_none_or(|start| start.utc() <= time) && end.is_none_or(|end| time < end))
+    }
+
+    fn is_within_available(
+        &self,
+        availability: &IcalAvailability,
+        time: DateTime<Utc>,
+    ) -> Result<bool, ParserError> {
+        for (_uid, available) in availability
+            .available
+            .iter()
+            .into_group_map_by(|available| available.get_uid())
+        {
+            let instances = expand_overlapping(
+                available,
+                &self.timezones,
+                time,
+                time + Duration::seconds(1),
+            )?;
+            if !instances.is_empty() {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+}
+
+/// The order of the PRIORITY of a VAVAILABILITY, from the highest to the lowest.
+fn rank(availability: &IcalAvailability) -> u8 {
+    match availability.get_priority() {
+        None | Some(0) => 10,
+        Some(priority) => priority,
+    }
+}
@@ -1,11 +1,19 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, Utc};
 use itertools::Itertools;
 
 use crate::{
-    PropertyParser,
-    parser::{Component, ComponentMut, ParserError},
+    parser::{
+        Component, ComponentMut, ParserError, PropertySource, VerifyOptions,
+        ical::{
+            component::{IcalEvent, IcalTodo},
+            recurrence::{Recurring, bounds, expand},
+        },
+    },
     property::Property,
+    types::{CalDateOrDateTime, CalDateTime, parse_duration},
 };
-use std::io::BufRead;
 
 #[derive(Debug, Clone, Default)]
 #[cfg_attr(
@@ -24,6 +32,103 @@ impl IcalAlarm<false> {
     }
 }
 
+/// The ACTION of an alarm.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AlarmAction {
+    Audio,
+    Display,
+    Email,
+    /// Deprecated by RFC 5545 but still defined by RFC 9074.
+    Procedure,
+    /// An alarm that does nothing, see RFC 9074.
+    None,
+    Other(String),
+}
+
+impl From<&str> for AlarmAction {
+    fn from(value: &str) -> Self {
+        match value.to_uppercase().as_str() {
+            "AUDIO" => Self::Audio,
+            "DISPLAY" => Self::Display,
+            "EMAIL" => Self::Email,
+            "PROCEDURE" => Self::Procedure,
+            "NONE" => Self::None,
+            _ => Self::Other(value.to_owned()),
+        }
+    }
+}
+
+/// Whether a relative TRIGGER refers to the start or the end of its parent component.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TriggerRelation {
+    #[default]
+    Start,
+    End,
+}
+
+/// The TRIGGER of an alarm.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AlarmTrigger {
+    /// A duration relative to the start or end of the parent component.
+    Relative(chrono::Duration, TriggerRelation),
+    Absolute(chrono::DateTime<chrono::Utc>),
+}
+
+impl TryFrom<&Property> for AlarmTrigger {
+    type Error = ParserError;
+
+    fn try_from(prop: &Property) -> Result<Self, Self::Error> {
+        let value = prop
+            .value
+            .as_deref()
+            .ok_or(ParserError::MissingProperty("TRIGGER"))?;
+
+        if prop
+            .get_value_type()
+            .is_some_and(|value_type| value_type.eq_ignore_ascii_case("DATE-TIME"))
+        {
+            return Ok(Self::Absolute(CalDateTime::parse(value, None)?.utc()));
+        }
+
+        let relation = match prop.get_param("RELATED") {
+            Some(related) if related.eq_ignore_ascii_case("END") => TriggerRelation::End,
+            Some(related) if !related.eq_ignore_ascii_case("START") => {
+                return Err(ParserError::InvalidValue("TRIGGER"));
+            }
+            _ => TriggerRelation::Start,
+        };
+        Ok(Self::Relative(parse_duration(value)?, relation))
+    }
+}
+
+impl IcalAlarm<true> {
+    pub fn get_action(&self) -> Option<AlarmAction> {
+        self.get_property("ACTION")
+            .and_then(|prop| prop.value.as_deref())
+            .map(AlarmAction::from)
+    }
+
+    /// The TRIGGER of the alarm, unless it's missing or malformed.
+    pub fn get_trigger(&self) -> Option<AlarmTrigger> {
+        self.get_property("TRIGGER")
+            .and_then(|prop| AlarmTrigger::try_from(prop).ok())
+    }
+
+    /// The number of additional repetitions of the alarm.
+    pub fn get_repeat(&self) -> Option<u32> {
+        self.get_property("REPEAT")
+            .and_then(|prop| prop.value.as_deref())
+            .and_then(|value| value.parse().ok())
+    }
+
+    /// The delay between repetitions of the alarm.
+    pub fn get_duration(&self) -> Option<chrono::Duration> {
+        self.get_property("DURATION")
+            .and_then(|prop| Option::<chrono::Duration>::try_from(prop).ok())
+            .flatten()
+    }
+}
+
 impl<const VERIFIED: bool> Component for IcalAlarm<VERIFIED> {
     const NAMES: &[&str] = &["VALARM"];
     type Unverified = IcalAlarm<false>;
@@ -47,18 +152,88 @@ impl ComponentMut for IcalAlarm<false> {
     }
 
     #[cfg(not(tarpaulin_include))]
-    fn add_sub_component<B: BufRead>(
+    fn add_sub_component<P: PropertySource>(
         &mut self,
         _: &str,
-        _: &mut PropertyParser<B>,
+        _: &mut P,
+        _: &VerifyOptions,
     ) -> Result<(), ParserError> {
         Err(ParserError::InvalidComponent)
     }
 
-    fn verify(self) -> Result<IcalAlarm<true>, ParserError> {
-        Ok(IcalAlarm {
+    fn verify_with(self, options: &VerifyOptions) -> Result<IcalAlarm<true>, ParserError> {
+        if options.require_alarm_properties {
+            self.verify_action()?;
+        }
+
+        let verified = IcalAlarm {
             properties: self.properties,
-        })
+        };
+
+        #[cfg(feature = "test")]
+        {
+            // Verify that the conditions for our getters are actually met
+            verified.get_action();
+            verified.get_trigger();
+            verified.get_repeat();
+            verified.get_duration();
+        }
+
+        Ok(verified)
+    }
+}
+
+impl IcalAlarm<false> {
+    /// Check the TRIGGER, the repetitions and the properties required by the ACTION.
+    fn verify_action(&self) -> Result<(), ParserError> {
+        let action: AlarmAction = self
+            .get_property("ACTION")
+            .and_then(|prop| prop.value.as_deref())
+            .ok_or(ParserError::MissingProperty("ACTION"))?
+            .into();
+
+        AlarmTrigger::try_from(
+            self.get_property("TRIGGER")
+                .ok_or(ParserError::MissingProperty("TRIGGER"))?,
+        )?;
+
+        if let Some(prop) = self.get_property("DURATION") {
+            Option::<chrono::Duration>::try_from(prop)?;
+        }
+        if let Some(prop) = self.get_property("REPEAT")
+            && prop
+                .value
+                .as_deref()
+                .is_none_or(|value| value.parse::<u32>().is_err())
+        {
+            return Err(ParserError::InvalidValue("REPEAT"));
+        }
+        match (self.get_property("DURATION"), self.get_property("REPEAT")) {
+            (Some(_), None) => return Err(ParserError::MissingProperty("REPEAT")),
+            (None, Some(_)) => return Err(ParserError::MissingProperty("DURATION")),
+            _ => {}
+        }
+
+        let (required, attach): (&[&'static str], _) = match action {
+            AlarmAction::Audio => (&[], 0..=1),
+            AlarmAction::Display => (&["DESCRIPTION"], 0..=usize::MAX),
+            AlarmAction::Email => (&["DESCRIPTION", "SUMMARY", "ATTENDEE"], 0..=usize::MAX),
+            AlarmAction::Procedure => (&[], 1..=1),
+            AlarmAction::None | AlarmAction::Other(_) => (&[], 0..=usize::MAX),
+        };
+        if let Some(name) = required
+            .iter()
+            .find(|name| self.get_property(name).is_none())
+        {
+            return Err(ParserError::MissingProperty(name));
+        }
+        match self.get_named_properties("ATTACH").len() {
+            count if attach.contains(&count) => Ok(()),
+            0 => Err(ParserError::MissingProperty("ATTACH")),
+            _ => Err(ParserError::PropertyConflict(
+                "ATTACH must not occur more than once",
+            )),
+        }
     }
 }
 
@@ -71,3 +246,201 @@ impl<const VERIFIED: bool> IcalAlarm<VERIFIED> {
             .collect()
     }
 }
+
+/// The component an alarm belongs to.
+#[derive(Debug, Clone, Copy)]
+pub enum AlarmSource<'c> {
+    Calendar,
+    Event(&'c IcalEvent),
+    Todo(&'c IcalTodo),
+}
+
+/// A point in time an alarm fires at.
+#[derive(Debug, Clone)]
+pub struct AlarmOccurrence<'c> {
+    pub time: DateTime<Utc>,
+    pub alarm: &'c IcalAlarm,
+    pub source: AlarmSource<'c>,
+    /// The RECURRENCE-ID of the instance the alarm belongs to.
+    pub recurrence_id: Option<CalDateOrDateTime>,
+    /// 0 for the TRIGGER itself, counting up to REPEAT for its repetitions.
+    pub repetition: u32,
+}
+
+impl IcalAlarm<true> {
+    /// How far the trigger and its repetitions reach away from the parent component, saturating
+    /// at [`Duration::MAX`].
+    fn reach(&self) -> Duration {
+        match self.get_trigger() {
+            Some(AlarmTrigger::Relative(offset, _)) => {
+                let (repeat, interval) = self.repetitions();
+                i32::try_from(repeat)
+                    .ok()
+                    .and_then(|repeat| interval.checked_mul(repeat))
+                    .and_then(|repetitions| offset.abs().checked_add(&repetitions))
+                    .unwrap_or(Duration::MAX)
+            }
+            _ => Duration::zero(),
+        }
+    }
+
+    /// The REPEAT and DURATION of the alarm, which only repeats if both are valid.
+    fn repetitions(&self) -> (u32, Duration) {
+        match (self.get_repeat(), self.get_duration()) {
+            (Some(repeat), Some(interval)) if interval > Duration::zero() => (repeat, interval),
+            _ => (0, Duration::zero()),
+        }
+    }
+
+    /// Add the trigger at `time` and its repetitions that lie within `[after, before)`.
+    pub(crate) fn push_occurrences<'c>(
+        &'c self,
+        time: DateTime<Utc>,
+        source: AlarmSource<'c>,
+        recurrence_id: Option<&CalDateOrDateTime>,
+        (after, before): (DateTime<Utc>, DateTime<Utc>),
+        occurrences: &mut Vec<AlarmOccurrence<'c>>,
+    ) {
+        let (repeat, interval) = self.repetitions();
+        // The repetitions `first..last` are the ones with `after <= time + interval * n < before`
+        let interval = nanoseconds(interval).max(1).unsigned_abs();
+        let repetitions_until = |end: DateTime<Utc>| {
+            nanoseconds(end - time)
+                .max(0)
+                .unsigned_abs()
+                .div_ceil(interval)
+                .min(u128::from(repeat) + 1)
+        };
+        let (first, last) = (repetitions_until(after), repetitions_until(before));
+        for repetition in first..last {
+            let offset = interval * repetition;
+            let offset = Duration::seconds((offset / 1_000_000_000) as i64)
+                + Duration::nanoseconds((offset % 1_000_000_000) as i64);
+            let Some(time) = time.checked_add_signed(offset) else {
+                break;
+            };
+            occurrences.push(AlarmOccurrence {
+                time,
+                alarm: self,
+                source,
+                recurrence_id: recurrence_id.cloned(),
+                repetition: repetition as u32,
+            });
+        }
+    }
+}
+
+/// The length of `duration` in nanoseconds, which unlike [`Duration::num_nanoseconds`] can't
+/// overflow.
+fn nanoseconds(duration: Duration) -> i128 {
+    i128::from(duration.num_seconds()) * 1_000_000_000 + i128::from(duration.subsec_nanos())
+}
+
+/// A component that can contain alarms.
+pub(crate) trait AlarmParent: Recurring {
+    fn get_alarms(&self) -> &[IcalAlarm];
+    fn alarm_source(&self) -> AlarmSource<'_>;
+}
+
+impl AlarmParent for IcalEvent {
+    fn get_alarms(&self) -> &[IcalAlarm] {
+        &self.alarms
+    }
+
+    fn alarm_source(&self) -> AlarmSource<'_> {
+        AlarmSource::Event(self)
+    }
+}
+
+impl AlarmParent for IcalTodo {
+    fn get_alarms(&self) -> &[IcalAlarm] {
+        &self.alarms
+    }
+
+    fn alarm_source(&self) -> AlarmSource<'_> {
+        AlarmSource::Todo(self)
+    }
+}
+
+/// Add the alarms of a master component and its overrides firing within `[after, before)`.
+pub(crate) fn schedule_alarms<'c, C: AlarmParent>(
+    components: &[&'c C],
+    timezones: &HashMap<String, Option<chrono_tz::Tz>>,
+    (after, before): (DateTime<Utc>, DateTime<Utc>),
+    occurrences: &mut Vec<AlarmOccurrence<'c>>,
+) -> Result<(), ParserError> {
+    let Some(reach) = components
+        .iter()
+        .flat_map(|component| component.get_alarms())
+        .map(IcalAlarm::reach)
+        .max()
+    else {
+        return Ok(());
+    };
+    let mut length = Duration::zero();
+    for component in components {
+        if let (Some(start), Some(end)) = bounds(*component, timezones)? {
+            length = length.max((end - &start).abs());
+        }
+    }
+
+    // Absolute triggers fire once, no matter how often their parent recurs
+    for component in components {
+        let recurrence_id = component
+            .get_property("RECURRENCE-ID")
+            .map(|prop| CalDateOrDateTime::parse_prop(prop, timezones))
+            .transpose()?;
+        for alarm in component.get_alarms() {
+            if let Some(AlarmTrigger::Absolute(time)) = alarm.get_trigger() {
+                alarm.push_occurrences(
+                    time,
+                    component.alarm_source(),
+                    recurrence_id.as_ref(),
+                    (after, before),
+                    occurrences,
+                );
+            }
+        }
+    }
+
+    let instances = expand(
+        components.iter().copied(),
+        timezones,
+        after
+            .checked_sub_signed(reach)
+            .and_then(|after| after.checked_sub_signed(length))
+            .unwrap_or(DateTime::<Utc>::MIN_UTC),
+        before
+            .checked_add_signed(reach)
+            .unwrap_or(DateTime::<Utc>::MAX_UTC),
+    )?;
+    for instance in &instances {
+        for alarm in instance.component.get_alarms() {
+            // Alarms without a valid trigger never fire
+            let Some(AlarmTrigger::Relative(offset, relation)) = alarm.get_trigger() else {
+                continue;
+            };
+            let reference = match relation {
+                TriggerRelation::Start => instance.start.as_ref(),
+                TriggerRelation::End => instance.end.as_ref(),
+            };
+            // An alarm relative to a bound its parent doesn't have can't fire
+            let Some(reference) = reference else {
+                continue;
+            };
+            // A trigger beyond the range of representable times never fires
+            let Some(time) = reference.utc().checked_add_signed(offset) else {
+                continue;
+            };
+            alarm.push_occurrences(
+                time,
+                instance.component.alarm_source(),
+                instance.recurrence_id.as_ref(),
+                (after, before),
+                occurrences,
+            );
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,496 @@
+//! Typed ATTENDEE and ORGANIZER properties as defined in
+//! [RFC5545](https://tools.ietf.org/html/rfc5545) and
+//! [RFC6638](https://tools.ietf.org/html/rfc6638).
+
+use crate::{
+    parser::{Component, ComponentMut, ParserError},
+    property::Property,
+};
+
+/// The CUTYPE of an attendee.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum CalendarUserType {
+    #[default]
+    Individual,
+    Group,
+    Resource,
+    Room,
+    Unknown,
+    Other(String),
+}
+
+impl CalendarUserType {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Individual => "INDIVIDUAL",
+            Self::Group => "GROUP",
+            Self::Resource => "RESOURCE",
+            Self::Room => "ROOM",
+            Self::Unknown => "UNKNOWN",
+            Self::Other(value) => value,
+        }
+    }
+}
+
+impl From<&str> for CalendarUserType {
+    fn from(value: &str) -> Self {
+        match value.to_uppercase().as_str() {
+            "INDIVIDUAL" => Self::Individual,
+            "GROUP" => Self::Group,
+            "RESOURCE" => Self::Resource,
+            "ROOM" => Self::Room,
+            "UNKNOWN" => Self::Unknown,
+            _ => Self::Other(value.to_owned()),
+        }
+    }
+}
+
+/// The ROLE of an attendee.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum ParticipationRole {
+    Chair,
+    #[default]
+    ReqParticipant,
+    OptParticipant,
+    NonParticipant,
+    Other(String),
+}
+
+impl ParticipationRole {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Chair => "CHAIR",
+            Self::ReqParticipant => "REQ-PARTICIPANT",
+            Self::OptParticipant => "OPT-PARTICIPANT",
+            Self::NonParticipant => "NON-PARTICIPANT",
+            Self::Other(value) => value,
+        }
+    }
+}
+
+impl From<&str> for ParticipationRole {
+    fn from(value: &str) -> Self {
+        match value.to_uppercase().as_str() {
+            "CHAIR" => Self::Chair,
+            "REQ-PARTICIPANT" => Self::ReqParticipant,
+            "OPT-PARTICIPANT" => Self::OptParticipant,
+            "NON-PARTICIPANT" => Self::NonParticipant,
+            _ => Self::Other(value.to_owned()),
+        }
+    }
+}
+
+/// The PARTSTAT of an attendee, where COMPLETED and IN-PROCESS only apply to to-dos.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum ParticipationStatus {
+    #[default]
+    NeedsAction,
+    Accepted,
+    Declined,
+    Tentative,
+    Delegated,
+    Completed,
+    InProcess,
+    Other(String),
+}
+
+impl ParticipationStatus {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::NeedsAction => "NEEDS-ACTION",
+            Self::Accepted => "ACCEPTED",
+            Self::Declined => "DECLINED",
+            Self::Tentative => "TENTATIVE",
+            Self::Delegated => "DELEGATED",
+            Self::Completed => "COMPLETED",
+            Self::InProcess => "IN-PROCESS",
+            Self::Other(value) => value,
+        }
+    }
+}
+
+impl From<&str> for ParticipationStatus {
+    fn from(value: &str) -> Self {
+        match value.to_uppercase().as_str() {
+            "NEEDS-ACTION" => Self::NeedsAction,
+            "ACCEPTED" => Self::Accepted,
+            "DECLINED" => Self::Declined,
+            "TENTATIVE" => Self::Tentative,
+            "DELEGATED" => Self::Delegated,
+            "COMPLETED" => Self::Completed,
+            "IN-PROCESS" => Self::InProcess,
+            _ => Self::Other(value.to_owned()),
+        }
+    }
+}
+
+/// The SCHEDULE-AGENT of an attendee or organizer, which is responsible for delivering
+/// scheduling messages to them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum ScheduleAgent {
+    #[default]
+    Server,
+    Client,
+    None,
+    Other(String),
+}
+
+impl ScheduleAgent {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Server => "SERVER",
+            Self::Client => "CLIENT",
+            Self::None => "NONE",
+            Self::Other(value) => value,
+        }
+    }
+}
+
+impl From<&str> for ScheduleAgent {
+    fn from(value: &str) -> Self {
+        match value.to_uppercase().as_str() {
+            "SERVER" => Self::Server,
+            "CLIENT" => Self::Client,
+            "NONE" => Self::None,
+            _ => Self::Other(value.to_owned()),
+        }
+    }
+}
+
+/// An ATTENDEE property.
+///
+/// Parameters are `None` or empty if missing. Parameters without a field like DIR or LANGUAGE
+/// are kept in `params`, and so are repeated parameters and parameters with more values than
+/// their field holds. Converting from and to a [`Property`] keeps every parameter value, but puts
+/// the parameters with a field first and writes known values like `accepted` or `true` in upper
+/// case.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Attendee {
+    /// The calendar user address, usually a `mailto:` URI.
+    pub address: String,
+    pub cn: Option<String>,
+    pub cutype: Option<CalendarUserType>,
+    pub role: Option<ParticipationRole>,
+    pub partstat: Option<ParticipationStatus>,
+    pub rsvp: Option<bool>,
+    pub member: Vec<String>,
+    pub delegated_to: Vec<String>,
+    pub delegated_from: Vec<String>,
+    pub sent_by: Option<String>,
+    pub schedule_agent: Option<ScheduleAgent>,
+    /// The SCHEDULE-STATUS codes of the last delivery, like `1.2` or `3.7`.
+    pub schedule_status: Vec<String>,
+    pub params: Vec<(String, Vec<String>)>,
+}
+
+impl Attendee {
+    pub fn new(address: impl Into<String>) -> Self {
+        Self {
+            address: address.into(),
+            ..Self::default()
+        }
+    }
+
+    /// The address without its `mailto:` scheme, if it is one.
+    pub fn email(&self) -> Option<&str> {
+        strip_mailto(&self.address)
+    }
+
+    /// Whether this attendee has the calendar user address `address`.
+    pub fn is(&self, address: &str) -> bool {
+        same_address(&self.address, address)
+    }
+}
+
+impl TryFrom<&Property> for Attendee {
+    type Error = ParserError;
+
+    fn try_from(prop: &Property) -> Result<Self, Self::Error> {
+        parse_attendee(prop, true)
+    }
+}
+
+/// Convert an ATTENDEE property, where an RSVP that isn't TRUE or FALSE is an error if `strict`
+/// and kept in `params` otherwise.
+fn parse_attendee(prop: &Property, strict: bool) -> Result<Attendee, ParserError> {
+    let Some(address) = prop.value.clone().filter(|_| prop.name == "ATTENDEE") else {
+        return Err(ParserError::InvalidValue("ATTENDEE"));
+    };
+    let mut attendee = Attendee::new(address);
+    for (name, values) in &prop.params {
+        // Only the first occurrence of a parameter with the values its field can hold gets
+        // into the field, everything else is kept in `params`
+        let value = single_value(values);
+        let single = value.is_some();
+        let multiple = !values.is_empty();
+        match name.as_str() {
+            "CN" if single && attendee.cn.is_none() => attendee.cn = value.map(str::to_owned),
+            "CUTYPE" if single && attendee.cutype.is_none() => {
+                attendee.cutype = value.map(Into::into)
+            }
+            "ROLE" if single && attendee.role.is_none() => attendee.role = value.map(Into::into),
+            "PARTSTAT" if single && attendee.partstat.is_none() => {
+                attendee.partstat = value.map(Into::into)
+            }
+            "RSVP" if attendee.rsvp.is_none() => match value.and_then(parse_boolean) {
+                Some(rsvp) => attendee.rsvp = Some(rsvp),
+                None if strict => return Err(ParserError::InvalidValue("ATTENDEE")),
+                None => attendee.params.push((name.clone(), values.clone())),
+            },
+            "MEMBER" if multiple && attendee.member.is_empty() => {
+                attendee.member.clone_from(values)
+            }
+            "DELEGATED-TO" if multiple && attendee.delegated_to.is_empty() => {
+                attendee.delegated_to.clone_from(values)
+            }
+            "DELEGATED-FROM" if multiple && attendee.delegated_from.is_empty() => {
+                attendee.delegated_from.clone_from(values)
+            }
+            "SENT-BY" if single && attendee.sent_by.is_none() => {
+                attendee.sent_by = value.map(str::to_owned)
+            }
+            "SCHEDULE-AGENT" if single && attendee.schedule_agent.is_none() => {
+                attendee.schedule_agent = value.map(Into::into)
+            }
+            "SCHEDULE-STATUS" if multiple && attendee.schedule_status.is_empty() => {
+                attendee.schedule_status.clone_from(values)
+            }
+            _ => attendee.params.push((name.clone(), values.clone())),
+        }
+    }
+    Ok(attendee)
+}
+
+impl From<Attendee> for Property {
+    fn from(attendee: Attendee) -> Self {
+        let mut params = Vec::new();
+        push_param(&mut params, "CN", attendee.cn);
+        push_param(
+            &mut params,
+            "CUTYPE",
+            attendee.cutype.as_ref().map(CalendarUserType::as_str),
+        );
+        push_param(
+            &mut params,
+            "ROLE",
+            attendee.role.as_ref().map(ParticipationRole::as_str),
+        );
+        push_param(
+            &mut params,
+            "PARTSTAT",
+            attendee.partstat.as_ref().map(ParticipationStatus::as_str),
+        );
+        push_param(
+            &mut params,
+            "RSVP",
+            attendee
+                .rsvp
+                .map(|rsvp| if rsvp { "TRUE" } else { "FALSE" }),
+        );
+        push_params(&mut params, "MEMBER", attendee.member);
+        push_params(&mut params, "DELEGATED-TO", attendee.delegated_to);
+        push_params(&mut params, "DELEGATED-FROM", attendee.delegated_from);
+        push_param(&mut params, "SENT-BY", attendee.sent_by);
+        push_param(
+            &mut params,
+            "SCHEDULE-AGENT",
+            attendee.schedule_agent.as_ref().map(ScheduleAgent::as_str),
+        );
+        push_params(&mut params, "SCHEDULE-STATUS", attendee.schedule_status);
+        params.extend(attendee.params);
+        Property {
+            name: "ATTENDEE".to_owned(),
+            params,
+            value: Some(attendee.address),
+        }
+    }
+}
+
+/// An ORGANIZER property.
+///
+/// Parameters without a field like DIR or LANGUAGE, repeated parameters and parameters with more
+/// values than their field holds are kept in `params`, like for [`Attendee`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Organizer {
+    /// The calendar user address, usually a `mailto:` URI.
+    pub address: String,
+    pub cn: Option<String>,
+    pub sent_by: Option<String>,
+    pub schedule_agent: Option<ScheduleAgent>,
+    /// The SCHEDULE-STATUS codes of the last delivery, like `1.2` or `3.7`.
+    pub schedule_status: Vec<String>,
+    pub params: Vec<(String, Vec<String>)>,
+}
+
+impl Organizer {
+    pub fn new(address: impl Into<String>) -> Self {
+        Self {
+            address: address.into(),
+            ..Self::default()
+        }
+    }
+
+    /// The address without its `mailto:` scheme, if it is one.
+    pub fn email(&self) -> Option<&str> {
+        strip_mailto(&self.address)
+    }
+
+    /// Whether this organizer has the calendar user address `address`.
+    pub fn is(&self, address: &str) -> bool {
+        same_address(&self.address, address)
+    }
+}
+
+impl TryFrom<&Property> for Organizer {
+    type Error = ParserError;
+
+    fn try_from(prop: &Property) -> Result<Self, Self::Error> {
+        let Some(address) = prop.value.clone().filter(|_| prop.name == "ORGANIZER") else {
+            return Err(ParserError::InvalidValue("ORGANIZER"));
+        };
+        let mut organizer = Organizer::new(address);
+        for (name, values) in &prop.params {
+            let value = single_value(values);
+            let single = value.is_some();
+            match name.as_str() {
+                "CN" if single && organizer.cn.is_none() => organizer.cn = value.map(str::to_owned),
+                "SENT-BY" if single && organizer.sent_by.is_none() => {
+                    organizer.sent_by = value.map(str::to_owned)
+                }
+                "SCHEDULE-AGENT" if single && organizer.schedule_agent.is_none() => {
+                    organizer.schedule_agent = value.map(Into::into)
+                }
+                "SCHEDULE-STATUS" if !values.is_empty() && organizer.schedule_status.is_empty() => {
+                    organizer.schedule_status.clone_from(values)
+                }
+                _ => organizer.params.push((name.clone(), values.clone())),
+            }
+        }
+        Ok(organizer)
+    }
+}
+
+impl From<Organizer> for Property {
+    fn from(organizer: Organizer) -> Self {
+        let mut params = Vec::new();
+        push_param(&mut params, "CN", organizer.cn);
+        push_param(&mut params, "SENT-BY", organizer.sent_by);
+        push_param(
+            &mut params,
+            "SCHEDULE-AGENT",
+            organizer.schedule_agent.as_ref().map(ScheduleAgent::as_str),
+        );
+        push_params(&mut params, "SCHEDULE-STATUS", organizer.schedule_status);
+        params.extend(organizer.params);
+        Property {
+            name: "ORGANIZER".to_owned(),
+            params,
+            value: Some(organizer.address),
+        }
+    }
+}
+
+/// The value of a parameter with exactly one value.
+fn single_value(values: &[String]) -> Option<&str> {
+    match values {
+        [value] => Some(value),
+        _ => None,
+    }
+}
+
+fn parse_boolean(value: &str) -> Option<bool> {
+    match value.to_uppercase().as_str() {
+        "TRUE" => Some(true),
+        "FALSE" => Some(false),
+        _ => None,
+    }
+}
+
+fn push_param(
+    params: &mut Vec<(String, Vec<String>)>,
+    name: &str,
+    value: Option<impl Into<String>>,
+) {
+    if let Some(value) = value {
+        params.push((name.to_owned(), vec![value.into()]));
+    }
+}
+
+fn push_params(params: &mut Vec<(String, Vec<String>)>, name: &str, values: Vec<String>) {
+    if !values.is_empty() {
+        params.push((name.to_owned(), values));
+    }
+}
+
+fn strip_mailto(address: &str) -> Option<&str> {
+    address
+        .get(..7)
+        .filter(|scheme| scheme.eq_ignore_ascii_case("mailto:"))
+        .map(|_| &address[7..])
+}
+
+/// Whether two calendar user addresses are the same, ignoring case and a `mailto:` scheme.
+pub(crate) fn same_address(left: &str, right: &str) -> bool {
+    let normalize = |address| strip_mailto(address).unwrap_or(address).to_lowercase();
+    normalize(left) == normalize(right)
+}
+
+/// Check the ATTENDEE and ORGANIZER properties of a component.
+pub(crate) fn verify_participation_properties<C: Component>(
+    component: &C,
+) -> Result<(), ParserError> {
+    for prop in component.get_named_properties("ATTENDEE") {
+        Attendee::try_from(prop)?;
+    }
+    if let Some(prop) = component.get_property("ORGANIZER") {
+        Organizer::try_from(prop)?;
+    }
+    Ok(())
+}
+
+/// The attendees of a component, skipping ATTENDEE properties without an address.
+pub(crate) fn get_attendees<C: Component>(component: &C) -> Vec<Attendee> {
+    component
+        .get_named_properties("ATTENDEE")
+        .into_iter()
+        .filter_map(|prop| parse_attendee(prop, false).ok())
+        .collect()
+}
+
+/// The organizer of a component, or `None` if the ORGANIZER has no address.
+pub(crate) fn get_organizer<C: Component>(component: &C) -> Option<Organizer> {
+    component
+        .get_property("ORGANIZER")
+        .and_then(|prop| Organizer::try_from(prop).ok())
+}
+
+/// Replace the attendee with the same address, returning whether there was one.
+pub(crate) fn update_attendee<C: ComponentMut>(component: &mut C, attendee: Attendee) -> bool {
+    match component
+        .get_properties_mut()
+        .iter_mut()
+        .find(|prop| is_attendee(prop, &attendee.address))
+    {
+        Some(prop) => {
+            *prop = attendee.into();
+            true
+        }
+        None => false,
+    }
+}
+
+/// Remove the attendee with `address`, returning whether there was one.
+pub(crate) fn remove_attendee<C: ComponentMut>(component: &mut C, address: &str) -> bool {
+    let properties = component.get_properties_mut();
+    let len = properties.len();
+    properties.retain(|prop| !is_attendee(prop, address));
+    properties.len() != len
+}
+
+fn is_attendee(prop: &Property, address: &str) -> bool {
+    prop.name == "ATTENDEE"
+        && prop
+            .value
+            .as_deref()
+            .is_some_and(|value| same_address(value, address))
+}
@@ -0,0 +1,332 @@
+use crate::{
+    parser::{
+        Component, ComponentMut, ParserError, PropertySource, VerifyOptions,
+        ical::component::parse_bounded,
+    },
+    property::Property,
+};
+use itertools::Itertools;
+
+/// A VAVAILABILITY component as defined in [RFC7953](https://tools.ietf.org/html/rfc7953).
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
+pub struct IcalAvailability<const VERIFIED: bool = true> {
+    pub properties: Vec<Property>,
+    pub available: Vec<IcalAvailable>,
+}
+
+impl IcalAvailability<false> {
+    pub fn new() -> Self {
+        Self {
+            properties: Vec::new(),
+            available: Vec::new(),
+        }
+    }
+}
+
+/// An AVAILABLE block within a VAVAILABILITY.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
+pub struct IcalAvailable<const VERIFIED: bool = true> {
+    pub properties: Vec<Property>,
+}
+
+impl IcalAvailable<false> {
+    pub fn new() -> Self {
+        Self {
+            properties: Vec::new(),
+        }
+    }
+}
+
+/// The BUSYTYPE of a VAVAILABILITY.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum BusyType {
+    Busy,
+    #[default]
+    BusyUnavailable,
+    BusyTentative,
+    Other(String),
+}
+
+impl BusyType {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Busy => "BUSY",
+            Self::BusyUnavailable => "BUSY-UNAVAILABLE",
+            Self::BusyTentative => "BUSY-TENTATIVE",
+            Self::Other(value) => value,
+        }
+    }
+}
+
+impl From<&str> for BusyType {
+    fn from(value: &str) -> Self {
+        match value.to_uppercase().as_str() {
+            "BUSY" => Self::Busy,
+            "BUSY-UNAVAILABLE" => Self::BusyUnavailable,
+            "BUSY-TENTATIVE" => Self::BusyTentative,
+            _ => Self::Other(value.to_owned()),
+        }
+    }
+}
+
+/// Check the properties VAVAILABILITY and AVAILABLE have in common.
+fn verify_common<C: Component>(component: &C, options: &VerifyOptions) -> Result<(), ParserError> {
+    if component
+        .get_property("UID")
+        .and_then(|prop| prop.value.as_ref())
+        .is_none()
+    {
+        return Err(ParserError::MissingProperty("UID"));
+    }
+
+    if options.require_dtstamp
+        && component
+            .get_property("DTSTAMP")
+            .and_then(|prop| prop.value.as_ref())
+            .is_none()
+    {
+        return Err(ParserError::MissingProperty("DTSTAMP"));
+    }
+
+    if component.get_property("DTEND").is_some() && component.get_property("DURATION").is_some() {
+        return Err(ParserError::PropertyConflict(
+            "both DTEND and DURATION are defined",
+        ));
+    }
+
+    if let Some(prop) = component.get_property("DURATION") {
+        Option::<chrono::Duration>::try_from(prop)?;
+        if component.get_property("DTSTART").is_none() {
+            return Err(ParserError::MissingProperty("DTSTART"));
+        }
+    }
+
+    Ok(())
+}
+
+impl IcalAvailability<true> {
+    pub fn get_uid(&self) -> &str {
+        self.get_property("UID")
+            .and_then(|prop| prop.value.as_deref())
+            .expect("already verified that this must exist")
+    }
+
+    /// The start of the time range, which is unbounded if missing.
+    pub fn get_dtstart(&self) -> Option<&Property> {
+        self.get_property("DTSTART")
+    }
+
+    /// The end of the time range, which is unbounded if missing together with DURATION.
+    pub fn get_dtend(&self) -> Option<&Property> {
+        self.get_property("DTEND")
+    }
+
+    pub fn get_duration(&self) -> Option<chrono::Duration> {
+        self.get_property("DURATION")
+            .and_then(|prop| Option::<chrono::Duration>::try_from(prop).unwrap())
+    }
+
+    /// How the time range is blocked outside of AVAILABLE blocks.
+    pub fn get_busytype(&self) -> BusyType {
+        self.get_property("BUSYTYPE")
+            .and_then(|prop| prop.value.as_deref())
+            .map(BusyType::from)
+            .unwrap_or_default()
+    }
+
+    /// The PRIORITY, where 1 is the highest and 0 means undefined, unless it's out of range.
+    pub fn get_priority(&self) -> Option<u8> {
+        parse_bounded(self, "PRIORITY", 0..=9).ok().flatten()
+    }
+}
+
+impl<const VERIFIED: bool> Component for IcalAvailability<VERIFIED> {
+    const NAMES: &[&str] = &["VAVAILABILITY"];
+    type Unverified = IcalAvailability<false>;
+
+    fn get_properties(&self) -> &Vec<Property> {
+        &self.properties
+    }
+
+    fn mutable(self) -> Self::Unverified {
+        IcalAvailability {
+            properties: self.properties,
+            available: self.available,
+        }
+    }
+}
+
+impl ComponentMut for IcalAvailability<false> {
+    type Verified = IcalAvailability<true>;
+
+    fn get_properties_mut(&mut self) -> &mut Vec<Property> {
+        &mut self.properties
+    }
+
+    fn add_sub_component<P: PropertySource>(
+        &mut self,
+        value: &str,
+        line_parser: &mut P,
+        options: &VerifyOptions,
+    ) -> Result<(), ParserError> {
+        match value {
+            "AVAILABLE" => {
+                let mut available = IcalAvailable::new();
+                available.parse_with(line_parser, options)?;
+                self.available.push(available.verify_with(options)?);
+            }
+            _ => return Err(ParserError::InvalidComponent),
+        };
+
+        Ok(())
+    }
+
+    fn verify_with(self, options: &VerifyOptions) -> Result<IcalAvailability<true>, ParserError> {
+        verify_common(&self, options)?;
+        if options.check_values {
+            parse_bounded(&self, "PRIORITY", 0..=9)?;
+        }
+
+        let verified = IcalAvailability {
+            properties: self.properties,
+            available: self.available,
+        };
+
+        #[cfg(feature = "test")]
+        {
+            // Verify that the conditions for our getters are actually met
+            verified.get_uid();
+            verified.get_dtstart();
+            verified.get_dtend();
+            verified.get_duration();
+            verified.get_busytype();
+            verified.get_priority();
+        }
+
+        Ok(verified)
+    }
+}
+
+impl<const VERIFIED: bool> IcalAvailability<VERIFIED> {
+    pub fn get_tzids(&self) -> Vec<&str> {
+        self.properties
+            .iter()
+            .filter_map(|prop| prop.get_tzid())
+            .chain(
+                self.available
+                    .iter()
+                    .flat_map(|available| available.get_tzids()),
+            )
+            .unique()
+            .collect()
+    }
+}
+
+impl IcalAvailable<true> {
+    pub fn get_uid(&self) -> &str {
+        self.get_property("UID")
+            .and_then(|prop| prop.value.as_deref())
+            .expect("already verified that this must exist")
+    }
+
+    pub fn get_recurrence_id(&self) -> Option<&Property> {
+        self.get_property("RECURRENCE-ID")
+    }
+
+    pub fn get_dtstart(&self) -> &Property {
+        self.get_property("DTSTART")
+            .expect("already verified that this must exist")
+    }
+
+    pub fn get_dtend(&self) -> Option<&Property> {
+        self.get_property("DTEND")
+    }
+
+    pub fn get_duration(&self) -> Option<chrono::Duration> {
+        self.get_property("DURATION")
+            .and_then(|prop| Option::<chrono::Duration>::try_from(prop).unwrap())
+    }
+
+    pub fn get_rrule(&self) -> Option<&Property> {
+        self.get_property("RRULE")
+    }
+}
+
+impl<const VERIFIED: bool> Component for IcalAvailable<VERIFIED> {
+    const NAMES: &[&str] = &["AVAILABLE"];
+    type Unverified = IcalAvailable<false>;
+
+    fn get_properties(&self) -> &Vec<Property> {
+        &self.properties
+    }
+
+    fn mutable(self) -> Self::Unverified {
+        IcalAvailable {
+            properties: self.properties,
+        }
+    }
+}
+
+impl ComponentMut for IcalAvailable<false> {
+    type Verified = IcalAvailable<true>;
+
+    fn get_properties_mut(&mut self) -> &mut Vec<Property> {
+        &mut self.properties
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    fn add_sub_component<P: PropertySource>(
+        &mut self,
+        _: &str,
+        _: &mut P,
+        _: &VerifyOptions,
+    ) -> Result<(), ParserError> {
+        Err(ParserError::InvalidComponent)
+    }
+
+    fn verify_with(self, options: &VerifyOptions) -> Result<IcalAvailable<true>, ParserError> {
+        verify_common(&self, options)?;
+        if self
+            .get_property("DTSTART")
+            .and_then(|prop| prop.value.as_ref())
+            .is_none()
+        {
+            return Err(ParserError::MissingProperty("DTSTART"));
+        }
+
+        let verified = IcalAvailable {
+            properties: self.properties,
+        };
+
+        #[cfg(feature = "test")]
+        {
+            // Verify that the conditions for our getters are actually met
+            verified.get_uid();
+            verified.get_recurrence_id();
+            verified.get_dtstart();
+            verified.get_dtend();
+            verified.get_duration();
+            verified.get_rrule();
+        }
+
+        Ok(verified)
+    }
+}
+
+impl<const VERIFIED: bool> IcalAvailable<VERIFIED> {
+    pub fn get_tzids(&self) -> Vec<&str> {
+        self.properties
+            .iter()
+            .filter_map(|prop| prop.get_tzid())
+            .unique()
+            .collect()
+    }
+}
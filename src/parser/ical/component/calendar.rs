@@ -1,15 +1,23 @@
 use crate::{
-    PropertyParser,
+    ical_param, ical_property,
     parser::{
-        Component, ComponentMut, ParserError,
-        ical::component::{
-            IcalAlarm, IcalEvent, IcalFreeBusy, IcalJournal, IcalTimeZone, IcalTodo,
+        Component, ComponentMut, ParserError, PropertySource, VerifyOptions, check_method,
+        ical::{
+            component::{
+                AlarmOccurrence, AlarmSource, AlarmTrigger, IcalAlarm, IcalAvailability, IcalEvent,
+                IcalFreeBusy, IcalJournal, IcalTimeZone, IcalTodo, Image, get_images,
+                get_text_with_fallback, parse_last_modified, parse_refresh_interval,
+                schedule_alarms, set_with_fallback, verify_metadata_properties,
+            },
+            recurrence::resolve_timezones,
         },
     },
     property::Property,
+    types::{CalDateTime, LOCAL_DATE_TIME, TimezoneResolver, format_duration},
 };
+use chrono::{DateTime, Datelike, Duration, NaiveDateTime, Utc};
+use chrono_tz::Tz;
 use itertools::Itertools;
-use std::io::BufRead;
 
 #[derive(Debug, Clone, Default)]
 /// An ICAL calendar.
@@ -24,6 +32,7 @@ pub struct IcalCalendar<const VERIFIED: bool = true> {
     pub todos: Vec<IcalTodo>,
     pub journals: Vec<IcalJournal>,
     pub free_busys: Vec<IcalFreeBusy>,
+    pub availabilities: Vec<IcalAvailability>,
     pub timezones: Vec<IcalTimeZone>,
 }
 
@@ -36,9 +45,75 @@ impl IcalCalendar<false> {
             todos: Vec::new(),
             journals: Vec::new(),
             free_busys: Vec::new(),
+            availabilities: Vec::new(),
             timezones: Vec::new(),
         }
     }
+
+    /// Set the NAME, updating X-WR-CALNAME if present.
+    pub fn set_name<S: Into<String>>(&mut self, name: S) {
+        set_with_fallback(self, ical_property!("NAME", name), "X-WR-CALNAME");
+    }
+
+    /// Set the DESCRIPTION, updating X-WR-CALDESC if present.
+    pub fn set_description<S: Into<String>>(&mut self, description: S) {
+        set_with_fallback(
+            self,
+            ical_property!("DESCRIPTION", description),
+            "X-WR-CALDESC",
+        );
+    }
+
+    pub fn set_uid<S: Into<String>>(&mut self, uid: S) {
+        self.set_property(ical_property!("UID", uid));
+    }
+
+    pub fn set_url<S: Into<String>>(&mut self, url: S) {
+        self.set_property(ical_property!("URL", url));
+    }
+
+    pub fn set_last_modified(&mut self, last_modified: DateTime<Utc>) {
+        self.set_property(ical_property!(
+            "LAST-MODIFIED",
+            CalDateTime::from(last_modified).format()
+        ));
+    }
+
+    /// Set how often clients should refresh the calendar.
+    pub fn set_refresh_interval(&mut self, interval: Duration) {
+        self.set_property(ical_property!(
+            "REFRESH-INTERVAL",
+            format_duration(interval),
+            ical_param!("VALUE", "DURATION")
+        ));
+    }
+
+    /// Set the URI the calendar can be refreshed from.
+    pub fn set_source<S: Into<String>>(&mut self, source: S) {
+        self.set_property(ical_property!(
+            "SOURCE",
+            source.into(),
+            ical_param!("VALUE", "URI")
+        ));
+    }
+
+    /// Set the COLOR as a CSS3 color name, updating X-APPLE-CALENDAR-COLOR if present.
+    pub fn set_color<S: Into<String>>(&mut self, color: S) {
+        set_with_fallback(
+            self,
+            ical_property!("COLOR", color),
+            "X-APPLE-CALENDAR-COLOR",
+        );
+    }
+
+    /// Add an IMAGE referenced by `uri`.
+    pub fn add_image<S: Into<String>>(&mut self, uri: S) {
+        self.add_property(ical_property!(
+            "IMAGE",
+            uri.into(),
+            ical_param!("VALUE", "URI")
+        ));
+    }
 }
 
 impl<const VERIFIED: bool> Component for IcalCalendar<VERIFIED> {
@@ -57,6 +132,7 @@ impl<const VERIFIED: bool> Component for IcalCalendar<VERIFIED> {
             todos: self.todos,
             journals: self.journals,
             free_busys: self.free_busys,
+            availabilities: self.availabilities,
             timezones: self.timezones,
         }
     }
@@ -69,41 +145,47 @@ impl ComponentMut for IcalCalendar<false> {
         &mut self.properties
     }
 
-    fn add_sub_component<B: BufRead>(
+    fn add_sub_component<P: PropertySource>(
         &mut self,
         value: &str,
-        line_parser: &mut PropertyParser<B>,
+        line_parser: &mut P,
+        options: &VerifyOptions,
     ) -> Result<(), ParserError> {
         match value {
-            "VALARM" => {
+            "VALARM" if options.allow_calendar_alarms => {
                 let mut alarm = IcalAlarm::new();
-                alarm.parse(line_parser)?;
-                self.alarms.push(alarm.verify()?);
+                alarm.parse_with(line_parser, options)?;
+                self.alarms.push(alarm.verify_with(options)?);
             }
             "VEVENT" => {
                 let mut event = IcalEvent::new();
-                event.parse(line_parser)?;
-                self.events.push(event.verify()?);
+                event.parse_with(line_parser, options)?;
+                self.events.push(event.verify_with(options)?);
             }
             "VTODO" => {
                 let mut todo = IcalTodo::new();
-                todo.parse(line_parser)?;
-                self.todos.push(todo.verify()?);
+                todo.parse_with(line_parser, options)?;
+                self.todos.push(todo.verify_with(options)?);
             }
             "VJOURNAL" => {
                 let mut journal = IcalJournal::new();
-                journal.parse(line_parser)?;
-                self.journals.push(journal.verify()?);
+                journal.parse_with(line_parser, options)?;
+                self.journals.push(journal.verify_with(options)?);
             }
             "VFREEBUSY" => {
                 let mut free_busy = IcalFreeBusy::new();
-                free_busy.parse(line_parser)?;
-                self.free_busys.push(free_busy.verify()?);
+                free_busy.parse_with(line_parser, options)?;
+                self.free_busys.push(free_busy.verify_with(options)?);
+            }
+            "VAVAILABILITY" => {
+                let mut availability = IcalAvailability::new();
+                availability.parse_with(line_parser, options)?;
+                self.availabilities.push(availability.verify_with(options)?);
             }
             "VTIMEZONE" => {
                 let mut timezone = IcalTimeZone::new();
-                timezone.parse(line_parser)?;
-                self.timezones.push(timezone.verify()?);
+                timezone.parse_with(line_parser, options)?;
+                self.timezones.push(timezone.verify_with(options)?);
             }
             _ => return Err(ParserError::InvalidComponent),
         };
@@ -111,20 +193,302 @@ impl ComponentMut for IcalCalendar<false> {
         Ok(())
     }
 
-    fn verify(self) -> Result<Self::Verified, ParserError> {
-        Ok(IcalCalendar {
+    fn verify_with(self, options: &VerifyOptions) -> Result<Self::Verified, ParserError> {
+        check_method(&self, options)?;
+        if !options.allow_calendar_alarms && !self.alarms.is_empty() {
+            return Err(ParserError::InvalidComponent);
+        }
+        if options.check_values {
+            parse_last_modified(&self)?;
+            parse_refresh_interval(&self)?;
+            verify_metadata_properties(&self)?;
+        }
+
+        let verified = IcalCalendar {
             properties: self.properties,
             events: self.events,
             alarms: self.alarms,
             todos: self.todos,
             journals: self.journals,
             free_busys: self.free_busys,
+            availabilities: self.availabilities,
             timezones: self.timezones,
-        })
+        };
+
+        #[cfg(feature = "test")]
+        {
+            // Verify that the conditions for our getters are actually met
+            verified.get_name();
+            verified.get_description();
+            verified.get_uid();
+            verified.get_url();
+            verified.get_last_modified();
+            verified.get_refresh_interval();
+            verified.get_source();
+            verified.get_color();
+            verified.get_images();
+        }
+
+        Ok(verified)
+    }
+}
+
+/// Add `tzid` to the floating DATE-TIMEs of a component, keeping track of the first and the
+/// last one in `range`.
+fn apply_timezone(
+    properties: &mut [Property],
+    tzid: &str,
+    tz: Tz,
+    range: &mut Option<(NaiveDateTime, NaiveDateTime)>,
+) -> Result<(), ParserError> {
+    let mut applied = false;
+    for prop in properties.iter_mut() {
+        let Some(starts) = floating_starts(prop) else {
+            continue;
+        };
+        for start in starts {
+            *range = Some(match *range {
+                Some((first, last)) => (first.min(start), last.max(start)),
+                None => (start, start),
+            });
+        }
+        prop.params.push(("TZID".to_owned(), vec![tzid.to_owned()]));
+        applied = true;
+    }
+    if !applied {
+        return Ok(());
+    }
+
+    // An UNTIL must be in UTC once DTSTART has a TZID
+    for prop in properties.iter_mut().filter(|prop| prop.name == "RRULE") {
+        let Some(value) = &prop.value else {
+            continue;
+        };
+        let parts: Vec<_> = value
+            .split(';')
+            .map(|part| match part.split_once('=') {
+                Some((key, until))
+                    if key.eq_ignore_ascii_case("UNTIL")
+                        && NaiveDateTime::parse_from_str(until, LOCAL_DATE_TIME).is_ok() =>
+                {
+                    Ok(format!(
+                        "{key}={}",
+                        CalDateTime::from(CalDateTime::parse(until, Some(tz))?.utc()).format()
+                    ))
+                }
+                _ => Ok(part.to_owned()),
+            })
+            .collect::<Result<_, ParserError>>()?;
+        prop.value = Some(parts.join(";"));
     }
+    Ok(())
+}
+
+/// The starts of the values of a date or period property without TZID that are all floating.
+fn floating_starts(prop: &Property) -> Option<Vec<NaiveDateTime>> {
+    let period = match prop.get_value_type() {
+        None => false,
+        Some(value_type) if value_type.eq_ignore_ascii_case("DATE-TIME") => false,
+        Some(value_type) if value_type.eq_ignore_ascii_case("PERIOD") => true,
+        Some(_) => return None,
+    };
+    if prop.get_tzid().is_some()
+        || !matches!(
+            prop.name.as_str(),
+            "DTSTART" | "DTEND" | "DUE" | "RECURRENCE-ID" | "RDATE" | "EXDATE"
+        )
+        || (period && prop.name != "RDATE")
+    {
+        return None;
+    }
+    prop.value
+        .as_deref()?
+        .split(',')
+        .map(|value| {
+            let start = if period {
+                value.split_once('/')?.0
+            } else {
+                value
+            };
+            NaiveDateTime::parse_from_str(start, LOCAL_DATE_TIME).ok()
+        })
+        .collect()
 }
 
 impl IcalCalendar<true> {
+    /// Apply the X-WR-TIMEZONE that Google Calendar exports set to their floating DATE-TIMEs.
+    ///
+    /// Floating DTSTART, DTEND, DUE, RECURRENCE-ID, RDATE and EXDATE values of all components
+    /// get a TZID, the floating UNTIL of their RRULEs is converted to UTC and a VTIMEZONE is added
+    /// unless the calendar already has one. The VTIMEZONE covers the years of the rewritten
+    /// date-times, and the rules of the last of them continue from then on. Calendars without
+    /// X-WR-TIMEZONE are left alone.
+    pub fn apply_wr_timezone(
+        mut self,
+        resolver: &impl TimezoneResolver,
+    ) -> Result<Self, ParserError> {
+        let Some(name) = self
+            .get_property("X-WR-TIMEZONE")
+            .and_then(|prop| prop.value.clone())
+        else {
+            return Ok(self);
+        };
+        let existing = self
+            .timezones
+            .iter()
+            .find(|timezone| timezone.get_tzid() == name);
+        let tz = resolver
+            .resolve(&name)
+            .or_else(|| existing.and_then(|timezone| Tz::try_from(timezone).ok()))
+            .ok_or(ParserError::InvalidValue("X-WR-TIMEZONE"))?;
+        let tzid = if existing.is_some() {
+            name
+        } else {
+            tz.name().to_owned()
+        };
+
+        let mut range = None;
+        for properties in self
+            .events
+            .iter_mut()
+            .map(|event| &mut event.properties)
+            .chain(self.todos.iter_mut().map(|todo| &mut todo.properties))
+            .chain(
+                self.journals
+                    .iter_mut()
+                    .map(|journal| &mut journal.properties),
+            )
+            .chain(
+                self.free_busys
+                    .iter_mut()
+                    .map(|free_busy| &mut free_busy.properties),
+            )
+            .chain(self.availabilities.iter_mut().flat_map(|availability| {
+                std::iter::once(&mut availability.properties).chain(
+                    availability
+                        .available
+                        .iter_mut()
+                        .map(|available| &mut available.properties),
+                )
+            }))
+        {
+            apply_timezone(properties, &tzid, tz, &mut range)?;
+        }
+
+        if let Some((first, last)) = range
+            && !self
+                .timezones
+                .iter()
+                .any(|timezone| timezone.get_tzid() == tzid)
+        {
+            // Start a year early so the observances already apply at the first date-time. The
+            // range only depends on the calendar, so the same input always gives the same output
+            self.timezones
+                .push(IcalTimeZone::from_tz(tz, first.year() - 1..=last.year()));
+        }
+        Ok(self)
+    }
+
+    /// The NAME, or X-WR-CALNAME for calendars without one.
+    pub fn get_name(&self) -> Option<&str> {
+        get_text_with_fallback(self, "NAME", "X-WR-CALNAME")
+    }
+
+    /// The DESCRIPTION, or X-WR-CALDESC for calendars without one.
+    pub fn get_description(&self) -> Option<&str> {
+        get_text_with_fallback(self, "DESCRIPTION", "X-WR-CALDESC")
+    }
+
+    pub fn get_uid(&self) -> Option<&str> {
+        self.get_property("UID")
+            .and_then(|prop| prop.value.as_deref())
+    }
+
+    pub fn get_url(&self) -> Option<&str> {
+        self.get_property("URL")
+            .and_then(|prop| prop.value.as_deref())
+    }
+
+    /// The LAST-MODIFIED, or `None` if it's missing or malformed.
+    pub fn get_last_modified(&self) -> Option<DateTime<Utc>> {
+        parse_last_modified(self).ok().flatten()
+    }
+
+    /// How often clients should refresh the calendar, or `None` if the REFRESH-INTERVAL is
+    /// missing or malformed.
+    pub fn get_refresh_interval(&self) -> Option<Duration> {
+        parse_refresh_interval(self).ok().flatten()
+    }
+
+    /// The URI the calendar can be refreshed from.
+    pub fn get_source(&self) -> Option<&str> {
+        self.get_property("SOURCE")
+            .and_then(|prop| prop.value.as_deref())
+    }
+
+    /// The COLOR, or X-APPLE-CALENDAR-COLOR for calendars without one.
+    pub fn get_color(&self) -> Option<&str> {
+        get_text_with_fallback(self, "COLOR", "X-APPLE-CALENDAR-COLOR")
+    }
+
+    pub fn get_images(&self) -> Vec<Image<'_>> {
+        get_images(self)
+    }
+
+    /// The alarms firing within `[after, before)`, ordered by time.
+    ///
+    /// Relative triggers are resolved against every instance of their recurring parent, and
+    /// alarms directly within the calendar are only scheduled for absolute triggers.
+    pub fn next_alarms(
+        &self,
+        after: DateTime<Utc>,
+        before: DateTime<Utc>,
+        resolver: &impl TimezoneResolver,
+    ) -> Result<Vec<AlarmOccurrence<'_>>, ParserError> {
+        let timezones = resolve_timezones(
+            self.events
+                .iter()
+                .flat_map(|event| &event.properties)
+                .chain(self.todos.iter().flat_map(|todo| &todo.properties)),
+            &self.timezones,
+            resolver,
+        );
+
+        let mut occurrences = Vec::new();
+        for (_uid, events) in self
+            .events
+            .iter()
+            .into_group_map_by(|event| event.get_uid())
+            .into_iter()
+            .sorted_by_key(|(uid, _)| *uid)
+        {
+            schedule_alarms(&events, &timezones, (after, before), &mut occurrences)?;
+        }
+        for (_uid, todos) in self
+            .todos
+            .iter()
+            .into_group_map_by(|todo| todo.get_uid())
+            .into_iter()
+            .sorted_by_key(|(uid, _)| *uid)
+        {
+            schedule_alarms(&todos, &timezones, (after, before), &mut occurrences)?;
+        }
+        for alarm in &self.alarms {
+            if let Some(AlarmTrigger::Absolute(time)) = alarm.get_trigger() {
+                alarm.push_occurrences(
+                    time,
+                    AlarmSource::Calendar,
+                    None,
+                    (after, before),
+                    &mut occurrences,
+                );
+            }
+        }
+
+        occurrences.sort_by_key(|occurrence| occurrence.time);
+        Ok(occurrences)
+    }
+
     pub fn expand_calendar(self) -> Vec<Self> {
         let event_cals: Vec<_> = self
             .events
@@ -225,6 +589,21 @@ impl IcalCalendar<true> {
                 ..Default::default()
             })
             .collect();
+        let availability_cals: Vec<_> = self
+            .availabilities
+            .into_iter()
+            .map(|availability| IcalCalendar::<true> {
+                properties: self.properties.clone(),
+                timezones: self
+                    .timezones
+                    .iter()
+                    .filter(|tz| availability.get_tzids().contains(&tz.get_tzid()))
+                    .cloned()
+                    .collect(),
+                availabilities: vec![availability],
+                ..Default::default()
+            })
+            .collect();
 
         [
             event_cals,
@@ -232,6 +611,7 @@ impl IcalCalendar<true> {
             todo_cals,
             journal_cals,
             freebusy_cals,
+            availability_cals,
         ]
         .concat()
     }
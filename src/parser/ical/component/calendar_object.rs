@@ -1,13 +1,24 @@
+use std::collections::HashMap;
+
 use crate::{
-    PropertyParser,
     generator::Emitter,
+    ical_property,
     parser::{
-        Component, ComponentMut, ParserError,
-        ical::component::{IcalEvent, IcalJournal, IcalTimeZone, IcalTodo},
+        Component, ComponentMut, ParserError, PropertySource, VerifyOptions, check_method,
+        ical::{
+            component::{
+                AlarmOccurrence, CompletionStrategy, EditSession, EditSummary, IcalEvent,
+                IcalJournal, IcalTimeZone, IcalTodo, TodoStatus, schedule_alarms,
+            },
+            recurrence::{Recurring, expand, next_recurrence, rebase_rrule, resolve_timezones},
+        },
     },
     property::Property,
+    types::{CalDate, CalDateOrDateTime, CalDateTime, TimezoneResolver},
 };
-use std::io::BufRead;
+use chrono::{DateTime, Duration, Utc};
+use chrono_tz::Tz;
+use itertools::Itertools;
 
 #[derive(Debug, Clone)]
 #[cfg_attr(
@@ -30,6 +41,51 @@ impl CalendarInnerData {
     }
 }
 
+/// Shift a date or date-time, keeping its value type.
+pub(crate) fn shifted(value: &CalDateOrDateTime, by: Duration) -> CalDateOrDateTime {
+    match value {
+        CalDateOrDateTime::Date(date) => {
+            CalDateOrDateTime::Date(CalDate(date.0 + by, date.1.clone()))
+        }
+        CalDateOrDateTime::DateTime(datetime) => CalDateOrDateTime::DateTime(datetime.clone() + by),
+    }
+}
+
+/// Replace the value of a date property, keeping its parameters.
+pub(crate) fn with_date(prop: &Property, value: &CalDateOrDateTime) -> Property {
+    Property {
+        value: Some(value.format()),
+        ..prop.clone()
+    }
+}
+
+/// A single instance of `main` starting at `start`, with its end shifted along with its start.
+pub(crate) fn instance_at<C: Component>(
+    main: &C,
+    start: &Property,
+    timezones: &HashMap<String, Option<Tz>>,
+) -> Result<C::Unverified, ParserError> {
+    let mut instance = main.clone().mutable();
+    for name in ["RRULE", "RDATE", "EXDATE", "RECURRENCE-ID"] {
+        instance.remove_property(name);
+    }
+    let new_start = CalDateOrDateTime::parse_prop(start, timezones)?;
+    if let Some(dtstart) = main.get_property("DTSTART") {
+        let shift = new_start.clone() - &CalDateOrDateTime::parse_prop(dtstart, timezones)?;
+        for name in ["DTEND", "DUE"] {
+            if let Some(end) = main.get_property(name) {
+                let value = CalDateOrDateTime::parse_prop(end, timezones)?;
+                instance.set_property(with_date(end, &shifted(&value, shift)));
+            }
+        }
+    }
+    instance.set_property(Property {
+        name: "DTSTART".to_owned(),
+        ..start.clone()
+    });
+    Ok(instance)
+}
+
 #[derive(Debug, Clone)]
 /// An ICAL calendar object.
 #[cfg_attr(
@@ -43,6 +99,18 @@ pub struct IcalCalendarObject {
 }
 
 impl IcalCalendarObject {
+    pub(crate) fn from_parts(
+        properties: Vec<Property>,
+        inner: CalendarInnerData,
+        timezones: Vec<IcalTimeZone>,
+    ) -> Self {
+        Self {
+            properties,
+            inner,
+            timezones,
+        }
+    }
+
     pub fn get_uid(&self) -> &str {
         self.inner.get_uid()
     }
@@ -50,6 +118,613 @@ impl IcalCalendarObject {
     pub fn get_inner(&self) -> &CalendarInnerData {
         &self.inner
     }
+
+    pub fn get_timezones(&self) -> &[IcalTimeZone] {
+        &self.timezones
+    }
+
+    pub(crate) fn get_inner_mut(&mut self) -> &mut CalendarInnerData {
+        &mut self.inner
+    }
+
+    /// The to-do and its overrides, if this is a to-do.
+    pub(crate) fn get_todos_mut(&mut self) -> Option<Vec<&mut IcalTodo>> {
+        match &mut self.inner {
+            CalendarInnerData::Todo(main, overrides) => {
+                Some(std::iter::once(main).chain(overrides).collect())
+            }
+            _ => None,
+        }
+    }
+
+    /// The alarms firing within `[after, before)`, ordered by time.
+    ///
+    /// Relative triggers are resolved against every instance of the recurring component.
+    pub fn next_alarms(
+        &self,
+        after: DateTime<Utc>,
+        before: DateTime<Utc>,
+        resolver: &impl TimezoneResolver,
+    ) -> Result<Vec<AlarmOccurrence<'_>>, ParserError> {
+        let mut occurrences = Vec::new();
+        match &self.inner {
+            CalendarInnerData::Event(main, overrides) => {
+                let events: Vec<_> = std::iter::once(main).chain(overrides).collect();
+                let timezones = resolve_timezones(
+                    events.iter().flat_map(|event| &event.properties),
+                    &self.timezones,
+                    resolver,
+                );
+                schedule_alarms(&events, &timezones, (after, before), &mut occurrences)?;
+            }
+            CalendarInnerData::Todo(main, overrides) => {
+                let todos: Vec<_> = std::iter::once(main).chain(overrides).collect();
+                let timezones = resolve_timezones(
+                    todos.iter().flat_map(|todo| &todo.properties),
+                    &self.timezones,
+                    resolver,
+                );
+                schedule_alarms(&todos, &timezones, (after, before), &mut occurrences)?;
+            }
+            CalendarInnerData::Journal(..) => {}
+        }
+
+        occurrences.sort_by_key(|occurrence| occurrence.time);
+        Ok(occurrences)
+    }
+
+    /// Complete the current instance of a to-do at `time`.
+    ///
+    /// The current instance of a recurring to-do is the first one that is neither completed nor
+    /// cancelled yet, and `strategy` decides how it is completed. Once the last instance is
+    /// completed the series itself is. STATUS, COMPLETED and PERCENT-COMPLETE are set
+    /// accordingly, and the SEQUENCE of every changed component is incremented. Advancing the
+    /// series drops the overrides of the instances before its new DTSTART.
+    ///
+    /// Completing a to-do that is already completed returns a `ParserError::PropertyConflict`.
+    pub fn complete_todo(
+        &mut self,
+        time: DateTime<Utc>,
+        strategy: CompletionStrategy,
+        resolver: &impl TimezoneResolver,
+    ) -> Result<(), ParserError> {
+        let CalendarInnerData::Todo(main, overrides) = &mut self.inner else {
+            return Err(ParserError::InvalidComponent);
+        };
+        let already_completed = ParserError::PropertyConflict("the to-do is already completed");
+        let timezones = resolve_timezones(
+            std::iter::once(&*main)
+                .chain(overrides.iter())
+                .flat_map(|todo| &todo.properties),
+            &self.timezones,
+            resolver,
+        );
+
+        let recurs = main.get_recurrence_id().is_none()
+            && (main.get_rrule().is_some() || main.get_property("RDATE").is_some());
+        let Some(dtstart_prop) = main.get_dtstart().filter(|_| recurs).cloned() else {
+            if main.is_completed() {
+                return Err(already_completed);
+            }
+            let mut todo = main.clone().mutable();
+            todo.set_completed(time);
+            todo.increment_sequence();
+            *main = todo.verify()?;
+            return Ok(());
+        };
+        let dtstart = CalDateOrDateTime::parse_prop(&dtstart_prop, &timezones)?;
+
+        match strategy {
+            CompletionStrategy::Advance => {
+                if main.is_completed() {
+                    return Err(already_completed);
+                }
+                let mut todo = main.clone().mutable();
+                let next = next_recurrence(&*main, &dtstart, &timezones, dtstart.utc())?;
+                match &next {
+                    Some(next) => {
+                        let shift = next.clone() - &dtstart;
+                        todo.set_property(with_date(&dtstart_prop, next));
+                        if let Some(due) = main.get_due() {
+                            let value = CalDateOrDateTime::parse_prop(due, &timezones)?;
+                            todo.set_property(with_date(due, &shifted(&value, shift)));
+                        }
+                        todo.remove_property("RRULE");
+                        for rrule in main.get_named_properties("RRULE") {
+                            let value = rrule.value.as_deref().unwrap_or_default();
+                            if let Some(value) = rebase_rrule(value, &dtstart, next)? {
+                                todo.add_property(Property {
+                                    value: Some(value),
+                                    ..rrule.clone()
+                                });
+                            }
+                        }
+                        // RDATEs before the new DTSTART would be instances again
+                        todo.remove_property("RDATE");
+                        for rdate in main.get_named_properties("RDATE") {
+                            if let (_, Some(remaining)) =
+                                split_dates(rdate, next.utc(), &timezones)?
+                            {
+                                todo.add_property(remaining);
+                            }
+                        }
+                        todo.set_needs_action();
+                    }
+                    None => todo.set_completed(time),
+                }
+                todo.increment_sequence();
+                let todo = todo.verify()?;
+
+                // Overrides of instances before the new DTSTART aren't part of the series anymore
+                let mut remaining = Vec::new();
+                for component in overrides.iter() {
+                    let before_next = match (&next, component.get_recurrence_id()) {
+                        (Some(next), Some(prop)) => {
+                            CalDateOrDateTime::parse_prop(prop, &timezones)?.utc() < next.utc()
+                        }
+                        _ => false,
+                    };
+                    if !before_next {
+                        remaining.push(component.clone());
+                    }
+                }
+                *main = todo;
+                *overrides = remaining;
+            }
+            CompletionStrategy::Override => {
+                if main.is_completed() {
+                    return Err(already_completed);
+                }
+                let mut after = dtstart.utc() - Duration::seconds(1);
+                let mut completed_instance = false;
+                loop {
+                    let Some(start) = next_recurrence(&*main, &dtstart, &timezones, after)? else {
+                        // Every instance is completed, so the series is as well
+                        let mut todo = main.clone().mutable();
+                        todo.set_completed(time);
+                        todo.increment_sequence();
+                        *main = todo.verify()?;
+                        return Ok(());
+                    };
+                    after = start.utc();
+
+                    let mut existing = None;
+                    for (index, todo) in overrides.iter().enumerate() {
+                        if let Some(prop) = todo.get_recurrence_id()
+                            && CalDateOrDateTime::parse_prop(prop, &timezones)?.utc() == after
+                        {
+                            existing = Some(index);
+                        }
+                    }
+                    match existing {
+                        Some(index)
+                            if overrides[index].is_completed()
+                                || overrides[index].get_status() == Some(TodoStatus::Cancelled) =>
+                        {
+                            continue;
+                        }
+                        // Another instance is still to be completed
+                        _ if completed_instance => return Ok(()),
+                        Some(index) => {
+                            let mut todo = overrides[index].clone().mutable();
+                            todo.set_completed(time);
+                            todo.increment_sequence();
+                            overrides[index] = todo.verify()?;
+                        }
+                        None => {
+                            let mut todo = main.clone().mutable();
+                            for name in ["RRULE", "RDATE", "EXDATE"] {
+                                todo.remove_property(name);
+                            }
+                            todo.set_property(Property {
+                                name: "RECURRENCE-ID".to_owned(),
+                                ..with_date(&dtstart_prop, &start)
+                            });
+                            todo.set_property(with_date(&dtstart_prop, &start));
+                            if let Some(due) = main.get_due() {
+                                let value = CalDateOrDateTime::parse_prop(due, &timezones)?;
+                                let shift = start.clone() - &dtstart;
+                                todo.set_property(with_date(due, &shifted(&value, shift)));
+                            }
+                            todo.set_completed(time);
+                            todo.increment_sequence();
+                            overrides.push(todo.verify()?);
+                        }
+                    }
+                    completed_instance = true;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Modify the instance with `recurrence_id`, as in "modify this occurrence".
+    ///
+    /// `properties` replace the properties with the same name in the override of the instance,
+    /// which is created by copying the main component if there is none yet. The override is
+    /// edited in an [`EditSession`] that is committed at `now`.
+    pub fn modify_occurrence(
+        &mut self,
+        recurrence_id: &Property,
+        properties: impl IntoIterator<Item = Property>,
+        now: DateTime<Utc>,
+        resolver: &impl TimezoneResolver,
+    ) -> Result<EditSummary, ParserError> {
+        let timezones = self.resolve_timezones(recurrence_id, resolver);
+        match &mut self.inner {
+            CalendarInnerData::Event(main, overrides) => {
+                modify_occurrence(main, overrides, recurrence_id, properties, now, &timezones)
+            }
+            CalendarInnerData::Todo(main, overrides) => {
+                modify_occurrence(main, overrides, recurrence_id, properties, now, &timezones)
+            }
+            CalendarInnerData::Journal(..) => Err(ParserError::InvalidComponent),
+        }
+    }
+
+    /// Delete the instance with `recurrence_id`, as in "delete this occurrence".
+    ///
+    /// The override of the instance is removed, and the main component gets an EXDATE in an
+    /// [`EditSession`] that is committed at `now`.
+    pub fn delete_occurrence(
+        &mut self,
+        recurrence_id: &Property,
+        now: DateTime<Utc>,
+        resolver: &impl TimezoneResolver,
+    ) -> Result<(), ParserError> {
+        let timezones = self.resolve_timezones(recurrence_id, resolver);
+        match &mut self.inner {
+            CalendarInnerData::Event(main, overrides) => {
+                delete_occurrence(main, overrides, recurrence_id, now, &timezones)
+            }
+            CalendarInnerData::Todo(main, overrides) => {
+                delete_occurrence(main, overrides, recurrence_id, now, &timezones)
+            }
+            CalendarInnerData::Journal(..) => Err(ParserError::InvalidComponent),
+        }
+    }
+
+    /// Split the series at the instance with `recurrence_id`, as in "modify this and following".
+    ///
+    /// The RRULEs of this series end right before the instance, and the returned series with
+    /// `new_uid` starts with it. RDATEs, EXDATEs and overrides are split between both series,
+    /// and the COUNT of the RRULEs of the new series only includes the remaining instances.
+    /// This series is changed in an [`EditSession`] committed at `now`, while the new series
+    /// starts without a SEQUENCE.
+    pub fn split_series(
+        &mut self,
+        recurrence_id: &Property,
+        new_uid: &str,
+        now: DateTime<Utc>,
+        resolver: &impl TimezoneResolver,
+    ) -> Result<IcalCalendarObject, ParserError> {
+        let timezones = self.resolve_timezones(recurrence_id, resolver);
+        let inner = match &mut self.inner {
+            CalendarInnerData::Event(main, overrides) => {
+                let (main, overrides) =
+                    split_series(main, overrides, recurrence_id, new_uid, now, &timezones)?;
+                CalendarInnerData::Event(main, overrides)
+            }
+            CalendarInnerData::Todo(main, overrides) => {
+                let (main, overrides) =
+                    split_series(main, overrides, recurrence_id, new_uid, now, &timezones)?;
+                CalendarInnerData::Todo(main, overrides)
+            }
+            CalendarInnerData::Journal(..) => return Err(ParserError::InvalidComponent),
+        };
+        Ok(Self::from_parts(
+            self.properties.clone(),
+            inner,
+            self.timezones.clone(),
+        ))
+    }
+
+    /// The timezones of the components and of `extra`.
+    fn resolve_timezones(
+        &self,
+        extra: &Property,
+        resolver: &impl TimezoneResolver,
+    ) -> HashMap<String, Option<Tz>> {
+        let properties: Vec<&Property> = match &self.inner {
+            CalendarInnerData::Event(main, overrides) => std::iter::once(main)
+                .chain(overrides)
+                .flat_map(|event| &event.properties)
+                .collect(),
+            CalendarInnerData::Todo(main, overrides) => std::iter::once(main)
+                .chain(overrides)
+                .flat_map(|todo| &todo.properties)
+                .collect(),
+            CalendarInnerData::Journal(main, overrides) => std::iter::once(main)
+                .chain(overrides)
+                .flat_map(|journal| &journal.properties)
+                .collect(),
+        };
+        resolve_timezones(
+            properties.into_iter().chain([extra]),
+            &self.timezones,
+            resolver,
+        )
+    }
+}
+
+fn modify_occurrence<C: Recurring<Unverified: ComponentMut<Verified = C>>>(
+    main: &C,
+    overrides: &mut Vec<C>,
+    recurrence_id: &Property,
+    properties: impl IntoIterator<Item = Property>,
+    now: DateTime<Utc>,
+    timezones: &HashMap<String, Option<Tz>>,
+) -> Result<EditSummary, ParserError> {
+    let start = CalDateOrDateTime::parse_prop(recurrence_id, timezones)?;
+    let index = find_override(overrides, &start, timezones)?;
+    let mut component = match index {
+        Some(index) => overrides[index].clone().mutable(),
+        None => {
+            require_occurrence(main, &start, timezones)?;
+            let dtstart = Property {
+                name: "DTSTART".to_owned(),
+                ..recurrence_id.clone()
+            };
+            let mut instance = instance_at(main, &dtstart, timezones)?;
+            instance.add_property(Property {
+                name: "RECURRENCE-ID".to_owned(),
+                ..recurrence_id.clone()
+            });
+            instance
+        }
+    };
+
+    let mut session = EditSession::new(&mut component);
+    for prop in properties {
+        session.set_property(prop);
+    }
+    let summary = session.commit(now);
+    let component = component.verify()?;
+    match index {
+        Some(index) => overrides[index] = component,
+        None => overrides.push(component),
+    }
+    Ok(summary)
+}
+
+fn delete_occurrence<C: Recurring<Unverified: ComponentMut<Verified = C>>>(
+    main: &mut C,
+    overrides: &mut Vec<C>,
+    recurrence_id: &Property,
+    now: DateTime<Utc>,
+    timezones: &HashMap<String, Option<Tz>>,
+) -> Result<(), ParserError> {
+    let start = CalDateOrDateTime::parse_prop(recurrence_id, timezones)?;
+    let index = find_override(overrides, &start, timezones)?;
+    if index.is_none() {
+        require_occurrence(main, &start, timezones)?;
+    }
+
+    let mut updated = main.clone().mutable();
+    let mut session = EditSession::new(&mut updated);
+    session.add_property(Property {
+        name: "EXDATE".to_owned(),
+        ..recurrence_id.clone()
+    });
+    session.commit(now);
+    *main = updated.verify()?;
+    if let Some(index) = index {
+        overrides.remove(index);
+    }
+    Ok(())
+}
+
+fn split_series<C: Recurring<Unverified: ComponentMut<Verified = C>>>(
+    main: &mut C,
+    overrides: &mut Vec<C>,
+    recurrence_id: &Property,
+    new_uid: &str,
+    now: DateTime<Utc>,
+    timezones: &HashMap<String, Option<Tz>>,
+) -> Result<(C, Vec<C>), ParserError> {
+    let start = CalDateOrDateTime::parse_prop(recurrence_id, timezones)?;
+    let dtstart_prop = main
+        .get_property("DTSTART")
+        .cloned()
+        .ok_or(ParserError::MissingProperty("DTSTART"))?;
+    let dtstart = CalDateOrDateTime::parse_prop(&dtstart_prop, timezones)?;
+    if start.utc() <= dtstart.utc() {
+        return Err(ParserError::PropertyConflict(
+            "cannot split a series at its first occurrence",
+        ));
+    }
+    require_occurrence(main, &start, timezones)?;
+    // The RECURRENCE-ID may be in another zone like UTC, but the new series keeps the zone of
+    // the DTSTART
+    let shift = start - &dtstart;
+    let start = shifted(&dtstart, shift);
+
+    let mut series = main.clone().mutable();
+    series.set_property(with_date(&dtstart_prop, &start));
+    for name in ["DTEND", "DUE"] {
+        if let Some(end) = main.get_property(name) {
+            let value = CalDateOrDateTime::parse_prop(end, timezones)?;
+            series.set_property(with_date(end, &shifted(&value, shift)));
+        }
+    }
+    series.set_property(ical_property!("UID", new_uid));
+    series.remove_property("SEQUENCE");
+    let timestamp = CalDateTime::from(now).format();
+    series.set_property(ical_property!("DTSTAMP", timestamp.clone()));
+    series.set_property(ical_property!("LAST-MODIFIED", timestamp));
+
+    let mut updated = main.clone().mutable();
+    let mut session = EditSession::new(&mut updated);
+    let until = until_before(&dtstart_prop, &start);
+    for name in ["RRULE", "RDATE", "EXDATE"] {
+        session.remove_property(name);
+        series.remove_property(name);
+    }
+    for rrule in main.get_named_properties("RRULE") {
+        let value = rrule.value.as_deref().unwrap_or_default();
+        session.add_property(Property {
+            value: Some(end_rrule(value, &until)),
+            ..rrule.clone()
+        });
+        if let Some(value) = rebase_rrule(value, &dtstart, &start)? {
+            series.add_property(Property {
+                value: Some(value),
+                ..rrule.clone()
+            });
+        }
+    }
+    for prop in main
+        .get_named_properties("RDATE")
+        .into_iter()
+        .chain(main.get_named_properties("EXDATE"))
+    {
+        let (before, after) = split_dates(prop, start.utc(), timezones)?;
+        session.get_properties_mut().extend(before);
+        series.get_properties_mut().extend(after);
+    }
+    session.commit(now);
+    let updated = updated.verify()?;
+
+    let mut later = Vec::new();
+    let mut earlier = Vec::new();
+    for component in overrides.iter() {
+        let recurrence_id = component
+            .get_property("RECURRENCE-ID")
+            .map(|prop| CalDateOrDateTime::parse_prop(prop, timezones))
+            .transpose()?;
+        if recurrence_id.is_some_and(|recurrence_id| recurrence_id.utc() >= start.utc()) {
+            let mut component = component.clone().mutable();
+            component.set_property(ical_property!("UID", new_uid));
+            later.push(component.verify()?);
+        } else {
+            earlier.push(component.clone());
+        }
+    }
+    let series = series.verify()?;
+
+    // Only change the series once the split can't fail anymore
+    *main = updated;
+    *overrides = earlier;
+    Ok((series, later))
+}
+
+/// The index of the override with the RECURRENCE-ID `start`.
+pub(crate) fn find_override<C: Component>(
+    overrides: &[C],
+    start: &CalDateOrDateTime,
+    timezones: &HashMap<String, Option<Tz>>,
+) -> Result<Option<usize>, ParserError> {
+    for (index, component) in overrides.iter().enumerate() {
+        if let Some(prop) = component.get_property("RECURRENCE-ID")
+            && CalDateOrDateTime::parse_prop(prop, timezones)?.utc() == start.utc()
+        {
+            return Ok(Some(index));
+        }
+    }
+    Ok(None)
+}
+
+/// Make sure the recurrence set of `main` has an instance starting at `start`.
+pub(crate) fn require_occurrence<C: Recurring>(
+    main: &C,
+    start: &CalDateOrDateTime,
+    timezones: &HashMap<String, Option<Tz>>,
+) -> Result<(), ParserError> {
+    let time = start.utc();
+    if !expand([main], timezones, time, time + Duration::seconds(1))?
+        .iter()
+        .any(|instance| {
+            instance
+                .recurrence_id
+                .as_ref()
+                .is_some_and(|recurrence_id| recurrence_id.utc() == time)
+        })
+    {
+        return Err(ParserError::InvalidValue("RECURRENCE-ID"));
+    }
+    Ok(())
+}
+
+/// The UNTIL of an RRULE ending right before `start`, which must be in UTC unless `dtstart` is
+/// a date or floating.
+fn until_before(dtstart: &Property, start: &CalDateOrDateTime) -> String {
+    let floating = dtstart.get_tzid().is_none()
+        && !dtstart
+            .value
+            .as_deref()
+            .is_some_and(|value| value.ends_with('Z'));
+    match start {
+        CalDateOrDateTime::Date(_) => shifted(start, -Duration::days(1)).format(),
+        CalDateOrDateTime::DateTime(_) if floating => {
+            shifted(start, -Duration::seconds(1)).format()
+        }
+        CalDateOrDateTime::DateTime(_) => {
+            CalDateTime::from(start.utc() - Duration::seconds(1)).format()
+        }
+    }
+}
+
+/// Replace the COUNT or UNTIL of an RRULE with `until`.
+fn end_rrule(rrule: &str, until: &str) -> String {
+    rrule
+        .split(';')
+        .filter(|part| {
+            !part.split_once('=').is_some_and(|(key, _)| {
+                key.eq_ignore_ascii_case("COUNT") || key.eq_ignore_ascii_case("UNTIL")
+            })
+        })
+        .map(str::to_owned)
+        .chain([format!("UNTIL={until}")])
+        .join(";")
+}
+
+/// Split the values of an RDATE or EXDATE into the ones before `time` and the others.
+fn split_dates(
+    prop: &Property,
+    time: DateTime<Utc>,
+    timezones: &HashMap<String, Option<Tz>>,
+) -> Result<(Option<Property>, Option<Property>), ParserError> {
+    let period = prop
+        .get_value_type()
+        .is_some_and(|value_type| value_type.eq_ignore_ascii_case("PERIOD"));
+    // The start of a period is a date-time
+    let single = Property {
+        params: prop
+            .params
+            .iter()
+            .filter(|(key, _)| !period || key != "VALUE")
+            .cloned()
+            .collect(),
+        ..prop.clone()
+    };
+
+    let (mut before, mut after) = (Vec::new(), Vec::new());
+    for value in prop.value.as_deref().unwrap_or_default().split(',') {
+        let start = match value.split_once('/') {
+            Some((start, _)) if period => start,
+            _ => value,
+        };
+        let start = CalDateOrDateTime::parse_prop(
+            &Property {
+                value: Some(start.to_owned()),
+                ..single.clone()
+            },
+            timezones,
+        )?;
+        if start.utc() < time {
+            before.push(value);
+        } else {
+            after.push(value);
+        }
+    }
+    let with_values = |values: Vec<&str>| {
+        (!values.is_empty()).then(|| Property {
+            value: Some(values.join(",")),
+            ..prop.clone()
+        })
+    };
+    Ok((with_values(before), with_values(after)))
 }
 
 #[derive(Debug, Clone, Default)]
@@ -111,14 +786,16 @@ impl ComponentMut for IcalCalendarObjectBuilder {
         &mut self.properties
     }
 
-    fn add_sub_component<B: BufRead>(
+    fn add_sub_component<P: PropertySource>(
         &mut self,
         value: &str,
-        line_parser: &mut PropertyParser<B>,
+        line_parser: &mut P,
+        options: &VerifyOptions,
     ) -> Result<(), ParserError> {
         match value {
             "VEVENT" => {
-                let event = IcalEvent::from_parser(line_parser)?.verify()?;
+                let event =
+                    IcalEvent::from_parser_with(line_parser, options)?.verify_with(options)?;
                 match &mut self.inner {
                     Some(CalendarInnerData::Event(main, overrides)) => {
                         if event.get_uid() != main.get_uid() {
@@ -133,7 +810,8 @@ impl ComponentMut for IcalCalendarObjectBuilder {
                 };
             }
             "VTODO" => {
-                let todo = IcalTodo::from_parser(line_parser)?.verify()?;
+                let todo =
+                    IcalTodo::from_parser_with(line_parser, options)?.verify_with(options)?;
                 match &mut self.inner {
                     Some(CalendarInnerData::Todo(main, overrides)) => {
                         if todo.get_uid() != main.get_uid() {
@@ -148,7 +826,8 @@ impl ComponentMut for IcalCalendarObjectBuilder {
                 };
             }
             "VJOURNAL" => {
-                let journal = IcalJournal::from_parser(line_parser)?.verify()?;
+                let journal =
+                    IcalJournal::from_parser_with(line_parser, options)?.verify_with(options)?;
                 match &mut self.inner {
                     Some(CalendarInnerData::Journal(main, overrides)) => {
                         if journal.get_uid() != main.get_uid() {
@@ -163,7 +842,8 @@ impl ComponentMut for IcalCalendarObjectBuilder {
                 };
             }
             "VTIMEZONE" => {
-                let timezone = IcalTimeZone::from_parser(line_parser)?.verify()?;
+                let timezone =
+                    IcalTimeZone::from_parser_with(line_parser, options)?.verify_with(options)?;
                 self.timezones.push(timezone);
             }
             _ => return Err(ParserError::InvalidComponent),
@@ -172,7 +852,9 @@ impl ComponentMut for IcalCalendarObjectBuilder {
         Ok(())
     }
 
-    fn verify(self) -> Result<Self::Verified, ParserError> {
+    fn verify_with(self, options: &VerifyOptions) -> Result<Self::Verified, ParserError> {
+        check_method(&self, options)?;
+
         Ok(IcalCalendarObject {
             properties: self.properties,
             timezones: self.timezones,
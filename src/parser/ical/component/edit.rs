@@ -0,0 +1,119 @@
+use std::ops::{Deref, DerefMut};
+
+use chrono::{DateTime, Utc};
+use itertools::Itertools;
+
+use crate::{
+    ical_property,
+    parser::{ComponentMut, ical::component::increment_sequence},
+    property::Property,
+    types::CalDateTime,
+};
+
+/// The properties whose changes are significant according to
+/// [RFC5546](https://tools.ietf.org/html/rfc5546#section-2.1.4), so attendees have to
+/// reconsider their participation.
+const SIGNIFICANT: &[&str] = &[
+    "DTSTART", "DTEND", "DURATION", "DUE", "RRULE", "RDATE", "EXDATE", "LOCATION", "STATUS",
+];
+
+/// The properties maintained by the session itself.
+const MAINTAINED: &[&str] = &["DTSTAMP", "LAST-MODIFIED", "SEQUENCE"];
+
+/// Changes to the properties of a component that keep its DTSTAMP, LAST-MODIFIED and SEQUENCE
+/// up to date.
+///
+/// The session dereferences to the component, so it's edited with the usual `ComponentMut`
+/// methods. Changes are detected by comparing the properties on [`EditSession::commit`] with
+/// the ones at the start of the session, and changes to sub-components aren't taken into
+/// account.
+#[derive(Debug)]
+pub struct EditSession<'c, C: ComponentMut> {
+    component: &'c mut C,
+    original: Vec<Property>,
+}
+
+/// What was changed in an [`EditSession`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EditSummary {
+    /// The names of the changed properties, in the order they first appear.
+    pub changed: Vec<String>,
+    /// Whether a significant property changed, so the SEQUENCE was incremented.
+    pub significant: bool,
+}
+
+impl<'c, C: ComponentMut> EditSession<'c, C> {
+    pub fn new(component: &'c mut C) -> Self {
+        let original = component.get_properties().clone();
+        Self {
+            component,
+            original,
+        }
+    }
+
+    /// Update DTSTAMP and LAST-MODIFIED to `now` if anything changed, and increment SEQUENCE
+    /// if a significant property changed.
+    ///
+    /// Maintained properties that were changed during the session are left as they are.
+    pub fn commit(self, now: DateTime<Utc>) -> EditSummary {
+        let current = self.component.get_properties();
+        let changed: Vec<String> = self
+            .original
+            .iter()
+            .chain(current)
+            .map(|prop| prop.name.as_str())
+            .unique()
+            .filter(|name| {
+                !self
+                    .original
+                    .iter()
+                    .filter(|prop| prop.name == *name)
+                    .eq(current.iter().filter(|prop| prop.name == *name))
+            })
+            .map(str::to_owned)
+            .collect();
+
+        let edited = |names: &[&str]| changed.iter().any(|name| names.contains(&name.as_str()));
+        let significant = edited(SIGNIFICANT);
+        if changed
+            .iter()
+            .all(|name| MAINTAINED.contains(&name.as_str()))
+        {
+            return EditSummary {
+                changed,
+                significant,
+            };
+        }
+
+        let timestamp = CalDateTime::from(now).format();
+        if !edited(&["DTSTAMP"]) {
+            self.component
+                .set_property(ical_property!("DTSTAMP", timestamp.clone()));
+        }
+        if !edited(&["LAST-MODIFIED"]) {
+            self.component
+                .set_property(ical_property!("LAST-MODIFIED", timestamp));
+        }
+        if significant && !edited(&["SEQUENCE"]) {
+            increment_sequence(self.component);
+        }
+        EditSummary {
+            changed,
+            significant,
+        }
+    }
+}
+
+impl<C: ComponentMut> Deref for EditSession<'_, C> {
+    type Target = C;
+
+    fn deref(&self) -> &C {
+        self.component
+    }
+}
+
+impl<C: ComponentMut> DerefMut for EditSession<'_, C> {
+    fn deref_mut(&mut self) -> &mut C {
+        self.component
+    }
+}
@@ -1,10 +1,17 @@
 use crate::{
-    PropertyParser,
-    parser::{Component, ComponentMut, ParserError, ical::component::IcalAlarm},
+    parser::{
+        Component, ComponentMut, ParserError, PropertySource, VerifyOptions,
+        ical::component::{
+            Attendee, Conference, EditSession, IcalAlarm, IcalLocation, IcalParticipant,
+            IcalResource, Image, Organizer, StructuredData, StyledDescription, get_attendees,
+            get_conferences, get_images, get_organizer, get_structured_data,
+            get_styled_descriptions, remove_attendee, update_attendee, verify_metadata_properties,
+            verify_participation_properties, verify_publishing_properties,
+        },
+    },
     property::Property,
 };
 use itertools::Itertools;
-use std::io::BufRead;
 
 #[derive(Debug, Clone, Default)]
 #[cfg_attr(
@@ -14,6 +21,9 @@ use std::io::BufRead;
 pub struct IcalEvent<const VERIFIED: bool = true> {
     pub properties: Vec<Property>,
     pub alarms: Vec<IcalAlarm>,
+    pub locations: Vec<IcalLocation>,
+    pub resources: Vec<IcalResource>,
+    pub participants: Vec<IcalParticipant>,
 }
 
 impl IcalEvent<false> {
@@ -21,8 +31,34 @@ impl IcalEvent<false> {
         Self {
             properties: Vec::new(),
             alarms: Vec::new(),
+            locations: Vec::new(),
+            resources: Vec::new(),
+            participants: Vec::new(),
         }
     }
+
+    /// Start editing the event, keeping its DTSTAMP, LAST-MODIFIED and SEQUENCE up to date.
+    pub fn edit(&mut self) -> EditSession<'_, Self> {
+        EditSession::new(self)
+    }
+
+    pub fn add_attendee(&mut self, attendee: Attendee) {
+        self.add_property(attendee.into());
+    }
+
+    /// Replace the attendee with the same address, returning whether the event had one.
+    pub fn update_attendee(&mut self, attendee: Attendee) -> bool {
+        update_attendee(self, attendee)
+    }
+
+    /// Remove the attendee with `address`, returning whether the event had one.
+    pub fn remove_attendee(&mut self, address: &str) -> bool {
+        remove_attendee(self, address)
+    }
+
+    pub fn set_organizer(&mut self, organizer: Organizer) {
+        self.set_property(organizer.into());
+    }
 }
 
 impl IcalEvent<true> {
@@ -58,6 +94,36 @@ impl IcalEvent<true> {
     pub fn get_rrule(&self) -> Option<&Property> {
         self.get_property("RRULE")
     }
+
+    pub fn get_structured_data(&self) -> Vec<StructuredData<'_>> {
+        get_structured_data(self)
+    }
+
+    pub fn get_styled_descriptions(&self) -> Vec<StyledDescription<'_>> {
+        get_styled_descriptions(self)
+    }
+
+    /// The COLOR as a CSS3 color name.
+    pub fn get_color(&self) -> Option<&str> {
+        self.get_property("COLOR")
+            .and_then(|prop| prop.value.as_deref())
+    }
+
+    pub fn get_images(&self) -> Vec<Image<'_>> {
+        get_images(self)
+    }
+
+    pub fn get_conferences(&self) -> Vec<Conference<'_>> {
+        get_conferences(self)
+    }
+
+    pub fn get_organizer(&self) -> Option<Organizer> {
+        get_organizer(self)
+    }
+
+    pub fn get_attendees(&self) -> Vec<Attendee> {
+        get_attendees(self)
+    }
 }
 
 impl<const VERIFIED: bool> Component for IcalEvent<VERIFIED> {
@@ -72,6 +138,9 @@ impl<const VERIFIED: bool> Component for IcalEvent<VERIFIED> {
         IcalEvent {
             properties: self.properties,
             alarms: self.alarms,
+            locations: self.locations,
+            resources: self.resources,
+            participants: self.participants,
         }
     }
 }
@@ -83,16 +152,32 @@ impl ComponentMut for IcalEvent<false> {
         &mut self.properties
     }
 
-    fn add_sub_component<B: BufRead>(
+    fn add_sub_component<P: PropertySource>(
         &mut self,
         value: &str,
-        line_parser: &mut PropertyParser<B>,
+        line_parser: &mut P,
+        options: &VerifyOptions,
     ) -> Result<(), ParserError> {
         match value {
             "VALARM" => {
                 let mut alarm = IcalAlarm::new();
-                alarm.parse(line_parser)?;
-                self.alarms.push(alarm.verify()?);
+                alarm.parse_with(line_parser, options)?;
+                self.alarms.push(alarm.verify_with(options)?);
+            }
+            "VLOCATION" => {
+                let mut location = IcalLocation::new();
+                location.parse_with(line_parser, options)?;
+                self.locations.push(location.verify_with(options)?);
+            }
+            "VRESOURCE" => {
+                let mut resource = IcalResource::new();
+                resource.parse_with(line_parser, options)?;
+                self.resources.push(resource.verify_with(options)?);
+            }
+            "PARTICIPANT" => {
+                let mut participant = IcalParticipant::new();
+                participant.parse_with(line_parser, options)?;
+                self.participants.push(participant.verify_with(options)?);
             }
             _ => return Err(ParserError::InvalidComponent),
         };
@@ -100,7 +185,7 @@ impl ComponentMut for IcalEvent<false> {
         Ok(())
     }
 
-    fn verify(self) -> Result<IcalEvent<true>, ParserError> {
+    fn verify_with(self, options: &VerifyOptions) -> Result<IcalEvent<true>, ParserError> {
         if self
             .get_property("UID")
             .and_then(|prop| prop.value.as_ref())
@@ -109,15 +194,17 @@ impl ComponentMut for IcalEvent<false> {
             return Err(ParserError::MissingProperty("UID"));
         }
 
-        // if self
-        //     .get_property("DTSTAMP")
-        //     .and_then(|prop| prop.value.as_ref())
-        //     .is_none()
-        // {
-        //     return Err(ParserError::MissingProperty("DTSTAMP"));
-        // }
+        if options.require_dtstamp
+            && self
+                .get_property("DTSTAMP")
+                .and_then(|prop| prop.value.as_ref())
+                .is_none()
+        {
+            return Err(ParserError::MissingProperty("DTSTAMP"));
+        }
 
-        if self.get_property("METHOD").is_none()
+        if options.require_dtstart
+            && self.get_property("METHOD").is_none()
             && self
                 .get_property("DTSTART")
                 .and_then(|prop| prop.value.as_ref())
@@ -136,9 +223,18 @@ impl ComponentMut for IcalEvent<false> {
             Option::<chrono::Duration>::try_from(prop)?;
         }
 
+        if options.check_values {
+            verify_publishing_properties(&self)?;
+            verify_metadata_properties(&self)?;
+            verify_participation_properties(&self)?;
+        }
+
         let verified = IcalEvent {
             properties: self.properties,
             alarms: self.alarms,
+            locations: self.locations,
+            resources: self.resources,
+            participants: self.participants,
         };
 
         #[cfg(feature = "test")]
@@ -151,6 +247,13 @@ impl ComponentMut for IcalEvent<false> {
             verified.get_dtend();
             verified.get_duration();
             verified.get_rrule();
+            verified.get_structured_data();
+            verified.get_styled_descriptions();
+            verified.get_color();
+            verified.get_images();
+            verified.get_conferences();
+            verified.get_organizer();
+            verified.get_attendees();
         }
 
         Ok(verified)
@@ -1,10 +1,17 @@
 use crate::{
-    PropertyParser,
-    parser::{Component, ComponentMut, ParserError},
+    ical_param, ical_property,
+    parser::{
+        Component, ComponentMut, ParserError, PropertySource, VerifyOptions,
+        ical::{
+            component::{CalendarInnerData, IcalCalendarObject, IcalEvent, IcalTimeZone},
+            recurrence::{expand_overlapping, resolve_timezones},
+        },
+    },
     property::Property,
+    types::{CalDateTime, CalDateTimeError, DateTimeOrDuration, Period, TimezoneResolver},
 };
+use chrono::{DateTime, Utc};
 use itertools::Itertools;
-use std::io::BufRead;
 
 #[derive(Debug, Clone, Default)]
 #[cfg_attr(
@@ -23,6 +30,261 @@ impl IcalFreeBusy<false> {
     }
 }
 
+/// The FBTYPE of a FREEBUSY property.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum FreeBusyType {
+    Free,
+    #[default]
+    Busy,
+    BusyUnavailable,
+    BusyTentative,
+    Other(String),
+}
+
+impl FreeBusyType {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Free => "FREE",
+            Self::Busy => "BUSY",
+            Self::BusyUnavailable => "BUSY-UNAVAILABLE",
+            Self::BusyTentative => "BUSY-TENTATIVE",
+            Self::Other(value) => value,
+        }
+    }
+}
+
+impl From<&str> for FreeBusyType {
+    fn from(value: &str) -> Self {
+        match value.to_uppercase().as_str() {
+            "FREE" => Self::Free,
+            "BUSY" => Self::Busy,
+            "BUSY-UNAVAILABLE" => Self::BusyUnavailable,
+            "BUSY-TENTATIVE" => Self::BusyTentative,
+            _ => Self::Other(value.to_owned()),
+        }
+    }
+}
+
+/// A period of a FREEBUSY property.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FreeBusyPeriod {
+    pub fbtype: FreeBusyType,
+    pub period: Period,
+}
+
+impl FreeBusyPeriod {
+    /// Parse the comma-separated periods of a FREEBUSY property.
+    ///
+    /// The periods of a FREEBUSY property are always in UTC.
+    pub fn parse_prop(prop: &Property) -> Result<Vec<Self>, CalDateTimeError> {
+        if prop.value.is_none() {
+            return Err(CalDateTimeError::InvalidPeriodFormat(
+                "empty property".into(),
+            ));
+        }
+        Self::parse_values(prop).collect()
+    }
+
+    /// Parse each of the comma-separated periods of a FREEBUSY property on its own.
+    fn parse_values(prop: &Property) -> impl Iterator<Item = Result<Self, CalDateTimeError>> {
+        let fbtype = prop
+            .get_param("FBTYPE")
+            .map(FreeBusyType::from)
+            .unwrap_or_default();
+        prop.value
+            .as_deref()
+            .into_iter()
+            .flat_map(|value| value.split(','))
+            .map(move |value| {
+                Ok(Self {
+                    fbtype: fbtype.clone(),
+                    period: Period::parse(value, None)?,
+                })
+            })
+    }
+}
+
+impl FreeBusyPeriod {
+    fn new(fbtype: FreeBusyType, start: DateTime<Utc>, end: DateTime<Utc>) -> Self {
+        Self {
+            fbtype,
+            period: Period(start.into(), DateTimeOrDuration::DateTime(end.into())),
+        }
+    }
+
+    fn format(&self) -> String {
+        format!(
+            "{}/{}",
+            self.period.start().format(),
+            self.period.end().format()
+        )
+    }
+}
+
+/// A busy interval in UTC.
+pub(crate) type BusyTime = (FreeBusyType, DateTime<Utc>, DateTime<Utc>);
+
+/// Add the busy time of a master event and its overrides within `[start, end)`.
+pub(crate) fn event_busy_time(
+    events: Vec<&IcalEvent>,
+    timezones: &[IcalTimeZone],
+    resolver: &impl TimezoneResolver,
+    (start, end): (DateTime<Utc>, DateTime<Utc>),
+    busy: &mut Vec<BusyTime>,
+) -> Result<(), ParserError> {
+    let timezones = resolve_timezones(
+        events.iter().flat_map(|event| &event.properties),
+        timezones,
+        resolver,
+    );
+
+    for instance in expand_overlapping(events, &timezones, start, end)? {
+        let value = |name| {
+            instance
+                .component
+                .get_property(name)
+                .and_then(|prop| prop.value.as_deref())
+                .map(str::to_uppercase)
+        };
+        if value("TRANSP").as_deref() == Some("TRANSPARENT") {
+            continue;
+        }
+        let fbtype = match value("STATUS").as_deref() {
+            Some("CANCELLED") => continue,
+            Some("TENTATIVE") => FreeBusyType::BusyTentative,
+            _ => FreeBusyType::Busy,
+        };
+        let (Some(instance_start), Some(instance_end)) = (&instance.start, &instance.end) else {
+            continue;
+        };
+        let period_start = instance_start.utc().max(start);
+        let period_end = instance_end.utc().min(end);
+        // Instances without a duration don't take up any time
+        if period_start < period_end {
+            busy.push((fbtype, period_start, period_end));
+        }
+    }
+    Ok(())
+}
+
+/// Sort busy time by FBTYPE and start, and merge overlapping intervals of the same FBTYPE.
+pub(crate) fn coalesce(mut busy: Vec<BusyTime>) -> Vec<BusyTime> {
+    busy.sort_by(|a, b| (a.0.as_str(), a.1).cmp(&(b.0.as_str(), b.1)));
+    let mut coalesced: Vec<BusyTime> = Vec::new();
+    for (fbtype, start, end) in busy {
+        match coalesced.last_mut() {
+            Some(last) if last.0 == fbtype && start <= last.2 => last.2 = last.2.max(end),
+            _ => coalesced.push((fbtype, start, end)),
+        }
+    }
+    coalesced
+}
+
+/// The busy time of the events of `objects` within `[start, end)`, following
+/// [RFC4791 7.10](https://tools.ietf.org/html/rfc4791#section-7.10).
+///
+/// Recurring events are expanded, and transparent or cancelled events are skipped. The periods
+/// are clipped to the time range, and overlapping periods of the same FBTYPE are coalesced. They
+/// are ordered by FBTYPE and start.
+pub fn busy_periods<'o>(
+    objects: impl IntoIterator<Item = &'o IcalCalendarObject>,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    resolver: &impl TimezoneResolver,
+) -> Result<Vec<FreeBusyPeriod>, ParserError> {
+    let mut busy = Vec::new();
+    for object in objects {
+        if let CalendarInnerData::Event(main, overrides) = object.get_inner() {
+            event_busy_time(
+                std::iter::once(main).chain(overrides).collect(),
+                object.get_timezones(),
+                resolver,
+                (start, end),
+                &mut busy,
+            )?;
+        }
+    }
+
+    Ok(coalesce(busy)
+        .into_iter()
+        .map(|(fbtype, start, end)| FreeBusyPeriod::new(fbtype, start, end))
+        .collect())
+}
+
+impl IcalFreeBusy<true> {
+    /// Aggregate the busy time of the events of `objects` within `[start, end)` into a
+    /// VFREEBUSY, as returned by a CalDAV free-busy query.
+    ///
+    /// See `busy_periods` for how the events are taken into account. The FREEBUSY properties
+    /// contain one FBTYPE each, and the VFREEBUSY is identified by `uid`.
+    pub fn aggregate<'o>(
+        objects: impl IntoIterator<Item = &'o IcalCalendarObject>,
+        uid: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        resolver: &impl TimezoneResolver,
+    ) -> Result<Self, ParserError> {
+        let mut freebusy = IcalFreeBusy::new();
+        freebusy.add_property(ical_property!("UID", uid));
+        freebusy.add_property(ical_property!(
+            "DTSTAMP",
+            CalDateTime::from(Utc::now()).format()
+        ));
+        freebusy.add_property(ical_property!("DTSTART", CalDateTime::from(start).format()));
+        freebusy.add_property(ical_property!("DTEND", CalDateTime::from(end).format()));
+
+        let periods = busy_periods(objects, start, end, resolver)?;
+        for (fbtype, periods) in &periods.iter().chunk_by(|period| &period.fbtype) {
+            let value = periods.map(FreeBusyPeriod::format).join(",");
+            freebusy.add_property(match fbtype {
+                FreeBusyType::Busy => ical_property!("FREEBUSY", value),
+                fbtype => ical_property!("FREEBUSY", value, ical_param!("FBTYPE", fbtype.as_str())),
+            });
+        }
+
+        freebusy.verify()
+    }
+
+    /// The start of the time range covered by the free/busy information.
+    pub fn get_dtstart(&self) -> Option<DateTime<Utc>> {
+        self.get_utc("DTSTART")
+    }
+
+    /// The end of the time range covered by the free/busy information.
+    pub fn get_dtend(&self) -> Option<DateTime<Utc>> {
+        self.get_utc("DTEND")
+    }
+
+    /// The value of the property `name` in UTC, unless it's missing or malformed.
+    fn get_utc(&self, name: &str) -> Option<DateTime<Utc>> {
+        self.get_property(name)
+            .and_then(|prop| prop.value.as_deref())
+            .and_then(|value| CalDateTime::parse(value, None).ok())
+            .map(|value| value.utc())
+    }
+
+    pub fn get_organizer(&self) -> Option<&str> {
+        self.get_property("ORGANIZER")
+            .and_then(|prop| prop.value.as_deref())
+    }
+
+    pub fn get_attendees(&self) -> Vec<&str> {
+        self.get_named_properties("ATTENDEE")
+            .into_iter()
+            .filter_map(|prop| prop.value.as_deref())
+            .collect()
+    }
+
+    /// The valid periods of all FREEBUSY properties.
+    pub fn get_periods(&self) -> Vec<FreeBusyPeriod> {
+        self.get_named_properties("FREEBUSY")
+            .into_iter()
+            .flat_map(FreeBusyPeriod::parse_values)
+            .filter_map(Result::ok)
+            .collect()
+    }
+}
+
 impl<const VERIFIED: bool> Component for IcalFreeBusy<VERIFIED> {
     const NAMES: &[&str] = &["VFREEBUSY"];
     type Unverified = IcalFreeBusy<false>;
@@ -46,18 +308,51 @@ impl ComponentMut for IcalFreeBusy<false> {
     }
 
     #[cfg(not(tarpaulin_include))]
-    fn add_sub_component<B: BufRead>(
+    fn add_sub_component<P: PropertySource>(
         &mut self,
         _: &str,
-        _: &mut PropertyParser<B>,
+        _: &mut P,
+        _: &VerifyOptions,
     ) -> Result<(), ParserError> {
         Err(ParserError::InvalidComponent)
     }
 
-    fn verify(self) -> Result<IcalFreeBusy<true>, ParserError> {
-        Ok(IcalFreeBusy {
+    fn verify_with(self, options: &VerifyOptions) -> Result<IcalFreeBusy<true>, ParserError> {
+        if options.require_dtstamp
+            && self
+                .get_property("DTSTAMP")
+                .and_then(|prop| prop.value.as_ref())
+                .is_none()
+        {
+            return Err(ParserError::MissingProperty("DTSTAMP"));
+        }
+
+        if options.check_values {
+            for name in ["DTSTART", "DTEND"] {
+                if let Some(prop) = self.get_property(name) {
+                    CalDateTime::parse(prop.value.as_deref().unwrap_or_default(), None)?;
+                }
+            }
+            for prop in self.get_named_properties("FREEBUSY") {
+                FreeBusyPeriod::parse_prop(prop)?;
+            }
+        }
+
+        let verified = IcalFreeBusy {
             properties: self.properties,
-        })
+        };
+
+        #[cfg(feature = "test")]
+        {
+            // Verify that the conditions for our getters are actually met
+            verified.get_dtstart();
+            verified.get_dtend();
+            verified.get_organizer();
+            verified.get_attendees();
+            verified.get_periods();
+        }
+
+        Ok(verified)
     }
 }
 
@@ -1,10 +1,11 @@
 use crate::{
-    PropertyParser,
-    parser::{Component, ComponentMut, ParserError},
+    parser::{
+        Component, ComponentMut, ParserError, PropertySource, VerifyOptions,
+        ical::component::EditSession,
+    },
     property::Property,
 };
 use itertools::Itertools;
-use std::io::BufRead;
 
 #[derive(Debug, Clone, Default)]
 #[cfg_attr(
@@ -21,6 +22,11 @@ impl IcalJournal<false> {
             properties: Vec::new(),
         }
     }
+
+    /// Start editing the journal, keeping its DTSTAMP, LAST-MODIFIED and SEQUENCE up to date.
+    pub fn edit(&mut self) -> EditSession<'_, Self> {
+        EditSession::new(self)
+    }
 }
 
 impl IcalJournal<true> {
@@ -67,15 +73,16 @@ impl ComponentMut for IcalJournal<false> {
         &mut self.properties
     }
 
-    fn add_sub_component<B: BufRead>(
+    fn add_sub_component<P: PropertySource>(
         &mut self,
         _: &str,
-        _: &mut PropertyParser<B>,
+        _: &mut P,
+        _: &VerifyOptions,
     ) -> Result<(), ParserError> {
         Err(ParserError::InvalidComponent)
     }
 
-    fn verify(self) -> Result<IcalJournal<true>, ParserError> {
+    fn verify_with(self, _options: &VerifyOptions) -> Result<IcalJournal<true>, ParserError> {
         if self
             .get_property("UID")
             .and_then(|prop| prop.value.as_ref())
@@ -0,0 +1,153 @@
+//! The new properties for iCalendar defined in
+//! [RFC7986](https://tools.ietf.org/html/rfc7986).
+
+use crate::{
+    parser::{Component, ComponentMut, ParserError, ical::component::PropertyContent},
+    property::Property,
+    types::CalDateTime,
+};
+use chrono::{DateTime, Duration, Utc};
+
+/// An IMAGE property.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image<'p> {
+    pub fmttype: Option<&'p str>,
+    /// The DISPLAY values like `BADGE` or `THUMBNAIL`, where no value means `BADGE`.
+    pub display: Vec<&'p str>,
+    pub altrep: Option<&'p str>,
+    /// Either a URI or BASE64 encoded binary data.
+    pub value: PropertyContent<'p>,
+}
+
+impl<'p> Image<'p> {
+    pub fn parse_prop(prop: &'p Property) -> Result<Self, ParserError> {
+        let value = prop.value.as_deref().unwrap_or_default();
+        let value = match prop.get_value_type().map(str::to_uppercase).as_deref() {
+            None | Some("URI") if !value.is_empty() => PropertyContent::Uri(value),
+            Some("BINARY")
+                if prop
+                    .get_param("ENCODING")
+                    .is_some_and(|encoding| encoding.eq_ignore_ascii_case("BASE64")) =>
+            {
+                PropertyContent::Binary(value)
+            }
+            _ => return Err(ParserError::InvalidValue("IMAGE")),
+        };
+        Ok(Self {
+            fmttype: prop.get_param("FMTTYPE"),
+            display: get_param_values(prop, "DISPLAY"),
+            altrep: prop.get_param("ALTREP"),
+            value,
+        })
+    }
+}
+
+/// A CONFERENCE property describing how to join an event.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Conference<'p> {
+    pub uri: &'p str,
+    /// The FEATURE values like `AUDIO`, `VIDEO` or `PHONE`.
+    pub features: Vec<&'p str>,
+    pub label: Option<&'p str>,
+}
+
+impl<'p> Conference<'p> {
+    pub fn parse_prop(prop: &'p Property) -> Result<Self, ParserError> {
+        match (prop.get_value_type(), prop.value.as_deref()) {
+            (None, Some(uri)) => Ok(uri),
+            (Some(value_type), Some(uri)) if value_type.eq_ignore_ascii_case("URI") => Ok(uri),
+            _ => Err(ParserError::InvalidValue("CONFERENCE")),
+        }
+        .map(|uri| Self {
+            uri,
+            features: get_param_values(prop, "FEATURE"),
+            label: prop.get_param("LABEL"),
+        })
+    }
+}
+
+fn get_param_values<'p>(prop: &'p Property, name: &str) -> Vec<&'p str> {
+    prop.params
+        .iter()
+        .filter(|(key, _)| key == name)
+        .flat_map(|(_, values)| values.iter().map(String::as_str))
+        .collect()
+}
+
+/// The value of `name`, falling back to the value of the legacy property `fallback`.
+pub(crate) fn get_text_with_fallback<'c, C: Component>(
+    component: &'c C,
+    name: &str,
+    fallback: &str,
+) -> Option<&'c str> {
+    component
+        .get_property(name)
+        .or_else(|| component.get_property(fallback))
+        .and_then(|prop| prop.value.as_deref())
+}
+
+pub(crate) fn parse_last_modified<C: Component>(
+    component: &C,
+) -> Result<Option<DateTime<Utc>>, ParserError> {
+    component
+        .get_property("LAST-MODIFIED")
+        .and_then(|prop| prop.value.as_deref())
+        .map(|value| Ok(CalDateTime::parse(value, None)?.utc()))
+        .transpose()
+}
+
+pub(crate) fn parse_refresh_interval<C: Component>(
+    component: &C,
+) -> Result<Option<Duration>, ParserError> {
+    component
+        .get_property("REFRESH-INTERVAL")
+        .map(Option::<Duration>::try_from)
+        .transpose()
+        .map(Option::flatten)
+        .map_err(|_| ParserError::InvalidValue("REFRESH-INTERVAL"))
+}
+
+/// Check the IMAGE and CONFERENCE properties of a component.
+pub(crate) fn verify_metadata_properties<C: Component>(component: &C) -> Result<(), ParserError> {
+    for prop in component.get_named_properties("IMAGE") {
+        Image::parse_prop(prop)?;
+    }
+    for prop in component.get_named_properties("CONFERENCE") {
+        Conference::parse_prop(prop)?;
+    }
+    Ok(())
+}
+
+/// The valid IMAGE properties of a component.
+pub(crate) fn get_images<C: Component>(component: &C) -> Vec<Image<'_>> {
+    component
+        .get_named_properties("IMAGE")
+        .into_iter()
+        .filter_map(|prop| Image::parse_prop(prop).ok())
+        .collect()
+}
+
+/// The valid CONFERENCE properties of a component.
+pub(crate) fn get_conferences<C: Component>(component: &C) -> Vec<Conference<'_>> {
+    component
+        .get_named_properties("CONFERENCE")
+        .into_iter()
+        .filter_map(|prop| Conference::parse_prop(prop).ok())
+        .collect()
+}
+
+/// Set `prop` and update the legacy property `fallback` if it's present.
+pub(crate) fn set_with_fallback<C: ComponentMut>(
+    component: &mut C,
+    prop: Property,
+    fallback: &str,
+) {
+    for legacy in component
+        .get_properties_mut()
+        .iter_mut()
+        .filter(|legacy| legacy.name == fallback)
+    {
+        legacy.value.clone_from(&prop.value);
+    }
+    component.set_property(prop);
+}
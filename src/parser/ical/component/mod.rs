@@ -14,3 +14,13 @@ mod timezone;
 pub use timezone::*;
 mod freebusy;
 pub use freebusy::*;
+mod availability;
+pub use availability::*;
+mod publishing;
+pub use publishing::*;
+mod metadata;
+pub use metadata::*;
+mod attendee;
+pub use attendee::*;
+mod edit;
+pub use edit::*;
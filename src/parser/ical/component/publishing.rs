@@ -0,0 +1,500 @@
+//! The components and properties of the event publishing extensions defined in
+//! [RFC9073](https://tools.ietf.org/html/rfc9073).
+
+use crate::{
+    parser::{Component, ComponentMut, ParserError, PropertySource, VerifyOptions},
+    property::Property,
+};
+
+/// The value of a STRUCTURED-DATA or STYLED-DESCRIPTION property, depending on its VALUE type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PropertyContent<'p> {
+    Text(&'p str),
+    /// BASE64 encoded binary data.
+    Binary(&'p str),
+    Uri(&'p str),
+}
+
+impl<'p> PropertyContent<'p> {
+    fn parse_prop(prop: &'p Property, allow_binary: bool) -> Option<Self> {
+        let value = prop.value.as_deref().unwrap_or_default();
+        match prop.get_value_type().map(str::to_uppercase).as_deref() {
+            None | Some("TEXT") => Some(Self::Text(value)),
+            Some("URI") => Some(Self::Uri(value)),
+            Some("BINARY")
+                if allow_binary
+                    && prop
+                        .get_param("ENCODING")
+                        .is_some_and(|encoding| encoding.eq_ignore_ascii_case("BASE64")) =>
+            {
+                Some(Self::Binary(value))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// A STRUCTURED-DATA property.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StructuredData<'p> {
+    /// The media type, required unless the data is referenced by URI.
+    pub fmttype: Option<&'p str>,
+    /// The URI of the schema, required unless the data is referenced by URI.
+    pub schema: Option<&'p str>,
+    pub value: PropertyContent<'p>,
+}
+
+impl<'p> StructuredData<'p> {
+    pub fn parse_prop(prop: &'p Property) -> Result<Self, ParserError> {
+        let data = PropertyContent::parse_prop(prop, true)
+            .map(|value| Self {
+                fmttype: prop.get_param("FMTTYPE"),
+                schema: prop.get_param("SCHEMA"),
+                value,
+            })
+            .ok_or(ParserError::InvalidValue("STRUCTURED-DATA"))?;
+        if !matches!(data.value, PropertyContent::Uri(_))
+            && (data.fmttype.is_none() || data.schema.is_none())
+        {
+            return Err(ParserError::InvalidValue("STRUCTURED-DATA"));
+        }
+        Ok(data)
+    }
+}
+
+/// A STYLED-DESCRIPTION property.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StyledDescription<'p> {
+    pub fmttype: Option<&'p str>,
+    /// Whether the description was derived from the DESCRIPTION of the component.
+    pub derived: bool,
+    pub value: PropertyContent<'p>,
+}
+
+impl<'p> StyledDescription<'p> {
+    pub fn parse_prop(prop: &'p Property) -> Result<Self, ParserError> {
+        PropertyContent::parse_prop(prop, false)
+            .map(|value| Self {
+                fmttype: prop.get_param("FMTTYPE"),
+                derived: prop
+                    .get_param("DERIVED")
+                    .is_some_and(|derived| derived.eq_ignore_ascii_case("TRUE")),
+                value,
+            })
+            .ok_or(ParserError::InvalidValue("STYLED-DESCRIPTION"))
+    }
+}
+
+/// Check the STRUCTURED-DATA and STYLED-DESCRIPTION properties of a component.
+pub(crate) fn verify_publishing_properties<C: Component>(component: &C) -> Result<(), ParserError> {
+    for prop in component.get_named_properties("STRUCTURED-DATA") {
+        StructuredData::parse_prop(prop)?;
+    }
+    for prop in component.get_named_properties("STYLED-DESCRIPTION") {
+        StyledDescription::parse_prop(prop)?;
+    }
+    Ok(())
+}
+
+/// The valid STRUCTURED-DATA properties of a component.
+pub(crate) fn get_structured_data<C: Component>(component: &C) -> Vec<StructuredData<'_>> {
+    component
+        .get_named_properties("STRUCTURED-DATA")
+        .into_iter()
+        .filter_map(|prop| StructuredData::parse_prop(prop).ok())
+        .collect()
+}
+
+/// The valid STYLED-DESCRIPTION properties of a component.
+pub(crate) fn get_styled_descriptions<C: Component>(component: &C) -> Vec<StyledDescription<'_>> {
+    component
+        .get_named_properties("STYLED-DESCRIPTION")
+        .into_iter()
+        .filter_map(|prop| StyledDescription::parse_prop(prop).ok())
+        .collect()
+}
+
+fn verify_uid<C: Component>(component: &C) -> Result<(), ParserError> {
+    if component
+        .get_property("UID")
+        .and_then(|prop| prop.value.as_ref())
+        .is_none()
+    {
+        return Err(ParserError::MissingProperty("UID"));
+    }
+    Ok(())
+}
+
+fn get_text<'c, C: Component>(component: &'c C, name: &str) -> Option<&'c str> {
+    component
+        .get_property(name)
+        .and_then(|prop| prop.value.as_deref())
+}
+
+/// A VLOCATION component.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
+pub struct IcalLocation<const VERIFIED: bool = true> {
+    pub properties: Vec<Property>,
+}
+
+impl IcalLocation<false> {
+    pub fn new() -> Self {
+        Self {
+            properties: Vec::new(),
+        }
+    }
+}
+
+impl IcalLocation<true> {
+    pub fn get_uid(&self) -> &str {
+        get_text(self, "UID").expect("already verified that this must exist")
+    }
+
+    pub fn get_name(&self) -> Option<&str> {
+        get_text(self, "NAME")
+    }
+
+    pub fn get_description(&self) -> Option<&str> {
+        get_text(self, "DESCRIPTION")
+    }
+
+    /// The values of the LOCATION-TYPE property, like `parking` or `restaurant`.
+    pub fn get_location_types(&self) -> Vec<&str> {
+        get_text(self, "LOCATION-TYPE")
+            .map(|value| value.split(',').collect())
+            .unwrap_or_default()
+    }
+
+    pub fn get_url(&self) -> Option<&str> {
+        get_text(self, "URL")
+    }
+
+    pub fn get_structured_data(&self) -> Vec<StructuredData<'_>> {
+        get_structured_data(self)
+    }
+}
+
+impl<const VERIFIED: bool> Component for IcalLocation<VERIFIED> {
+    const NAMES: &[&str] = &["VLOCATION"];
+    type Unverified = IcalLocation<false>;
+
+    fn get_properties(&self) -> &Vec<Property> {
+        &self.properties
+    }
+
+    fn mutable(self) -> Self::Unverified {
+        IcalLocation {
+            properties: self.properties,
+        }
+    }
+}
+
+impl ComponentMut for IcalLocation<false> {
+    type Verified = IcalLocation<true>;
+
+    fn get_properties_mut(&mut self) -> &mut Vec<Property> {
+        &mut self.properties
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    fn add_sub_component<P: PropertySource>(
+        &mut self,
+        _: &str,
+        _: &mut P,
+        _: &VerifyOptions,
+    ) -> Result<(), ParserError> {
+        Err(ParserError::InvalidComponent)
+    }
+
+    fn verify_with(self, options: &VerifyOptions) -> Result<IcalLocation<true>, ParserError> {
+        verify_uid(&self)?;
+        if options.check_values {
+            verify_publishing_properties(&self)?;
+        }
+
+        let verified = IcalLocation {
+            properties: self.properties,
+        };
+
+        #[cfg(feature = "test")]
+        {
+            // Verify that the conditions for our getters are actually met
+            verified.get_uid();
+            verified.get_name();
+            verified.get_description();
+            verified.get_location_types();
+            verified.get_url();
+            verified.get_structured_data();
+        }
+
+        Ok(verified)
+    }
+}
+
+/// A VRESOURCE component.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
+pub struct IcalResource<const VERIFIED: bool = true> {
+    pub properties: Vec<Property>,
+}
+
+impl IcalResource<false> {
+    pub fn new() -> Self {
+        Self {
+            properties: Vec::new(),
+        }
+    }
+}
+
+impl IcalResource<true> {
+    pub fn get_uid(&self) -> &str {
+        get_text(self, "UID").expect("already verified that this must exist")
+    }
+
+    pub fn get_name(&self) -> Option<&str> {
+        get_text(self, "NAME")
+    }
+
+    pub fn get_description(&self) -> Option<&str> {
+        get_text(self, "DESCRIPTION")
+    }
+
+    /// The RESOURCE-TYPE, like `projector` or `room`.
+    pub fn get_resource_type(&self) -> Option<&str> {
+        get_text(self, "RESOURCE-TYPE")
+    }
+
+    pub fn get_structured_data(&self) -> Vec<StructuredData<'_>> {
+        get_structured_data(self)
+    }
+}
+
+impl<const VERIFIED: bool> Component for IcalResource<VERIFIED> {
+    const NAMES: &[&str] = &["VRESOURCE"];
+    type Unverified = IcalResource<false>;
+
+    fn get_properties(&self) -> &Vec<Property> {
+        &self.properties
+    }
+
+    fn mutable(self) -> Self::Unverified {
+        IcalResource {
+            properties: self.properties,
+        }
+    }
+}
+
+impl ComponentMut for IcalResource<false> {
+    type Verified = IcalResource<true>;
+
+    fn get_properties_mut(&mut self) -> &mut Vec<Property> {
+        &mut self.properties
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    fn add_sub_component<P: PropertySource>(
+        &mut self,
+        _: &str,
+        _: &mut P,
+        _: &VerifyOptions,
+    ) -> Result<(), ParserError> {
+        Err(ParserError::InvalidComponent)
+    }
+
+    fn verify_with(self, options: &VerifyOptions) -> Result<IcalResource<true>, ParserError> {
+        verify_uid(&self)?;
+        if options.check_values {
+            verify_publishing_properties(&self)?;
+        }
+
+        let verified = IcalResource {
+            properties: self.properties,
+        };
+
+        #[cfg(feature = "test")]
+        {
+            // Verify that the conditions for our getters are actually met
+            verified.get_uid();
+            verified.get_name();
+            verified.get_description();
+            verified.get_resource_type();
+            verified.get_structured_data();
+        }
+
+        Ok(verified)
+    }
+}
+
+/// The PARTICIPANT-TYPE of a PARTICIPANT.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParticipantType {
+    Active,
+    Inactive,
+    Sponsor,
+    Contact,
+    BookingContact,
+    EmergencyContact,
+    PublicityContact,
+    PlannerContact,
+    Performer,
+    Speaker,
+    Other(String),
+}
+
+impl From<&str> for ParticipantType {
+    fn from(value: &str) -> Self {
+        match value.to_uppercase().as_str() {
+            "ACTIVE" => Self::Active,
+            "INACTIVE" => Self::Inactive,
+            "SPONSOR" => Self::Sponsor,
+            "CONTACT" => Self::Contact,
+            "BOOKING-CONTACT" => Self::BookingContact,
+            "EMERGENCY-CONTACT" => Self::EmergencyContact,
+            "PUBLICITY-CONTACT" => Self::PublicityContact,
+            "PLANNER-CONTACT" => Self::PlannerContact,
+            "PERFORMER" => Self::Performer,
+            "SPEAKER" => Self::Speaker,
+            _ => Self::Other(value.to_owned()),
+        }
+    }
+}
+
+/// A PARTICIPANT component.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
+pub struct IcalParticipant<const VERIFIED: bool = true> {
+    pub properties: Vec<Property>,
+    pub locations: Vec<IcalLocation>,
+    pub resources: Vec<IcalResource>,
+}
+
+impl IcalParticipant<false> {
+    pub fn new() -> Self {
+        Self {
+            properties: Vec::new(),
+            locations: Vec::new(),
+            resources: Vec::new(),
+        }
+    }
+}
+
+impl IcalParticipant<true> {
+    pub fn get_uid(&self) -> &str {
+        get_text(self, "UID").expect("already verified that this must exist")
+    }
+
+    pub fn get_participant_type(&self) -> ParticipantType {
+        get_text(self, "PARTICIPANT-TYPE")
+            .map(ParticipantType::from)
+            .expect("already verified that this must exist")
+    }
+
+    /// The CALENDAR-ADDRESS used to schedule the participant, usually a `mailto:` URI.
+    pub fn get_calendar_address(&self) -> Option<&str> {
+        get_text(self, "CALENDAR-ADDRESS")
+    }
+
+    pub fn get_summary(&self) -> Option<&str> {
+        get_text(self, "SUMMARY")
+    }
+
+    pub fn get_description(&self) -> Option<&str> {
+        get_text(self, "DESCRIPTION")
+    }
+
+    pub fn get_structured_data(&self) -> Vec<StructuredData<'_>> {
+        get_structured_data(self)
+    }
+
+    pub fn get_styled_descriptions(&self) -> Vec<StyledDescription<'_>> {
+        get_styled_descriptions(self)
+    }
+}
+
+impl<const VERIFIED: bool> Component for IcalParticipant<VERIFIED> {
+    const NAMES: &[&str] = &["PARTICIPANT"];
+    type Unverified = IcalParticipant<false>;
+
+    fn get_properties(&self) -> &Vec<Property> {
+        &self.properties
+    }
+
+    fn mutable(self) -> Self::Unverified {
+        IcalParticipant {
+            properties: self.properties,
+            locations: self.locations,
+            resources: self.resources,
+        }
+    }
+}
+
+impl ComponentMut for IcalParticipant<false> {
+    type Verified = IcalParticipant<true>;
+
+    fn get_properties_mut(&mut self) -> &mut Vec<Property> {
+        &mut self.properties
+    }
+
+    fn add_sub_component<P: PropertySource>(
+        &mut self,
+        value: &str,
+        line_parser: &mut P,
+        options: &VerifyOptions,
+    ) -> Result<(), ParserError> {
+        match value {
+            "VLOCATION" => {
+                let mut location = IcalLocation::new();
+                location.parse_with(line_parser, options)?;
+                self.locations.push(location.verify_with(options)?);
+            }
+            "VRESOURCE" => {
+                let mut resource = IcalResource::new();
+                resource.parse_with(line_parser, options)?;
+                self.resources.push(resource.verify_with(options)?);
+            }
+            _ => return Err(ParserError::InvalidComponent),
+        };
+
+        Ok(())
+    }
+
+    fn verify_with(self, options: &VerifyOptions) -> Result<IcalParticipant<true>, ParserError> {
+        verify_uid(&self)?;
+        if get_text(&self, "PARTICIPANT-TYPE").is_none() {
+            return Err(ParserError::MissingProperty("PARTICIPANT-TYPE"));
+        }
+        if options.check_values {
+            verify_publishing_properties(&self)?;
+        }
+
+        let verified = IcalParticipant {
+            properties: self.properties,
+            locations: self.locations,
+            resources: self.resources,
+        };
+
+        #[cfg(feature = "test")]
+        {
+            // Verify that the conditions for our getters are actually met
+            verified.get_uid();
+            verified.get_participant_type();
+            verified.get_calendar_address();
+            verified.get_summary();
+            verified.get_description();
+            verified.get_structured_data();
+            verified.get_styled_descriptions();
+        }
+
+        Ok(verified)
+    }
+}
@@ -1,9 +1,12 @@
 use crate::{
-    PropertyParser,
-    parser::{Component, ComponentMut, ParserError},
+    ical_property,
+    parser::{Component, ComponentMut, ParserError, PropertySource, VerifyOptions},
     property::Property,
+    types::LOCAL_DATE_TIME,
 };
-use std::io::BufRead;
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, Offset, TimeZone, Utc, Weekday};
+use chrono_tz::{OffsetComponents, OffsetName, Tz};
+use std::ops::RangeInclusive;
 
 #[derive(Debug, Clone, Default)]
 #[cfg_attr(
@@ -29,6 +32,202 @@ impl IcalTimeZone {
     }
 }
 
+/// The offset, whether it's daylight saving time and the abbreviation of an observance.
+type Observance = (i32, bool, Option<String>);
+
+/// A transition between observances by the rule it follows within a year.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Transition {
+    before: Observance,
+    after: Observance,
+    month: u32,
+    /// The week of the month, with -1 for the last one.
+    week: i32,
+    weekday: Weekday,
+    time: NaiveTime,
+}
+
+/// The transitions of consecutive years with the same rules, with their first and last time.
+type Run = Vec<(Transition, DateTime<Utc>, DateTime<Utc>)>;
+
+impl IcalTimeZone {
+    /// A VTIMEZONE for `tz` with the observances of `years`.
+    ///
+    /// Consecutive years with the same transitions share observances that recur yearly until
+    /// the last of these years, and the rules of the last of `years` continue from then on.
+    /// Within such a run of years, transitions are assumed to happen on the same weekday of the
+    /// month, which holds for the rules of most timezones.
+    pub fn from_tz(tz: Tz, years: RangeInclusive<i32>) -> Self {
+        let observance = |time: DateTime<Utc>| -> Observance {
+            let offset = tz.offset_from_utc_datetime(&time.naive_utc());
+            (
+                offset.fix().local_minus_utc(),
+                offset.dst_offset() != Duration::zero(),
+                offset.abbreviation().map(str::to_owned),
+            )
+        };
+        let local = |time: DateTime<Utc>, offset: i32| {
+            (time + Duration::seconds(offset.into())).naive_utc()
+        };
+        let new_year = |year: i32| {
+            NaiveDate::from_ymd_opt(year, 1, 1)
+                .unwrap_or_default()
+                .and_hms_opt(0, 0, 0)
+                .unwrap_or_default()
+                .and_utc()
+        };
+
+        let mut runs: Vec<Run> = Vec::new();
+        let mut previous: Option<Vec<Transition>> = None;
+        for year in years.clone() {
+            let start = new_year(year);
+            let mut transitions = Vec::new();
+            for day in (0..366)
+                .map(|day| start + Duration::days(day))
+                .take_while(|day| day.year() == year)
+            {
+                let (before, after) = (observance(day), observance(day + Duration::days(1)));
+                if before == after {
+                    continue;
+                }
+                // Narrow the transition down to the second
+                let (mut low, mut high) = (day, day + Duration::days(1));
+                while high - low > Duration::seconds(1) {
+                    let middle = low + (high - low) / 2;
+                    if observance(middle) == before {
+                        low = middle;
+                    } else {
+                        high = middle;
+                    }
+                }
+                let time = local(high, before.0);
+                let week = if (time.date() + Duration::days(7)).month() != time.month() {
+                    -1
+                } else {
+                    (time.day() as i32 - 1) / 7 + 1
+                };
+                transitions.push((
+                    Transition {
+                        before,
+                        after,
+                        month: time.month(),
+                        week,
+                        weekday: time.weekday(),
+                        time: time.time(),
+                    },
+                    high,
+                ));
+            }
+
+            let rules: Vec<_> = transitions.iter().map(|(rule, _)| rule.clone()).collect();
+            match runs.last_mut() {
+                Some(run) if previous.as_ref() == Some(&rules) => {
+                    for ((_, _, last), (_, time)) in run.iter_mut().zip(transitions) {
+                        *last = time;
+                    }
+                }
+                _ => runs.push(
+                    transitions
+                        .into_iter()
+                        .map(|(rule, time)| (rule, time, time))
+                        .collect(),
+                ),
+            }
+            previous = Some(rules);
+        }
+
+        let mut transitions = Vec::new();
+        if runs.first().is_none_or(Vec::is_empty) {
+            // Cover the start of `years` until the first transition
+            let start = new_year(*years.start());
+            let (offset, dst, name) = observance(start);
+            transitions.push(IcalTimeZoneTransition {
+                transition: if dst {
+                    IcalTimeZoneTransitionType::DAYLIGHT
+                } else {
+                    IcalTimeZoneTransitionType::STANDARD
+                },
+                properties: [
+                    Some(ical_property!("TZOFFSETFROM", format_utc_offset(offset))),
+                    Some(ical_property!("TZOFFSETTO", format_utc_offset(offset))),
+                    name.map(|name| ical_property!("TZNAME", name)),
+                    Some(ical_property!(
+                        "DTSTART",
+                        local(start, offset).format(LOCAL_DATE_TIME).to_string()
+                    )),
+                ]
+                .into_iter()
+                .flatten()
+                .collect(),
+            });
+        }
+
+        let last_run = runs.len().saturating_sub(1);
+        for (index, run) in runs.into_iter().enumerate() {
+            for (rule, first, last) in run {
+                let rrule = format!(
+                    "FREQ=YEARLY;BYMONTH={};BYDAY={}{}",
+                    rule.month,
+                    rule.week,
+                    &rule.weekday.to_string()[..2].to_uppercase()
+                );
+                let rrule = if index == last_run {
+                    Some(rrule)
+                } else if first != last {
+                    Some(format!("{rrule};UNTIL={}", last.format("%Y%m%dT%H%M%SZ")))
+                } else {
+                    None
+                };
+                transitions.push(IcalTimeZoneTransition {
+                    transition: if rule.after.1 {
+                        IcalTimeZoneTransitionType::DAYLIGHT
+                    } else {
+                        IcalTimeZoneTransitionType::STANDARD
+                    },
+                    properties: [
+                        Some(ical_property!(
+                            "TZOFFSETFROM",
+                            format_utc_offset(rule.before.0)
+                        )),
+                        Some(ical_property!(
+                            "TZOFFSETTO",
+                            format_utc_offset(rule.after.0)
+                        )),
+                        rule.after.2.map(|name| ical_property!("TZNAME", name)),
+                        Some(ical_property!(
+                            "DTSTART",
+                            local(first, rule.before.0)
+                                .format(LOCAL_DATE_TIME)
+                                .to_string()
+                        )),
+                        rrule.map(|rrule| ical_property!("RRULE", rrule)),
+                    ]
+                    .into_iter()
+                    .flatten()
+                    .collect(),
+                });
+            }
+        }
+
+        IcalTimeZone {
+            properties: vec![ical_property!("TZID", tz.name())],
+            transitions,
+        }
+    }
+}
+
+/// Format an offset in seconds as a UTC-OFFSET value like `+0100`.
+fn format_utc_offset(offset: i32) -> String {
+    let sign = if offset < 0 { '-' } else { '+' };
+    let offset = offset.abs();
+    let (hours, minutes, seconds) = (offset / 3600, offset / 60 % 60, offset % 60);
+    if seconds == 0 {
+        format!("{sign}{hours:02}{minutes:02}")
+    } else {
+        format!("{sign}{hours:02}{minutes:02}{seconds:02}")
+    }
+}
+
 impl TryFrom<&IcalTimeZone> for chrono_tz::Tz {
     type Error = chrono_tz::ParseError;
 
@@ -75,23 +274,24 @@ impl ComponentMut for IcalTimeZone<false> {
         &mut self.properties
     }
 
-    fn add_sub_component<B: BufRead>(
+    fn add_sub_component<P: PropertySource>(
         &mut self,
         value: &str,
-        line_parser: &mut PropertyParser<B>,
+        line_parser: &mut P,
+        options: &VerifyOptions,
     ) -> Result<(), ParserError> {
         use self::IcalTimeZoneTransitionType::{DAYLIGHT, STANDARD};
 
         match value {
             "STANDARD" => {
                 let mut transition = IcalTimeZoneTransition::new(STANDARD);
-                transition.parse(line_parser)?;
-                self.transitions.push(transition.verify()?);
+                transition.parse_with(line_parser, options)?;
+                self.transitions.push(transition.verify_with(options)?);
             }
             "DAYLIGHT" => {
                 let mut transition = IcalTimeZoneTransition::new(DAYLIGHT);
-                transition.parse(line_parser)?;
-                self.transitions.push(transition.verify()?);
+                transition.parse_with(line_parser, options)?;
+                self.transitions.push(transition.verify_with(options)?);
             }
             _ => return Err(ParserError::InvalidComponent),
         };
@@ -99,7 +299,7 @@ impl ComponentMut for IcalTimeZone<false> {
         Ok(())
     }
 
-    fn verify(self) -> Result<IcalTimeZone<true>, ParserError> {
+    fn verify_with(self, _options: &VerifyOptions) -> Result<IcalTimeZone<true>, ParserError> {
         if !matches!(
             self.get_property("TZID"),
             Some(&Property { value: Some(_), .. }),
@@ -177,15 +377,19 @@ impl ComponentMut for IcalTimeZoneTransition<false> {
     }
 
     #[cfg(not(tarpaulin_include))]
-    fn add_sub_component<B: BufRead>(
+    fn add_sub_component<P: PropertySource>(
         &mut self,
         _: &str,
-        _: &mut PropertyParser<B>,
+        _: &mut P,
+        _: &VerifyOptions,
     ) -> Result<(), ParserError> {
         Err(ParserError::InvalidComponent)
     }
 
-    fn verify(self) -> Result<IcalTimeZoneTransition<true>, ParserError> {
+    fn verify_with(
+        self,
+        _options: &VerifyOptions,
+    ) -> Result<IcalTimeZoneTransition<true>, ParserError> {
         Ok(IcalTimeZoneTransition {
             transition: self.transition,
             properties: self.properties,
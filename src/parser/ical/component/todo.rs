@@ -1,10 +1,26 @@
+use std::collections::HashMap;
+
 use crate::{
-    PropertyParser,
-    parser::{Component, ComponentMut, ParserError, ical::component::IcalAlarm},
+    ical_property,
+    parser::{
+        Component, ComponentMut, ParserError, PropertySource, VerifyOptions,
+        ical::{
+            component::{
+                Attendee, Conference, EditSession, IcalAlarm, IcalLocation, IcalParticipant,
+                IcalResource, Image, Organizer, StructuredData, StyledDescription, get_attendees,
+                get_conferences, get_images, get_organizer, get_structured_data,
+                get_styled_descriptions, remove_attendee, update_attendee,
+                verify_metadata_properties, verify_participation_properties,
+                verify_publishing_properties,
+            },
+            recurrence::bounds,
+        },
+    },
     property::Property,
+    types::{CalDateOrDateTime, CalDateTime, OlsonResolver, TimezoneResolver},
 };
+use chrono::{DateTime, Utc};
 use itertools::Itertools;
-use std::io::BufRead;
 
 #[derive(Debug, Clone, Default)]
 #[cfg_attr(
@@ -14,6 +30,9 @@ use std::io::BufRead;
 pub struct IcalTodo<const VERIFIED: bool = true> {
     pub properties: Vec<Property>,
     pub alarms: Vec<IcalAlarm>,
+    pub locations: Vec<IcalLocation>,
+    pub resources: Vec<IcalResource>,
+    pub participants: Vec<IcalParticipant>,
 }
 
 impl IcalTodo<false> {
@@ -21,8 +40,84 @@ impl IcalTodo<false> {
         Self {
             properties: Vec::new(),
             alarms: Vec::new(),
+            locations: Vec::new(),
+            resources: Vec::new(),
+            participants: Vec::new(),
         }
     }
+
+    /// Start editing the to-do, keeping its DTSTAMP, LAST-MODIFIED and SEQUENCE up to date.
+    pub fn edit(&mut self) -> EditSession<'_, Self> {
+        EditSession::new(self)
+    }
+
+    pub fn add_attendee(&mut self, attendee: Attendee) {
+        self.add_property(attendee.into());
+    }
+
+    /// Replace the attendee with the same address, returning whether the to-do had one.
+    pub fn update_attendee(&mut self, attendee: Attendee) -> bool {
+        update_attendee(self, attendee)
+    }
+
+    /// Remove the attendee with `address`, returning whether the to-do had one.
+    pub fn remove_attendee(&mut self, address: &str) -> bool {
+        remove_attendee(self, address)
+    }
+
+    pub fn set_organizer(&mut self, organizer: Organizer) {
+        self.set_property(organizer.into());
+    }
+}
+
+/// The STATUS of a to-do.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TodoStatus {
+    NeedsAction,
+    Completed,
+    InProcess,
+    Cancelled,
+    Other(String),
+}
+
+impl From<&str> for TodoStatus {
+    fn from(value: &str) -> Self {
+        match value.to_uppercase().as_str() {
+            "NEEDS-ACTION" => Self::NeedsAction,
+            "COMPLETED" => Self::Completed,
+            "IN-PROCESS" => Self::InProcess,
+            "CANCELLED" => Self::Cancelled,
+            _ => Self::Other(value.to_owned()),
+        }
+    }
+}
+
+/// How an instance of a recurring to-do is completed.
+///
+/// Clients disagree on this, so both conventions are supported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompletionStrategy {
+    /// Move DTSTART and DUE of the series to its next instance.
+    Advance,
+    /// Add a completed override for the instance and keep the series unchanged.
+    Override,
+}
+
+/// Parse an integer property and check that it lies within `range`.
+pub(crate) fn parse_bounded<T: Component>(
+    component: &T,
+    name: &'static str,
+    range: std::ops::RangeInclusive<u8>,
+) -> Result<Option<u8>, ParserError> {
+    let Some(prop) = component.get_property(name) else {
+        return Ok(None);
+    };
+    prop.value
+        .as_deref()
+        .and_then(|value| value.parse().ok())
+        .filter(|value| range.contains(value))
+        .map(Some)
+        .ok_or(ParserError::InvalidValue(name))
 }
 
 impl IcalTodo<true> {
@@ -58,6 +153,113 @@ impl IcalTodo<true> {
     pub fn get_rrule(&self) -> Option<&Property> {
         self.get_property("RRULE")
     }
+
+    pub fn get_status(&self) -> Option<TodoStatus> {
+        self.get_property("STATUS")
+            .and_then(|prop| prop.value.as_deref())
+            .map(TodoStatus::from)
+    }
+
+    /// The percentage of the to-do that has been completed, between 0 and 100, unless it's out
+    /// of range.
+    pub fn get_percent_complete(&self) -> Option<u8> {
+        parse_bounded(self, "PERCENT-COMPLETE", 0..=100)
+            .ok()
+            .flatten()
+    }
+
+    /// The priority between 1 (highest) and 9 (lowest), with 0 meaning undefined, unless it's
+    /// out of range.
+    pub fn get_priority(&self) -> Option<u8> {
+        parse_bounded(self, "PRIORITY", 0..=9).ok().flatten()
+    }
+
+    /// The time the to-do was completed, unless it's malformed.
+    pub fn get_completed(&self) -> Option<DateTime<Utc>> {
+        self.get_property("COMPLETED")
+            .and_then(|prop| prop.value.as_deref())
+            .and_then(|value| CalDateTime::parse(value, None).ok())
+            .map(|completed| completed.utc())
+    }
+
+    /// Whether the to-do has a COMPLETED status or time, a cancelled to-do isn't completed.
+    pub fn is_completed(&self) -> bool {
+        self.get_status() == Some(TodoStatus::Completed) || self.get_completed().is_some()
+    }
+
+    /// The time the to-do is due, which is either its DUE or DTSTART plus its DURATION.
+    pub fn get_due_time(
+        &self,
+        timezones: &HashMap<String, Option<chrono_tz::Tz>>,
+    ) -> Result<Option<CalDateOrDateTime>, ParserError> {
+        Ok(bounds(self, timezones)?.1)
+    }
+
+    pub fn get_structured_data(&self) -> Vec<StructuredData<'_>> {
+        get_structured_data(self)
+    }
+
+    pub fn get_styled_descriptions(&self) -> Vec<StyledDescription<'_>> {
+        get_styled_descriptions(self)
+    }
+
+    /// The COLOR as a CSS3 color name.
+    pub fn get_color(&self) -> Option<&str> {
+        self.get_property("COLOR")
+            .and_then(|prop| prop.value.as_deref())
+    }
+
+    pub fn get_images(&self) -> Vec<Image<'_>> {
+        get_images(self)
+    }
+
+    pub fn get_conferences(&self) -> Vec<Conference<'_>> {
+        get_conferences(self)
+    }
+
+    pub fn get_organizer(&self) -> Option<Organizer> {
+        get_organizer(self)
+    }
+
+    pub fn get_attendees(&self) -> Vec<Attendee> {
+        get_attendees(self)
+    }
+}
+
+impl IcalTodo<false> {
+    /// Mark the to-do as completed at `time`.
+    pub(crate) fn set_completed(&mut self, time: DateTime<Utc>) {
+        self.set_property(ical_property!("STATUS", "COMPLETED"));
+        self.set_property(ical_property!(
+            "COMPLETED",
+            CalDateTime::from(time).format()
+        ));
+        self.set_property(ical_property!("PERCENT-COMPLETE", "100"));
+    }
+
+    /// Mark the to-do as not started.
+    pub(crate) fn set_needs_action(&mut self) {
+        self.set_property(ical_property!("STATUS", "NEEDS-ACTION"));
+        self.remove_property("COMPLETED");
+        self.remove_property("PERCENT-COMPLETE");
+    }
+
+    pub(crate) fn increment_sequence(&mut self) {
+        increment_sequence(self);
+    }
+}
+
+/// Increment the SEQUENCE of a component, which starts at 0 if missing and stays within the
+/// range of INTEGER values.
+pub(crate) fn increment_sequence<C: ComponentMut>(component: &mut C) {
+    let sequence = component
+        .get_property("SEQUENCE")
+        .and_then(|prop| prop.value.as_deref())
+        .and_then(|value| value.parse::<u32>().ok())
+        .unwrap_or_default()
+        .saturating_add(1)
+        .min(i32::MAX as u32);
+    component.set_property(ical_property!("SEQUENCE", sequence.to_string()));
 }
 
 impl<const VERIFIED: bool> Component for IcalTodo<VERIFIED> {
@@ -72,6 +274,9 @@ impl<const VERIFIED: bool> Component for IcalTodo<VERIFIED> {
         IcalTodo {
             properties: self.properties,
             alarms: self.alarms,
+            locations: self.locations,
+            resources: self.resources,
+            participants: self.participants,
         }
     }
 }
@@ -83,16 +288,32 @@ impl ComponentMut for IcalTodo<false> {
         &mut self.properties
     }
 
-    fn add_sub_component<B: BufRead>(
+    fn add_sub_component<P: PropertySource>(
         &mut self,
         value: &str,
-        line_parser: &mut PropertyParser<B>,
+        line_parser: &mut P,
+        options: &VerifyOptions,
     ) -> Result<(), ParserError> {
         match value {
             "VALARM" => {
                 let mut alarm = IcalAlarm::new();
-                alarm.parse(line_parser)?;
-                self.alarms.push(alarm.verify()?);
+                alarm.parse_with(line_parser, options)?;
+                self.alarms.push(alarm.verify_with(options)?);
+            }
+            "VLOCATION" => {
+                let mut location = IcalLocation::new();
+                location.parse_with(line_parser, options)?;
+                self.locations.push(location.verify_with(options)?);
+            }
+            "VRESOURCE" => {
+                let mut resource = IcalResource::new();
+                resource.parse_with(line_parser, options)?;
+                self.resources.push(resource.verify_with(options)?);
+            }
+            "PARTICIPANT" => {
+                let mut participant = IcalParticipant::new();
+                participant.parse_with(line_parser, options)?;
+                self.participants.push(participant.verify_with(options)?);
             }
             _ => return Err(ParserError::InvalidComponent),
         };
@@ -100,7 +321,7 @@ impl ComponentMut for IcalTodo<false> {
         Ok(())
     }
 
-    fn verify(self) -> Result<IcalTodo<true>, ParserError> {
+    fn verify_with(self, options: &VerifyOptions) -> Result<IcalTodo<true>, ParserError> {
         if self
             .get_property("UID")
             .and_then(|prop| prop.value.as_ref())
@@ -117,13 +338,60 @@ impl ComponentMut for IcalTodo<false> {
             return Err(ParserError::MissingProperty("DTSTAMP"));
         }
 
+        if self.get_property("DUE").is_some() && self.get_property("DURATION").is_some() {
+            return Err(ParserError::PropertyConflict(
+                "both DUE and DURATION are defined",
+            ));
+        }
+
         if let Some(prop) = self.get_property("DURATION") {
             Option::<chrono::Duration>::try_from(prop)?;
+            if self.get_property("DTSTART").is_none() {
+                return Err(ParserError::MissingProperty("DTSTART"));
+            }
+        }
+
+        if let (Some(dtstart), Some(due)) = (self.get_property("DTSTART"), self.get_property("DUE"))
+        {
+            let timezones: HashMap<_, _> = [dtstart, due]
+                .into_iter()
+                .filter_map(Property::get_tzid)
+                .map(|tzid| (tzid.to_owned(), OlsonResolver.resolve(tzid)))
+                .collect();
+            // TZIDs only defined by the VTIMEZONEs of the calendar can't be compared here
+            if timezones.values().all(Option::is_some)
+                && let (Ok(dtstart), Ok(due)) = (
+                    CalDateOrDateTime::parse_prop(dtstart, &timezones),
+                    CalDateOrDateTime::parse_prop(due, &timezones),
+                )
+                && due.utc() < dtstart.utc()
+            {
+                return Err(ParserError::PropertyConflict(
+                    "DUE must not be earlier than DTSTART",
+                ));
+            }
+        }
+
+        if options.check_values {
+            parse_bounded(&self, "PERCENT-COMPLETE", 0..=100)?;
+            parse_bounded(&self, "PRIORITY", 0..=9)?;
+            if let Some(prop) = self.get_property("COMPLETED") {
+                CalDateTime::parse(prop.value.as_deref().unwrap_or_default(), None)?;
+            }
+        }
+
+        if options.check_values {
+            verify_publishing_properties(&self)?;
+            verify_metadata_properties(&self)?;
+            verify_participation_properties(&self)?;
         }
 
         let verified = IcalTodo {
             properties: self.properties,
             alarms: self.alarms,
+            locations: self.locations,
+            resources: self.resources,
+            participants: self.participants,
         };
 
         #[cfg(feature = "test")]
@@ -136,6 +404,17 @@ impl ComponentMut for IcalTodo<false> {
             verified.get_due();
             verified.get_duration();
             verified.get_rrule();
+            verified.get_status();
+            verified.get_percent_complete();
+            verified.get_priority();
+            verified.get_completed();
+            verified.get_structured_data();
+            verified.get_styled_descriptions();
+            verified.get_color();
+            verified.get_images();
+            verified.get_conferences();
+            verified.get_organizer();
+            verified.get_attendees();
         }
 
         Ok(verified)
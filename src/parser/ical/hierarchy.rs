@@ -0,0 +1,345 @@
+//! Build task trees from the RELATED-TO properties of to-dos.
+//!
+//! A relation can be stated by either side: a subtask refers to its parent with
+//! `RELATED-TO;RELTYPE=PARENT` (the default relation type) and a parent can refer to its subtasks
+//! with `RELATED-TO;RELTYPE=CHILD`.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use ical::parser::ical::hierarchy::TodoHierarchy;
+//!
+//! let input = "BEGIN:VCALENDAR\r
+//! BEGIN:VTODO\r
+//! UID:move\r
+//! DTSTAMP:20250101T000000Z\r
+//! END:VTODO\r
+//! BEGIN:VTODO\r
+//! UID:pack\r
+//! DTSTAMP:20250101T000000Z\r
+//! RELATED-TO:move\r
+//! END:VTODO\r
+//! END:VCALENDAR\r
+//! ";
+//! let calendar = ical::IcalParser::new(input.as_bytes()).next().unwrap().unwrap();
+//!
+//! let hierarchy = TodoHierarchy::new(&calendar.todos);
+//! assert_eq!(hierarchy.roots(), ["move"]);
+//! assert_eq!(hierarchy.children("move"), ["pack"]);
+//! assert!(hierarchy.issues().is_empty());
+//! ```
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::{
+    ical_param,
+    parser::{
+        Component,
+        ical::component::{CalendarInnerData, IcalCalendarObject, IcalTodo},
+    },
+    property::Property,
+};
+
+/// The RELTYPE parameter of a RELATED-TO property.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RelationType {
+    Parent,
+    Child,
+    Sibling,
+    Other(String),
+}
+
+impl From<&str> for RelationType {
+    fn from(value: &str) -> Self {
+        match value.to_uppercase().as_str() {
+            "PARENT" => Self::Parent,
+            "CHILD" => Self::Child,
+            "SIBLING" => Self::Sibling,
+            _ => Self::Other(value.to_owned()),
+        }
+    }
+}
+
+impl RelationType {
+    fn of(prop: &Property) -> Self {
+        prop.get_param("RELTYPE").map_or(Self::Parent, Self::from)
+    }
+}
+
+/// A problem found while building a `TodoHierarchy`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HierarchyIssue {
+    /// A RELATED-TO refers to a to-do that isn't part of the hierarchy.
+    MissingRelative {
+        uid: String,
+        related: String,
+        reltype: RelationType,
+    },
+    /// A to-do has more than one parent. The first one is used.
+    MultipleParents { uid: String, parents: Vec<String> },
+    /// The to-dos are their own ancestors, starting with the smallest UID.
+    Cycle(Vec<String>),
+}
+
+#[derive(Debug, Clone, thiserror::Error, PartialEq, Eq)]
+pub enum HierarchyError {
+    #[error("unknown to-do {0}")]
+    UnknownTodo(String),
+    #[error("{0} can't become a subtask of itself or its descendants")]
+    Cycle(String),
+}
+
+/// The parent, child and sibling relations between to-dos.
+///
+/// To-dos sharing a UID, like the overrides of a recurring to-do, are represented by the first
+/// one.
+#[derive(Debug, Clone)]
+pub struct TodoHierarchy<'t> {
+    todos: BTreeMap<&'t str, &'t IcalTodo>,
+    parents: BTreeMap<&'t str, &'t str>,
+    children: BTreeMap<&'t str, Vec<&'t str>>,
+    siblings: BTreeMap<&'t str, BTreeSet<&'t str>>,
+    issues: Vec<HierarchyIssue>,
+}
+
+impl<'t> TodoHierarchy<'t> {
+    pub fn new(todos: impl IntoIterator<Item = &'t IcalTodo>) -> Self {
+        let mut all: BTreeMap<&str, &IcalTodo> = BTreeMap::new();
+        for todo in todos {
+            all.entry(todo.get_uid()).or_insert(todo);
+        }
+
+        let mut hierarchy = Self {
+            todos: all,
+            parents: BTreeMap::new(),
+            children: BTreeMap::new(),
+            siblings: BTreeMap::new(),
+            issues: Vec::new(),
+        };
+        hierarchy.link();
+        hierarchy.find_cycles();
+        hierarchy
+    }
+
+    /// Build the hierarchy from the to-dos of calendar objects, ignoring other components.
+    pub fn from_objects(objects: impl IntoIterator<Item = &'t IcalCalendarObject>) -> Self {
+        Self::new(
+            objects
+                .into_iter()
+                .filter_map(|object| match object.get_inner() {
+                    CalendarInnerData::Todo(main, _) => Some(main),
+                    _ => None,
+                }),
+        )
+    }
+
+    fn link(&mut self) {
+        let mut candidates: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+        for (&uid, todo) in &self.todos {
+            for prop in todo.get_named_properties("RELATED-TO") {
+                let Some(related) = prop.value.as_deref() else {
+                    continue;
+                };
+                let reltype = RelationType::of(prop);
+                let Some((&related, _)) = self.todos.get_key_value(related) else {
+                    self.issues.push(HierarchyIssue::MissingRelative {
+                        uid: uid.to_owned(),
+                        related: related.to_owned(),
+                        reltype,
+                    });
+                    continue;
+                };
+                match reltype {
+                    RelationType::Parent => candidates.entry(uid).or_default().push(related),
+                    RelationType::Child => candidates.entry(related).or_default().push(uid),
+                    RelationType::Sibling => {
+                        self.siblings.entry(uid).or_default().insert(related);
+                        self.siblings.entry(related).or_default().insert(uid);
+                    }
+                    RelationType::Other(_) => {}
+                }
+            }
+        }
+
+        for (uid, candidates) in candidates {
+            let mut parents = Vec::new();
+            for parent in candidates {
+                if !parents.contains(&parent) {
+                    parents.push(parent);
+                }
+            }
+            if parents.len() > 1 {
+                self.issues.push(HierarchyIssue::MultipleParents {
+                    uid: uid.to_owned(),
+                    parents: parents.iter().map(|parent| (*parent).to_owned()).collect(),
+                });
+            }
+            self.parents.insert(uid, parents[0]);
+            self.children.entry(parents[0]).or_default().push(uid);
+        }
+    }
+
+    fn find_cycles(&mut self) {
+        let mut cycles = BTreeSet::new();
+        for &uid in self.todos.keys() {
+            let mut path = vec![uid];
+            while let Some(&parent) = self.parents.get(path[path.len() - 1]) {
+                if let Some(position) = path.iter().position(|&ancestor| ancestor == parent) {
+                    let mut cycle = path.split_off(position);
+                    let smallest = (0..cycle.len()).min_by_key(|&i| cycle[i]).unwrap_or(0);
+                    cycle.rotate_left(smallest);
+                    cycles.insert(cycle);
+                    break;
+                }
+                path.push(parent);
+            }
+        }
+        self.issues.extend(
+            cycles
+                .into_iter()
+                .map(|cycle| HierarchyIssue::Cycle(cycle.into_iter().map(str::to_owned).collect())),
+        );
+    }
+
+    pub fn get(&self, uid: &str) -> Option<&'t IcalTodo> {
+        self.todos.get(uid).copied()
+    }
+
+    pub fn parent(&self, uid: &str) -> Option<&'t str> {
+        self.parents.get(uid).copied()
+    }
+
+    pub fn children(&self, uid: &str) -> &[&'t str] {
+        self.children.get(uid).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn siblings(&self, uid: &str) -> Vec<&'t str> {
+        self.siblings
+            .get(uid)
+            .map(|siblings| siblings.iter().copied().collect())
+            .unwrap_or_default()
+    }
+
+    /// The to-dos without a parent. To-dos in a cycle can't be reached from here.
+    pub fn roots(&self) -> Vec<&'t str> {
+        self.todos
+            .keys()
+            .filter(|uid| !self.parents.contains_key(*uid))
+            .copied()
+            .collect()
+    }
+
+    /// All subtasks below `uid`, depth first.
+    pub fn descendants(&self, uid: &str) -> Vec<&'t str> {
+        let mut descendants = Vec::new();
+        let mut stack: Vec<_> = self.children(uid).iter().rev().copied().collect();
+        while let Some(child) = stack.pop() {
+            if child == uid || descendants.contains(&child) {
+                continue;
+            }
+            descendants.push(child);
+            stack.extend(self.children(child).iter().rev());
+        }
+        descendants
+    }
+
+    pub fn issues(&self) -> &[HierarchyIssue] {
+        &self.issues
+    }
+}
+
+/// Whether `prop` relates its to-do to `uid` as a parent, seen from the to-do itself.
+fn is_parent_relation(prop: &Property, uid: &str) -> bool {
+    prop.name == "RELATED-TO"
+        && prop.value.as_deref() == Some(uid)
+        && RelationType::of(prop) == RelationType::Parent
+}
+
+/// Whether `prop` relates its to-do to `uid` as a child.
+fn is_child_relation(prop: &Property, uid: &str) -> bool {
+    prop.name == "RELATED-TO"
+        && prop.value.as_deref() == Some(uid)
+        && RelationType::of(prop) == RelationType::Child
+}
+
+fn reparent_todos(
+    todos: &mut [&mut IcalTodo],
+    child: &str,
+    parent: Option<&str>,
+) -> Result<(), HierarchyError> {
+    let hierarchy = TodoHierarchy::new(todos.iter().map(|todo| &**todo));
+    if hierarchy.get(child).is_none() {
+        return Err(HierarchyError::UnknownTodo(child.to_owned()));
+    }
+    if let Some(parent) = parent {
+        if hierarchy.get(parent).is_none() {
+            return Err(HierarchyError::UnknownTodo(parent.to_owned()));
+        }
+        if parent == child || hierarchy.descendants(child).contains(&parent) {
+            return Err(HierarchyError::Cycle(child.to_owned()));
+        }
+    }
+    let old_parents: Vec<String> = todos
+        .iter()
+        .filter(|todo| todo.get_uid() == child)
+        .flat_map(|todo| todo.get_named_properties("RELATED-TO"))
+        .filter(|prop| RelationType::of(prop) == RelationType::Parent)
+        .filter_map(|prop| prop.value.clone())
+        .collect();
+
+    for todo in todos.iter_mut() {
+        let uid = todo.get_uid().to_owned();
+        if uid == child {
+            todo.properties.retain(|prop| {
+                !old_parents
+                    .iter()
+                    .any(|old_parent| is_parent_relation(prop, old_parent))
+            });
+            if let Some(parent) = parent {
+                todo.properties.push(Property {
+                    name: "RELATED-TO".to_owned(),
+                    params: vec![ical_param!("RELTYPE", "PARENT")],
+                    value: Some(parent.to_owned()),
+                });
+            }
+        } else {
+            todo.properties
+                .retain(|prop| !is_child_relation(prop, child));
+            if parent == Some(uid.as_str()) {
+                todo.properties.push(Property {
+                    name: "RELATED-TO".to_owned(),
+                    params: vec![ical_param!("RELTYPE", "CHILD")],
+                    value: Some(child.to_owned()),
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Move the to-do `child` below `parent`, or make it a root if `parent` is `None`.
+///
+/// The RELATED-TO properties are rewritten on both sides: the child refers to its new parent, and
+/// the new parent refers to the child while the old ones don't anymore. All to-dos sharing a UID
+/// are changed alike.
+pub fn reparent(
+    todos: &mut [IcalTodo],
+    child: &str,
+    parent: Option<&str>,
+) -> Result<(), HierarchyError> {
+    reparent_todos(&mut todos.iter_mut().collect::<Vec<_>>(), child, parent)
+}
+
+/// Like `reparent`, but for the to-dos of calendar objects including their overrides.
+pub fn reparent_objects(
+    objects: &mut [IcalCalendarObject],
+    child: &str,
+    parent: Option<&str>,
+) -> Result<(), HierarchyError> {
+    let mut todos: Vec<_> = objects
+        .iter_mut()
+        .filter_map(IcalCalendarObject::get_todos_mut)
+        .flatten()
+        .collect();
+    reparent_todos(&mut todos, child, parent)
+}
@@ -0,0 +1,427 @@
+//! Extract scheduling messages from emails and wrap them into emails as defined in
+//! [RFC6047](https://tools.ietf.org/html/rfc6047).
+//!
+//! Only the MIME structure is handled: nested multipart bodies, base64 and quoted-printable
+//! transfer encodings, and the charset of text parts. Headers like `Subject` or encoded words
+//! are left to the caller.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use ical::parser::{ParserLimits, ical::imip};
+//!
+//! let input = "BEGIN:VCALENDAR\r
+//! METHOD:REQUEST\r
+//! BEGIN:VEVENT\r
+//! UID:1\r
+//! DTSTART:20250106T100000Z\r
+//! ORGANIZER:mailto:alice@example.com\r
+//! ATTENDEE:mailto:bob@example.com\r
+//! END:VEVENT\r
+//! END:VCALENDAR\r
+//! ";
+//! let calendar = ical::IcalParser::new(input.as_bytes()).next().unwrap().unwrap();
+//! let body = imip::build(&calendar, "You are invited", "boundary-1").unwrap();
+//!
+//! let email = format!("From: alice@example.com\r\nTo: bob@example.com\r\n{body}");
+//! let parts = imip::parse(email.as_bytes(), &ParserLimits::default()).unwrap();
+//! let part = parts[0].as_ref().unwrap();
+//! assert_eq!(part.method.as_deref(), Some("REQUEST"));
+//! assert_eq!(part.calendar.events[0].get_uid(), "1");
+//! ```
+
+use crate::{
+    charset::Charset,
+    generator::Emitter,
+    parser::{
+        Component, ParserError, ParserLimits, VerifyOptions, ical::IcalParser,
+        ical::component::IcalCalendar,
+    },
+};
+
+/// The maximum nesting depth of multipart bodies, deeper messages are rejected.
+pub const MAX_MULTIPART_DEPTH: usize = 32;
+
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum ImipError {
+    #[error("invalid MIME message: {0}")]
+    InvalidMime(&'static str),
+    #[error(transparent)]
+    Parser(#[from] ParserError),
+}
+
+/// A calendar found in a `text/calendar` part of an email.
+#[derive(Debug, Clone)]
+pub struct CalendarPart {
+    /// The METHOD of the calendar, or the `method` parameter of the part if it has none.
+    pub method: Option<String>,
+    pub calendar: IcalCalendar,
+}
+
+/// The calendars of every `text/calendar` part of an RFC5322 `message`, in order.
+///
+/// Emails usually come from untrusted senders, so every calendar is parsed with `limits`. Like
+/// scheduling messages, the events may leave out DTSTART. A calendar that fails to parse doesn't
+/// affect the other parts, only a malformed MIME structure fails the whole message.
+pub fn parse(
+    message: &[u8],
+    limits: &ParserLimits,
+) -> Result<Vec<Result<CalendarPart, ParserError>>, ImipError> {
+    let mut parts = Vec::new();
+    parse_entity(message, limits, &mut parts, 0)?;
+    Ok(parts)
+}
+
+fn parse_entity(
+    entity: &[u8],
+    limits: &ParserLimits,
+    parts: &mut Vec<Result<CalendarPart, ParserError>>,
+    depth: usize,
+) -> Result<(), ImipError> {
+    let (headers, body) = split_headers(entity);
+    let content_type = header(&headers, "Content-Type")
+        .map(ContentType::parse)
+        .unwrap_or_default();
+
+    if content_type.mime_type.starts_with("multipart/") {
+        if depth == MAX_MULTIPART_DEPTH {
+            return Err(ImipError::InvalidMime("too deeply nested multipart"));
+        }
+        let boundary = content_type
+            .param("boundary")
+            .ok_or(ImipError::InvalidMime("missing multipart boundary"))?;
+        for part in split_multipart(body, boundary)? {
+            parse_entity(part, limits, parts, depth + 1)?;
+        }
+        return Ok(());
+    }
+    if content_type.mime_type != "text/calendar" {
+        return Ok(());
+    }
+
+    let body = match header(&headers, "Content-Transfer-Encoding")
+        .map(|encoding| encoding.trim().to_lowercase())
+        .as_deref()
+    {
+        Some("base64") => decode_base64(body)?,
+        Some("quoted-printable") => decode_quoted_printable(body),
+        _ => body.to_vec(),
+    };
+    let text = content_type
+        .param("charset")
+        .and_then(Charset::from_label)
+        .unwrap_or(Charset::Utf8)
+        .decode(&body);
+
+    let options = VerifyOptions {
+        require_dtstart: false,
+        ..VerifyOptions::lenient()
+    };
+    let parser =
+        IcalParser::with_limits(text.as_bytes(), limits.clone()).with_verify_options(options);
+    for calendar in parser {
+        let calendar = match calendar {
+            Ok(calendar) => calendar,
+            Err(err) => {
+                // The rest of the part can't be parsed after an error
+                parts.push(Err(err));
+                break;
+            }
+        };
+        let method = calendar
+            .get_property("METHOD")
+            .and_then(|prop| prop.value.clone())
+            .or_else(|| content_type.param("method").map(str::to_uppercase));
+        parts.push(Ok(CalendarPart { method, calendar }));
+    }
+    Ok(())
+}
+
+/// Wrap a scheduling message into a MIME entity with a `text` alternative.
+///
+/// The result starts with the `MIME-Version` and `Content-Type` headers, so the caller only needs
+/// to put the other headers like `From`, `To` and `Subject` in front of it. `boundary` must not
+/// occur in `text`.
+pub fn build<C: Component + Emitter>(
+    calendar: &C,
+    text: &str,
+    boundary: &str,
+) -> Result<String, ImipError> {
+    let method = calendar
+        .get_property("METHOD")
+        .and_then(|prop| prop.value.as_deref())
+        .ok_or(ParserError::MissingProperty("METHOD"))?;
+
+    let mut body = format!(
+        "MIME-Version: 1.0\r\n\
+        Content-Type: multipart/alternative; boundary=\"{boundary}\"\r\n\
+        \r\n\
+        --{boundary}\r\n\
+        Content-Type: text/plain; charset=UTF-8\r\n\
+        Content-Transfer-Encoding: quoted-printable\r\n\
+        \r\n\
+        {}\r\n\
+        --{boundary}\r\n\
+        Content-Type: text/calendar; charset=UTF-8; method={method}\r\n\
+        Content-Transfer-Encoding: base64\r\n\
+        \r\n",
+        encode_quoted_printable(text)
+    );
+    let encoded = encode_base64(calendar.generate().as_bytes());
+    for line in encoded.as_bytes().chunks(76) {
+        body.push_str(std::str::from_utf8(line).expect("base64 is ASCII"));
+        body.push_str("\r\n");
+    }
+    body.push_str(&format!("--{boundary}--\r\n"));
+    Ok(body)
+}
+
+/// A parsed `Content-Type` header.
+#[derive(Debug)]
+struct ContentType {
+    /// The lowercase media type like `text/calendar`.
+    mime_type: String,
+    /// The parameters with lowercase names and unquoted values.
+    params: Vec<(String, String)>,
+}
+
+impl Default for ContentType {
+    fn default() -> Self {
+        Self {
+            mime_type: "text/plain".to_owned(),
+            params: Vec::new(),
+        }
+    }
+}
+
+impl ContentType {
+    fn parse(value: &str) -> Self {
+        let mut segments = split_params(value).into_iter();
+        let mime_type = segments
+            .next()
+            .map(|mime_type| mime_type.trim().to_lowercase())
+            .unwrap_or_default();
+        let params = segments
+            .filter_map(|segment| {
+                let (name, value) = segment.split_once('=')?;
+                let value = value.trim();
+                let value = value
+                    .strip_prefix('"')
+                    .and_then(|value| value.strip_suffix('"'))
+                    .unwrap_or(value);
+                Some((name.trim().to_lowercase(), value.replace("\\\"", "\"")))
+            })
+            .collect();
+        Self { mime_type, params }
+    }
+
+    fn param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Split a header value at the semicolons outside of quotes.
+fn split_params(value: &str) -> Vec<&str> {
+    let mut segments = Vec::new();
+    let mut start = 0;
+    let mut in_quotes = false;
+    let mut previous = None;
+    for (pos, char) in value.char_indices() {
+        match char {
+            '"' if previous != Some('\\') => in_quotes = !in_quotes,
+            ';' if !in_quotes => {
+                segments.push(&value[start..pos]);
+                start = pos + 1;
+            }
+            _ => {}
+        }
+        previous = Some(char);
+    }
+    segments.push(&value[start..]);
+    segments
+}
+
+/// Split an entity into its unfolded headers and its body.
+fn split_headers(entity: &[u8]) -> (Vec<(String, String)>, &[u8]) {
+    let mut headers: Vec<(String, String)> = Vec::new();
+    let mut rest = entity;
+    while !rest.is_empty() {
+        let end = rest.iter().position(|byte| *byte == b'\n');
+        let (line, next) = match end {
+            Some(end) => (&rest[..end], &rest[end + 1..]),
+            None => (rest, &rest[rest.len()..]),
+        };
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        rest = next;
+        if line.is_empty() {
+            break;
+        }
+
+        let line = String::from_utf8_lossy(line);
+        match (line.starts_with([' ', '\t']), headers.last_mut()) {
+            (true, Some((_, value))) => {
+                value.push(' ');
+                value.push_str(line.trim());
+            }
+            _ => {
+                if let Some((name, value)) = line.split_once(':') {
+                    headers.push((name.trim().to_owned(), value.trim().to_owned()));
+                }
+            }
+        }
+    }
+    (headers, rest)
+}
+
+fn header<'h>(headers: &'h [(String, String)], name: &str) -> Option<&'h str> {
+    headers
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.as_str())
+}
+
+/// The parts of a multipart body between the delimiter lines of `boundary`.
+fn split_multipart<'b>(body: &'b [u8], boundary: &str) -> Result<Vec<&'b [u8]>, ImipError> {
+    let delimiter = format!("--{boundary}");
+    let mut parts = Vec::new();
+    let mut start = None;
+    let mut pos = 0;
+    while pos < body.len() {
+        let end = body[pos..]
+            .iter()
+            .position(|byte| *byte == b'\n')
+            .map_or(body.len(), |end| pos + end + 1);
+        // The delimiter may only be followed by `--` and whitespace, so `--abc-2` doesn't
+        // close a part of `abc`
+        let line = body[pos..end].trim_ascii_end();
+        if let Some(rest) = line
+            .strip_prefix(delimiter.as_bytes())
+            .filter(|rest| rest.is_empty() || *rest == b"--")
+        {
+            if let Some(start) = start {
+                // The line break before the delimiter belongs to the delimiter
+                let part = &body[start..pos];
+                let part = part.strip_suffix(b"\n").unwrap_or(part);
+                parts.push(part.strip_suffix(b"\r").unwrap_or(part));
+            }
+            if !rest.is_empty() {
+                return Ok(parts);
+            }
+            start = Some(end);
+        }
+        pos = end;
+    }
+    Err(ImipError::InvalidMime("missing closing multipart boundary"))
+}
+
+fn decode_base64(body: &[u8]) -> Result<Vec<u8>, ImipError> {
+    let mut decoded = Vec::with_capacity(body.len() / 4 * 3);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for byte in body {
+        let value = match byte {
+            b'=' => break,
+            byte if byte.is_ascii_whitespace() => continue,
+            byte => BASE64
+                .iter()
+                .position(|char| char == byte)
+                .ok_or(ImipError::InvalidMime("invalid base64"))?,
+        };
+        buffer = buffer << 6 | value as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            decoded.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    Ok(decoded)
+}
+
+fn encode_base64(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let buffer = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |buffer, (index, byte)| {
+                buffer | (*byte as u32) << (16 - 8 * index)
+            });
+        for index in 0..4 {
+            if index <= chunk.len() {
+                encoded.push(BASE64[(buffer >> (18 - 6 * index) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+/// Decode quoted-printable data, keeping invalid escapes as they are.
+fn decode_quoted_printable(body: &[u8]) -> Vec<u8> {
+    let mut decoded = Vec::with_capacity(body.len());
+    let mut pos = 0;
+    while pos < body.len() {
+        if body[pos] != b'=' {
+            decoded.push(body[pos]);
+            pos += 1;
+            continue;
+        }
+        let rest = &body[pos + 1..];
+        if let Some(rest) = rest
+            .strip_prefix(b"\r\n")
+            .or_else(|| rest.strip_prefix(b"\n"))
+        {
+            // A soft line break
+            pos = body.len() - rest.len();
+        } else if let Some(byte) = rest
+            .get(..2)
+            // `from_str_radix` also accepts a sign like in `=+1`
+            .filter(|hex| hex.iter().all(u8::is_ascii_hexdigit))
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+        {
+            decoded.push(byte);
+            pos += 3;
+        } else {
+            decoded.push(b'=');
+            pos += 1;
+        }
+    }
+    decoded
+}
+
+/// Encode text as quoted-printable with CRLF line breaks and lines of at most 76 characters.
+fn encode_quoted_printable(text: &str) -> String {
+    let mut encoded = String::with_capacity(text.len());
+    for (index, line) in text.lines().enumerate() {
+        if index > 0 {
+            encoded.push_str("\r\n");
+        }
+        let mut length = 0;
+        let bytes = line.as_bytes();
+        for (pos, byte) in bytes.iter().enumerate() {
+            let is_last = pos + 1 == bytes.len();
+            let escaped = match byte {
+                b' ' | b'\t' if is_last => format!("={byte:02X}"),
+                b'=' => "=3D".to_owned(),
+                b' '..=b'~' | b'\t' => (*byte as char).to_string(),
+                _ => format!("={byte:02X}"),
+            };
+            // Keep room for the `=` of a soft line break
+            if length + escaped.len() > 75 {
+                encoded.push_str("=\r\n");
+                length = 0;
+            }
+            length += escaped.len();
+            encoded.push_str(&escaped);
+        }
+    }
+    encoded
+}
@@ -0,0 +1,662 @@
+//! Generate scheduling messages as defined in [RFC5546](https://tools.ietf.org/html/rfc5546).
+//!
+//! Messages are calendar objects with a METHOD, built from the calendar object of the organizer
+//! or a copy of it. They only contain the properties their METHOD allows and get a new DTSTAMP.
+//! CANCEL and ADD change the recurrence set, so they also update the calendar object of the
+//! organizer and increment its SEQUENCE. The replies of attendees are applied to the calendar
+//! object of the organizer with [`apply_reply`].
+//!
+//! # Examples
+//!
+//! ```rust
+//! use ical::generator::Emitter;
+//! use ical::parser::Component;
+//! use ical::parser::ical::itip;
+//! use ical::types::OlsonResolver;
+//!
+//! let input = "BEGIN:VCALENDAR\r
+//! BEGIN:VEVENT\r
+//! UID:1\r
+//! DTSTAMP:20250101T000000Z\r
+//! DTSTART:20250106T100000Z\r
+//! ORGANIZER:mailto:alice@example.com\r
+//! ATTENDEE:mailto:bob@example.com\r
+//! SUMMARY:Planning\r
+//! END:VEVENT\r
+//! END:VCALENDAR\r
+//! ";
+//! let mut object = ical::IcalObjectParser::new(input.as_bytes())
+//!     .next()
+//!     .unwrap()
+//!     .unwrap();
+//!
+//! let request = itip::request(&object).unwrap();
+//! assert_eq!(request.get_property("METHOD").unwrap().value.as_deref(), Some("REQUEST"));
+//!
+//! let cancel = itip::cancel(&mut object, None, &OlsonResolver).unwrap();
+//! assert!(cancel.generate().contains("STATUS:CANCELLED\r\n"));
+//! ```
+
+use chrono::Utc;
+
+use crate::{
+    ical_property,
+    parser::{
+        Component, ComponentMut, ParserError, VerifyOptions,
+        ical::{
+            component::{
+                CalendarInnerData, IcalCalendarObject, IcalEvent, IcalJournal, IcalTimeZone,
+                IcalTodo, find_override, increment_sequence, instance_at, require_occurrence,
+                same_address,
+            },
+            recurrence::{Recurring, resolve_timezones},
+        },
+    },
+    property::Property,
+    types::{CalDateOrDateTime, CalDateTime, TimezoneResolver},
+};
+
+/// The properties of a CANCEL message.
+const CANCEL: &[&str] = &[
+    "UID",
+    "SEQUENCE",
+    "ORGANIZER",
+    "ATTENDEE",
+    "RECURRENCE-ID",
+    "DTSTART",
+    "DTEND",
+    "DURATION",
+    "DUE",
+    "SUMMARY",
+    "COMMENT",
+    "STATUS",
+];
+
+/// The properties of a REFRESH message.
+const REFRESH: &[&str] = &["UID", "ORGANIZER", "ATTENDEE", "RECURRENCE-ID", "COMMENT"];
+
+/// The properties of a DECLINECOUNTER message.
+const DECLINECOUNTER: &[&str] = &[
+    "UID",
+    "SEQUENCE",
+    "ORGANIZER",
+    "ATTENDEE",
+    "RECURRENCE-ID",
+    "COMMENT",
+];
+
+/// A component that can be scheduled.
+trait Scheduled: Component<Unverified: ComponentMut<Verified = Self> + Default> + Clone {
+    fn into_inner(main: Self, overrides: Vec<Self>) -> CalendarInnerData;
+}
+
+impl Scheduled for IcalEvent {
+    fn into_inner(main: Self, overrides: Vec<Self>) -> CalendarInnerData {
+        CalendarInnerData::Event(main, overrides)
+    }
+}
+
+impl Scheduled for IcalTodo {
+    fn into_inner(main: Self, overrides: Vec<Self>) -> CalendarInnerData {
+        CalendarInnerData::Todo(main, overrides)
+    }
+}
+
+impl Scheduled for IcalJournal {
+    fn into_inner(main: Self, overrides: Vec<Self>) -> CalendarInnerData {
+        CalendarInnerData::Journal(main, overrides)
+    }
+}
+
+/// Invite the attendees to the event or to-do, or send them its current state.
+///
+/// The message contains every component with all of its attendees and the current SEQUENCE,
+/// which the organizer increments when rescheduling.
+pub fn request(object: &IcalCalendarObject) -> Result<IcalCalendarObject, ParserError> {
+    let inner = match object.get_inner() {
+        CalendarInnerData::Event(main, overrides) => request_inner(main, overrides)?,
+        CalendarInnerData::Todo(main, overrides) => request_inner(main, overrides)?,
+        CalendarInnerData::Journal(..) => return Err(ParserError::InvalidComponent),
+    };
+    Ok(message(object, "REQUEST", inner))
+}
+
+fn request_inner<C: Scheduled>(
+    main: &C,
+    overrides: &[C],
+) -> Result<CalendarInnerData, ParserError> {
+    require_organizer(main)?;
+    if main.get_property("ATTENDEE").is_none() {
+        return Err(ParserError::MissingProperty("ATTENDEE"));
+    }
+    let main = stamp(main.clone().mutable(), true)?;
+    let overrides = overrides
+        .iter()
+        .map(|component| stamp(component.clone().mutable(), true))
+        .collect::<Result<_, _>>()?;
+    Ok(C::into_inner(main, overrides))
+}
+
+/// Cancel the whole series, or only the instance with `recurrence_id`.
+///
+/// The organizer's `object` is updated as well: a cancelled series or override gets
+/// `STATUS:CANCELLED`, and other cancelled instances are excluded with an EXDATE. Instances are
+/// matched by time, and a `recurrence_id` that isn't an instance of the series fails with
+/// [`ParserError::InvalidValue`]. The `object` is only changed if the message can be built.
+pub fn cancel(
+    object: &mut IcalCalendarObject,
+    recurrence_id: Option<&Property>,
+    resolver: &impl TimezoneResolver,
+) -> Result<IcalCalendarObject, ParserError> {
+    let timezones = object.get_timezones().to_vec();
+    let inner = match object.get_inner_mut() {
+        CalendarInnerData::Event(main, overrides) => {
+            cancel_inner(main, overrides, recurrence_id, &timezones, resolver)?
+        }
+        CalendarInnerData::Todo(main, overrides) => {
+            cancel_inner(main, overrides, recurrence_id, &timezones, resolver)?
+        }
+        CalendarInnerData::Journal(main, overrides) => {
+            cancel_inner(main, overrides, recurrence_id, &timezones, resolver)?
+        }
+    };
+    Ok(message(object, "CANCEL", inner))
+}
+
+fn cancel_inner<C: Scheduled + Recurring>(
+    main: &mut C,
+    overrides: &mut Vec<C>,
+    recurrence_id: Option<&Property>,
+    timezones: &[IcalTimeZone],
+    resolver: &impl TimezoneResolver,
+) -> Result<CalendarInnerData, ParserError> {
+    require_organizer(main)?;
+    let cancelled = |component: &C| -> Result<C, ParserError> {
+        let mut component = component.clone().mutable();
+        component.set_property(ical_property!("STATUS", "CANCELLED"));
+        increment_sequence(&mut component);
+        component.verify()
+    };
+
+    let Some(recurrence_id) = recurrence_id else {
+        let updated_main = cancelled(main)?;
+        let updated_overrides = overrides.iter().map(cancelled).collect::<Result<_, _>>()?;
+        let message = stamp(subset(&updated_main, CANCEL), true)?;
+        *main = updated_main;
+        *overrides = updated_overrides;
+        return Ok(C::into_inner(message, vec![]));
+    };
+
+    let timezones = resolve_timezones(
+        std::iter::once(&*main)
+            .chain(overrides.iter())
+            .flat_map(|component| component.get_properties())
+            .chain([recurrence_id]),
+        timezones,
+        resolver,
+    );
+    let start = CalDateOrDateTime::parse_prop(recurrence_id, &timezones)?;
+    let message = match find_override(overrides, &start, &timezones)? {
+        Some(index) => {
+            let updated = cancelled(&overrides[index])?;
+            let message = stamp(subset(&updated, CANCEL), true)?;
+            overrides[index] = updated;
+            message
+        }
+        None => {
+            require_occurrence(main, &start, &timezones)?;
+            let mut updated = main.clone().mutable();
+            updated.add_property(Property {
+                name: "EXDATE".to_owned(),
+                ..recurrence_id.clone()
+            });
+            increment_sequence(&mut updated);
+            let updated = updated.verify()?;
+
+            let mut message = subset(&updated, CANCEL);
+            for name in ["DTEND", "DURATION", "DUE"] {
+                message.remove_property(name);
+            }
+            message.set_property(Property {
+                name: "RECURRENCE-ID".to_owned(),
+                ..recurrence_id.clone()
+            });
+            message.set_property(Property {
+                name: "DTSTART".to_owned(),
+                ..recurrence_id.clone()
+            });
+            message.set_property(ical_property!("STATUS", "CANCELLED"));
+            let message = stamp(message, true)?;
+            *main = updated;
+            message
+        }
+    };
+    Ok(C::into_inner(message, vec![]))
+}
+
+/// Add an instance starting at `start` to a recurring series.
+///
+/// The organizer's `object` gets a matching RDATE, and the message contains the new instance
+/// with its end shifted along with its start.
+pub fn add(
+    object: &mut IcalCalendarObject,
+    start: &Property,
+    resolver: &impl TimezoneResolver,
+) -> Result<IcalCalendarObject, ParserError> {
+    let timezones = object.get_timezones().to_vec();
+    let inner = match object.get_inner_mut() {
+        CalendarInnerData::Event(main, _) => add_inner(main, start, &timezones, resolver)?,
+        CalendarInnerData::Todo(main, _) => add_inner(main, start, &timezones, resolver)?,
+        CalendarInnerData::Journal(main, _) => add_inner(main, start, &timezones, resolver)?,
+    };
+    Ok(message(object, "ADD", inner))
+}
+
+fn add_inner<C: Scheduled>(
+    main: &mut C,
+    start: &Property,
+    timezones: &[IcalTimeZone],
+    resolver: &impl TimezoneResolver,
+) -> Result<CalendarInnerData, ParserError> {
+    require_organizer(main)?;
+    let timezones = resolve_timezones(
+        main.get_properties().iter().chain([start]),
+        timezones,
+        resolver,
+    );
+
+    let mut instance = instance_at(main, start, &timezones)?;
+    let mut updated = main.clone().mutable();
+    updated.add_property(Property {
+        name: "RDATE".to_owned(),
+        ..start.clone()
+    });
+    increment_sequence(&mut updated);
+    let updated = updated.verify()?;
+
+    if let Some(sequence) = updated.get_property("SEQUENCE") {
+        instance.set_property(sequence.clone());
+    }
+    let message = stamp(instance, true)?;
+    *main = updated;
+    Ok(C::into_inner(message, vec![]))
+}
+
+/// Ask the organizer for the current state of the event or to-do on behalf of `attendee`.
+pub fn refresh(
+    object: &IcalCalendarObject,
+    attendee: &str,
+) -> Result<IcalCalendarObject, ParserError> {
+    let inner = match object.get_inner() {
+        CalendarInnerData::Event(main, _) => refresh_inner(main, attendee)?,
+        CalendarInnerData::Todo(main, _) => refresh_inner(main, attendee)?,
+        CalendarInnerData::Journal(..) => return Err(ParserError::InvalidComponent),
+    };
+    Ok(message(object, "REFRESH", inner))
+}
+
+fn refresh_inner<C: Scheduled>(main: &C, attendee: &str) -> Result<CalendarInnerData, ParserError> {
+    require_organizer(main)?;
+    let mut message = subset(main, REFRESH);
+    only_attendee(&mut message, attendee);
+    Ok(C::into_inner(stamp(message, false)?, vec![]))
+}
+
+/// Propose the changes `attendee` made to their copy of the event or to-do to the organizer.
+pub fn counter(
+    proposal: &IcalCalendarObject,
+    attendee: &str,
+) -> Result<IcalCalendarObject, ParserError> {
+    let inner = match proposal.get_inner() {
+        CalendarInnerData::Event(main, overrides) => counter_inner(main, overrides, attendee)?,
+        CalendarInnerData::Todo(main, overrides) => counter_inner(main, overrides, attendee)?,
+        CalendarInnerData::Journal(..) => return Err(ParserError::InvalidComponent),
+    };
+    Ok(message(proposal, "COUNTER", inner))
+}
+
+fn counter_inner<C: Scheduled>(
+    main: &C,
+    overrides: &[C],
+    attendee: &str,
+) -> Result<CalendarInnerData, ParserError> {
+    require_organizer(main)?;
+    if !main
+        .get_named_properties("ATTENDEE")
+        .into_iter()
+        .any(|prop| is_address(prop, attendee))
+    {
+        return Err(ParserError::InvalidValue("ATTENDEE"));
+    }
+    let main = stamp(main.clone().mutable(), true)?;
+    let overrides = overrides
+        .iter()
+        .map(|component| stamp(component.clone().mutable(), true))
+        .collect::<Result<_, _>>()?;
+    Ok(C::into_inner(main, overrides))
+}
+
+/// Decline the counter proposal of `attendee`.
+pub fn decline_counter(
+    object: &IcalCalendarObject,
+    attendee: &str,
+) -> Result<IcalCalendarObject, ParserError> {
+    let inner = match object.get_inner() {
+        CalendarInnerData::Event(main, _) => decline_counter_inner(main, attendee)?,
+        CalendarInnerData::Todo(main, _) => decline_counter_inner(main, attendee)?,
+        CalendarInnerData::Journal(..) => return Err(ParserError::InvalidComponent),
+    };
+    Ok(message(object, "DECLINECOUNTER", inner))
+}
+
+fn decline_counter_inner<C: Scheduled>(
+    main: &C,
+    attendee: &str,
+) -> Result<CalendarInnerData, ParserError> {
+    require_organizer(main)?;
+    let mut message = subset(main, DECLINECOUNTER);
+    only_attendee(&mut message, attendee);
+    Ok(C::into_inner(stamp(message, true)?, vec![]))
+}
+
+/// The ATTENDEE parameter remembering the DTSTAMP of the last REPLY that was applied.
+const REPLY_DTSTAMP: &str = "X-CALENDARSERVER-DTSTAMP";
+
+/// What applying a REPLY did to one instance.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplyChange {
+    /// The calendar user address of the replying attendee.
+    pub attendee: String,
+    /// The RECURRENCE-ID of the instance, or `None` for the whole series.
+    pub recurrence_id: Option<String>,
+    pub outcome: ReplyOutcome,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReplyOutcome {
+    /// The PARTSTAT of the attendee changed, where `created_override` means that the instance
+    /// got its own override for it.
+    Updated {
+        from: Option<String>,
+        to: String,
+        created_override: bool,
+    },
+    /// The reply confirmed the PARTSTAT the attendee already had.
+    Unchanged,
+    /// The reply has a lower SEQUENCE than the instance, or isn't newer than the last reply
+    /// of the attendee, and was ignored.
+    Stale,
+}
+
+/// Apply the REPLY of an attendee to the organizer's `object`.
+///
+/// Each component of the reply updates the PARTSTAT of its single ATTENDEE on the instance with
+/// the same RECURRENCE-ID. An instance without an override gets one, which is created from the
+/// main component like an instance added with [`add`]. A reply without RECURRENCE-ID updates
+/// the attendee in the overrides as well, except for overrides with a newer SEQUENCE or reply. Replies for an older SEQUENCE, and
+/// replies with a DTSTAMP that isn't newer than the last applied one, are ignored. The DTSTAMP
+/// of the last applied reply is kept in the `X-CALENDARSERVER-DTSTAMP` parameter of the ATTENDEE.
+///
+/// RECURRENCE-IDs are compared by time and must be an instance of the recurrence set, otherwise
+/// the reply fails with [`ParserError::InvalidValue`]. The `object` is only changed when every
+/// component of the reply applies.
+pub fn apply_reply(
+    object: &mut IcalCalendarObject,
+    reply: &IcalCalendarObject,
+    resolver: &impl TimezoneResolver,
+) -> Result<Vec<ReplyChange>, ParserError> {
+    if !reply
+        .get_property("METHOD")
+        .and_then(|prop| prop.value.as_deref())
+        .is_some_and(|method| method.eq_ignore_ascii_case("REPLY"))
+    {
+        return Err(ParserError::InvalidValue("METHOD"));
+    }
+    if reply.get_uid() != object.get_uid() {
+        return Err(ParserError::InvalidValue("UID"));
+    }
+
+    let timezones = [object.get_timezones(), reply.get_timezones()].concat();
+    match (object.get_inner_mut(), reply.get_inner()) {
+        (CalendarInnerData::Event(main, overrides), CalendarInnerData::Event(first, rest)) => {
+            apply_reply_inner(main, overrides, first, rest, &timezones, resolver)
+        }
+        (CalendarInnerData::Todo(main, overrides), CalendarInnerData::Todo(first, rest)) => {
+            apply_reply_inner(main, overrides, first, rest, &timezones, resolver)
+        }
+        _ => Err(ParserError::InvalidComponent),
+    }
+}
+
+fn apply_reply_inner<C: Scheduled + Recurring>(
+    main: &mut C,
+    overrides: &mut Vec<C>,
+    first: &C,
+    rest: &[C],
+    timezones: &[IcalTimeZone],
+    resolver: &impl TimezoneResolver,
+) -> Result<Vec<ReplyChange>, ParserError> {
+    let timezones = resolve_timezones(
+        std::iter::once(first)
+            .chain(rest)
+            .chain([&*main])
+            .chain(overrides.iter())
+            .flat_map(|component| component.get_properties()),
+        timezones,
+        resolver,
+    );
+
+    // Only update the object once every component of the reply applies
+    let mut updated_main = main.clone();
+    let mut updated_overrides = overrides.clone();
+    let mut changes = Vec::new();
+    for reply in std::iter::once(first).chain(rest) {
+        let attendee = match reply.get_named_properties("ATTENDEE").as_slice() {
+            [] => return Err(ParserError::MissingProperty("ATTENDEE")),
+            [attendee] => *attendee,
+            _ => return Err(ParserError::InvalidValue("ATTENDEE")),
+        };
+        let address = attendee.value.clone().unwrap_or_default();
+        let recurrence_id = reply.get_property("RECURRENCE-ID");
+
+        let index = match recurrence_id {
+            Some(recurrence_id) => {
+                let start = CalDateOrDateTime::parse_prop(recurrence_id, &timezones)?;
+                let index = find_override(&updated_overrides, &start, &timezones)?;
+                if index.is_none() {
+                    require_occurrence(&updated_main, &start, &timezones)?;
+                }
+                index
+            }
+            None => None,
+        };
+        let (mut target, created_override) = match (recurrence_id, index) {
+            (None, _) => (updated_main.clone().mutable(), false),
+            (Some(_), Some(index)) => (updated_overrides[index].clone().mutable(), false),
+            (Some(recurrence_id), None) => {
+                let start = Property {
+                    name: "DTSTART".to_owned(),
+                    ..recurrence_id.clone()
+                };
+                let mut instance = instance_at(&updated_main, &start, &timezones)?;
+                instance.add_property(recurrence_id.clone());
+                (instance, true)
+            }
+        };
+        let outdated = sequence(reply) < sequence(&target);
+        let Some(current) = target
+            .get_properties_mut()
+            .iter_mut()
+            .find(|prop| prop.name == "ATTENDEE" && is_address(prop, &address))
+        else {
+            return Err(ParserError::InvalidValue("ATTENDEE"));
+        };
+        let reply_dtstamp = reply
+            .get_property("DTSTAMP")
+            .and_then(|prop| prop.value.as_deref());
+        let stale = outdated || already_replied(current, reply_dtstamp)?;
+
+        let from = current.get_param("PARTSTAT").map(str::to_owned);
+        let to = attendee
+            .get_param("PARTSTAT")
+            .unwrap_or("NEEDS-ACTION")
+            .to_uppercase();
+        let outcome = if stale {
+            ReplyOutcome::Stale
+        } else {
+            set_param(current, "PARTSTAT", &to);
+            if let Some(reply_dtstamp) = reply_dtstamp {
+                set_param(current, REPLY_DTSTAMP, reply_dtstamp);
+            }
+            let target = target.verify()?;
+            match (recurrence_id, index) {
+                (None, _) => {
+                    updated_main = target;
+                    // The overrides of the series follow, unless they got a newer SEQUENCE or
+                    // reply of their own
+                    for component in &mut updated_overrides {
+                        if sequence(reply) < sequence(component) {
+                            continue;
+                        }
+                        let mut updated = component.clone().mutable();
+                        let Some(current) = updated
+                            .get_properties_mut()
+                            .iter_mut()
+                            .find(|prop| prop.name == "ATTENDEE" && is_address(prop, &address))
+                        else {
+                            continue;
+                        };
+                        if already_replied(current, reply_dtstamp)? {
+                            continue;
+                        }
+                        set_param(current, "PARTSTAT", &to);
+                        if let Some(reply_dtstamp) = reply_dtstamp {
+                            set_param(current, REPLY_DTSTAMP, reply_dtstamp);
+                        }
+                        *component = updated.verify()?;
+                    }
+                }
+                (Some(_), Some(index)) => updated_overrides[index] = target,
+                (Some(_), None) => updated_overrides.push(target),
+            }
+            if from.as_deref() == Some(to.as_str()) && !created_override {
+                ReplyOutcome::Unchanged
+            } else {
+                ReplyOutcome::Updated {
+                    from,
+                    to,
+                    created_override,
+                }
+            }
+        };
+        changes.push(ReplyChange {
+            attendee: address,
+            recurrence_id: recurrence_id.and_then(|prop| prop.value.clone()),
+            outcome,
+        });
+    }
+    *main = updated_main;
+    *overrides = updated_overrides;
+    Ok(changes)
+}
+
+/// The SEQUENCE of a component, which is 0 if missing.
+fn sequence<C: Component>(component: &C) -> u32 {
+    component
+        .get_property("SEQUENCE")
+        .and_then(|prop| prop.value.as_deref())
+        .and_then(|value| value.parse().ok())
+        .unwrap_or_default()
+}
+
+/// Whether the ATTENDEE `current` already has a reply that isn't older than `reply_dtstamp`.
+fn already_replied(current: &Property, reply_dtstamp: Option<&str>) -> Result<bool, ParserError> {
+    Ok(match (reply_dtstamp, current.get_param(REPLY_DTSTAMP)) {
+        (Some(reply_dtstamp), Some(last)) => {
+            CalDateTime::parse(reply_dtstamp, None)?.utc() <= CalDateTime::parse(last, None)?.utc()
+        }
+        _ => false,
+    })
+}
+
+/// Replace the values of the parameter `name`.
+fn set_param(prop: &mut Property, name: &str, value: &str) {
+    prop.params.retain(|(key, _)| key != name);
+    prop.params.push((name.to_owned(), vec![value.to_owned()]));
+}
+
+/// Wrap the components of a message into a calendar object with `method`.
+fn message(
+    object: &IcalCalendarObject,
+    method: &str,
+    inner: CalendarInnerData,
+) -> IcalCalendarObject {
+    let mut properties: Vec<_> = object
+        .get_properties()
+        .iter()
+        .filter(|prop| prop.name != "METHOD")
+        .cloned()
+        .collect();
+    properties.push(ical_property!("METHOD", method));
+    IcalCalendarObject::from_parts(properties, inner, object.get_timezones().to_vec())
+}
+
+fn require_organizer<C: Component>(component: &C) -> Result<(), ParserError> {
+    if component
+        .get_property("ORGANIZER")
+        .and_then(|prop| prop.value.as_ref())
+        .is_none()
+    {
+        return Err(ParserError::MissingProperty("ORGANIZER"));
+    }
+    Ok(())
+}
+
+/// A component with only the properties called `names`.
+fn subset<C: Scheduled>(component: &C, names: &[&str]) -> C::Unverified {
+    let mut subset = C::Unverified::default();
+    for prop in component.get_properties() {
+        if names.contains(&prop.name.as_str()) {
+            subset.add_property(prop.clone());
+        }
+    }
+    subset
+}
+
+/// Replace the attendees of a component with `attendee`, keeping its parameters if present.
+fn only_attendee<C: ComponentMut>(component: &mut C, attendee: &str) {
+    let prop = component
+        .get_named_properties("ATTENDEE")
+        .into_iter()
+        .find(|prop| is_address(prop, attendee))
+        .cloned()
+        .unwrap_or_else(|| ical_property!("ATTENDEE", attendee));
+    component.set_property(prop);
+}
+
+/// Whether a calendar user address property refers to `address`, ignoring a `mailto:` prefix.
+fn is_address(prop: &Property, address: &str) -> bool {
+    prop.value
+        .as_deref()
+        .is_some_and(|value| same_address(value, address))
+}
+
+/// Set the DTSTAMP of a message component and verify it.
+///
+/// Messages that require a SEQUENCE get `SEQUENCE:0` if the component has none.
+fn stamp<C: ComponentMut>(mut component: C, sequence: bool) -> Result<C::Verified, ParserError> {
+    component.set_property(ical_property!(
+        "DTSTAMP",
+        CalDateTime::from(Utc::now()).format()
+    ));
+    if !sequence {
+        component.remove_property("SEQUENCE");
+    } else if component.get_property("SEQUENCE").is_none() {
+        component.add_property(ical_property!("SEQUENCE", "0"));
+    }
+    // REFRESH and DECLINECOUNTER only refer to the component and leave out its DTSTART
+    component.verify_with(&VerifyOptions {
+        require_dtstart: false,
+        ..VerifyOptions::lenient()
+    })
+}
@@ -32,11 +32,28 @@
 //! }
 //! ```
 
+pub mod availability;
 pub mod component;
+pub mod hierarchy;
+pub mod imip;
+pub mod itip;
+pub mod recurrence;
+pub mod slots;
+pub mod validator;
 use component::IcalCalendar;
 
-use crate::parser::{ComponentParser, ical::component::IcalCalendarObject};
+use crate::parser::{
+    BorrowedComponentParser, ComponentParser, ical::component::IcalCalendarObject,
+};
 
 /// Reader returning `IcalCalendar` object from a `BufRead`.
 pub type IcalParser<B> = ComponentParser<B, IcalCalendar>;
 pub type IcalObjectParser<B> = ComponentParser<B, IcalCalendarObject>;
+/// Reader returning `BorrowedComponent` calendars from a `&str`.
+pub type BorrowedIcalParser<'a> = BorrowedComponentParser<'a, IcalCalendar>;
+
+/// Reader returning `IcalCalendar` object from an `AsyncBufRead`.
+#[cfg(feature = "tokio")]
+pub type AsyncIcalParser<B> = crate::parser::AsyncComponentParser<B, IcalCalendar>;
+#[cfg(feature = "tokio")]
+pub type AsyncIcalObjectParser<B> = crate::parser::AsyncComponentParser<B, IcalCalendarObject>;
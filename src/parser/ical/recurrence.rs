@@ -0,0 +1,413 @@
+//! Expand recurring components into their instances.
+//!
+//! The instances of a component are defined by its DTSTART, RRULE, RDATE and EXDATE properties.
+//! Instances with a matching RECURRENCE-ID component replace the generated ones. The RANGE
+//! parameter of RECURRENCE-ID is not supported.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, Utc};
+use chrono_tz::Tz;
+use itertools::Itertools;
+use rrule::{RRule, RRuleSet, Unvalidated};
+
+use crate::{
+    parser::{
+        Component, ParserError,
+        ical::component::{IcalAvailable, IcalEvent, IcalJournal, IcalTimeZone, IcalTodo},
+    },
+    property::Property,
+    types::{CalDate, CalDateOrDateTime, CalDateTime, DateOrDateTimeOrPeriod, TimezoneResolver},
+};
+
+/// A component that can recur.
+pub trait Recurring: Component {
+    /// The property marking the end of an instance.
+    const END: &'static str;
+
+    /// The length of an instance without an end or DURATION.
+    fn default_duration(start: &CalDateOrDateTime) -> Option<Duration>;
+}
+
+impl Recurring for IcalEvent {
+    const END: &'static str = "DTEND";
+
+    fn default_duration(start: &CalDateOrDateTime) -> Option<Duration> {
+        // RFC5545 3.6.1: An all-day event without an end lasts one day
+        Some(if start.is_date() {
+            Duration::days(1)
+        } else {
+            Duration::zero()
+        })
+    }
+}
+
+impl Recurring for IcalTodo {
+    const END: &'static str = "DUE";
+
+    fn default_duration(_start: &CalDateOrDateTime) -> Option<Duration> {
+        None
+    }
+}
+
+impl Recurring for IcalJournal {
+    const END: &'static str = "DTEND";
+
+    fn default_duration(_start: &CalDateOrDateTime) -> Option<Duration> {
+        None
+    }
+}
+
+impl Recurring for IcalAvailable {
+    const END: &'static str = "DTEND";
+
+    fn default_duration(_start: &CalDateOrDateTime) -> Option<Duration> {
+        None
+    }
+}
+
+/// A single instance of a recurring component.
+#[derive(Debug, Clone)]
+pub struct Instance<'c, C> {
+    /// The component defining the instance, which is either an override or the master.
+    pub component: &'c C,
+    /// The RECURRENCE-ID of the instance, `None` for components that don't recur.
+    pub recurrence_id: Option<CalDateOrDateTime>,
+    pub start: Option<CalDateOrDateTime>,
+    pub end: Option<CalDateOrDateTime>,
+}
+
+impl<C> Instance<'_, C> {
+    /// The time the instance is sorted and filtered by, which is its start if it has one.
+    fn time(&self) -> Option<DateTime<Utc>> {
+        self.start
+            .as_ref()
+            .or(self.end.as_ref())
+            .map(|time| time.utc())
+    }
+}
+
+/// Map the TZIDs used by `properties` to timezones.
+///
+/// TZIDs unknown to `resolver` are looked up in `timezones`. A VTIMEZONE without a known IANA
+/// name is treated as local time, and TZIDs that can't be found at all are left out.
+pub fn resolve_timezones<'p>(
+    properties: impl IntoIterator<Item = &'p Property>,
+    timezones: &[IcalTimeZone],
+    resolver: &impl TimezoneResolver,
+) -> HashMap<String, Option<Tz>> {
+    let mut resolved = HashMap::new();
+    for tzid in properties.into_iter().filter_map(Property::get_tzid) {
+        if resolved.contains_key(tzid) {
+            continue;
+        }
+        if let Some(tz) = resolver.resolve(tzid) {
+            resolved.insert(tzid.to_owned(), Some(tz));
+        } else if let Some(timezone) = timezones.iter().find(|tz| tz.get_tzid() == tzid) {
+            resolved.insert(tzid.to_owned(), Tz::try_from(timezone).ok());
+        }
+    }
+    resolved
+}
+
+/// The start and end of a component.
+pub(crate) fn bounds<C: Recurring>(
+    component: &C,
+    timezones: &HashMap<String, Option<Tz>>,
+) -> Result<(Option<CalDateOrDateTime>, Option<CalDateOrDateTime>), ParserError> {
+    let start = component
+        .get_property("DTSTART")
+        .map(|prop| CalDateOrDateTime::parse_prop(prop, timezones))
+        .transpose()?;
+
+    let end = if let Some(prop) = component.get_property(C::END) {
+        Some(CalDateOrDateTime::parse_prop(prop, timezones)?)
+    } else if let Some(start) = &start {
+        let duration = match component.get_property("DURATION") {
+            Some(prop) => Option::<Duration>::try_from(prop)?,
+            None => C::default_duration(start),
+        };
+        duration
+            .map(|duration| instance_end(start, duration))
+            .transpose()?
+    } else {
+        None
+    };
+
+    Ok((start, end))
+}
+
+/// The end of an instance starting at `start` that lasts `duration`.
+fn instance_end(
+    start: &CalDateOrDateTime,
+    duration: Duration,
+) -> Result<CalDateOrDateTime, ParserError> {
+    start
+        .checked_add_signed(duration)
+        .map(CalDateOrDateTime::from)
+        .ok_or(ParserError::InvalidValue("DURATION"))
+}
+
+/// Parse the comma-separated values of a date list property like EXDATE.
+fn parse_date_list(
+    prop: &Property,
+    timezones: &HashMap<String, Option<Tz>>,
+) -> Result<Vec<CalDateOrDateTime>, ParserError> {
+    prop.value
+        .iter()
+        .flat_map(|value| value.split(','))
+        .map(|value| {
+            let prop = Property {
+                value: Some(value.to_owned()),
+                ..prop.clone()
+            };
+            Ok(DateOrDateTimeOrPeriod::parse_prop(&prop, timezones, "DATE-TIME")?.start())
+        })
+        .collect()
+}
+
+/// The RRULE, RDATE and EXDATE properties of a master component starting at `dtstart`.
+fn recurrence_set<C: Component>(
+    master: &C,
+    dtstart: &CalDateOrDateTime,
+    timezones: &HashMap<String, Option<Tz>>,
+) -> Result<RRuleSet, ParserError> {
+    let rrule_start = DateTime::<rrule::Tz>::from(dtstart.clone());
+    // RFC5545 3.8.5.2: DTSTART is always the first instance, also without an RRULE or if the
+    // RRULE doesn't match it
+    let mut set = RRuleSet::new(rrule_start).rdate(rrule_start);
+    for prop in master.get_named_properties("RRULE") {
+        let rrule = prop
+            .value
+            .as_deref()
+            .unwrap_or_default()
+            .parse::<RRule<Unvalidated>>()
+            .and_then(|rrule| rrule.validate(rrule_start))
+            .map_err(|_| ParserError::InvalidValue("RRULE"))?;
+        set = set.rrule(rrule);
+    }
+    for prop in master.get_named_properties("RDATE") {
+        for date in parse_date_list(prop, timezones)? {
+            set = set.rdate(date.into());
+        }
+    }
+    for prop in master.get_named_properties("EXDATE") {
+        for date in parse_date_list(prop, timezones)? {
+            set = set.exdate(date.into());
+        }
+    }
+    Ok(set)
+}
+
+/// Convert a recurrence back to the value type of the DTSTART it was generated from.
+fn recurrence_start(date: DateTime<rrule::Tz>, dtstart: &CalDateOrDateTime) -> CalDateOrDateTime {
+    match dtstart {
+        CalDateOrDateTime::Date(date_start) => CalDateOrDateTime::Date(CalDate(
+            CalDateTime::from(date).date_floor(),
+            date_start.timezone().to_owned(),
+        )),
+        CalDateOrDateTime::DateTime(_) => CalDateTime::from(date).into(),
+    }
+}
+
+/// The first recurrence of a master component starting at `dtstart` that lies after `after`.
+pub(crate) fn next_recurrence<C: Component>(
+    master: &C,
+    dtstart: &CalDateOrDateTime,
+    timezones: &HashMap<String, Option<Tz>>,
+    after: DateTime<Utc>,
+) -> Result<Option<CalDateOrDateTime>, ParserError> {
+    let set = recurrence_set(master, dtstart, timezones)?;
+    let tz = set.get_dt_start().timezone();
+    // The bounds of a `RRuleSet` are inclusive
+    let set = set.after((after + Duration::seconds(1)).with_timezone(&tz));
+    Ok(set
+        .all(1)
+        .dates
+        .into_iter()
+        .next()
+        .map(|date| recurrence_start(date, dtstart)))
+}
+
+/// Move the start of an RRULE from `dtstart` to the later recurrence `next`.
+///
+/// The COUNT is reduced by the recurrences in between, and `None` is returned once none are left.
+pub(crate) fn rebase_rrule(
+    rrule: &str,
+    dtstart: &CalDateOrDateTime,
+    next: &CalDateOrDateTime,
+) -> Result<Option<String>, ParserError> {
+    let rrule_start = DateTime::<rrule::Tz>::from(dtstart.clone());
+    let parsed = rrule
+        .parse::<RRule<Unvalidated>>()
+        .and_then(|parsed| parsed.validate(rrule_start))
+        .map_err(|_| ParserError::InvalidValue("RRULE"))?;
+    let Some(count) = parsed.get_count() else {
+        return Ok(Some(rrule.to_owned()));
+    };
+
+    let tz = rrule_start.timezone();
+    let passed = RRuleSet::new(rrule_start)
+        .rrule(parsed)
+        .before((next.utc() - Duration::seconds(1)).with_timezone(&tz))
+        .all(u16::MAX)
+        .dates
+        .len() as u32;
+    if passed >= count {
+        return Ok(None);
+    }
+    Ok(Some(
+        rrule
+            .split(';')
+            .map(|part| match part.split_once('=') {
+                Some((key, _)) if key.eq_ignore_ascii_case("COUNT") => {
+                    format!("COUNT={}", count - passed)
+                }
+                _ => part.to_owned(),
+            })
+            .join(";"),
+    ))
+}
+
+/// Expand the instances of a master component and its overrides that start within
+/// `[after, before)`, ordered by their start.
+///
+/// Instances of to-dos without a DTSTART are filtered by their DUE instead.
+///
+/// # Examples
+///
+/// ```rust
+/// use chrono::{TimeZone, Utc};
+/// use ical::parser::ical::recurrence::{expand, resolve_timezones};
+/// use ical::types::OlsonResolver;
+///
+/// let input = "BEGIN:VCALENDAR\r
+/// BEGIN:VEVENT\r
+/// UID:1\r
+/// DTSTART:20250101T100000Z\r
+/// RRULE:FREQ=DAILY;COUNT=5\r
+/// EXDATE:20250102T100000Z\r
+/// END:VEVENT\r
+/// END:VCALENDAR\r
+/// ";
+/// let calendar = ical::IcalParser::new(input.as_bytes()).next().unwrap().unwrap();
+///
+/// let timezones = resolve_timezones(
+///     calendar.events.iter().flat_map(|event| &event.properties),
+///     &calendar.timezones,
+///     &OlsonResolver,
+/// );
+/// let instances = expand(
+///     &calendar.events,
+///     &timezones,
+///     Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap(),
+///     Utc.with_ymd_and_hms(2025, 1, 4, 0, 0, 0).unwrap(),
+/// )
+/// .unwrap();
+/// assert_eq!(instances.len(), 2);
+/// ```
+pub fn expand<'c, C: Recurring>(
+    components: impl IntoIterator<Item = &'c C>,
+    timezones: &HashMap<String, Option<Tz>>,
+    after: DateTime<Utc>,
+    before: DateTime<Utc>,
+) -> Result<Vec<Instance<'c, C>>, ParserError> {
+    let (overrides, masters): (Vec<&C>, Vec<&C>) = components
+        .into_iter()
+        .partition(|component| component.get_property("RECURRENCE-ID").is_some());
+
+    let mut instances = Vec::new();
+    let mut overridden = Vec::new();
+    for component in overrides {
+        let recurrence_id = component
+            .get_property("RECURRENCE-ID")
+            .map(|prop| CalDateOrDateTime::parse_prop(prop, timezones))
+            .transpose()?;
+        overridden.extend(recurrence_id.as_ref().map(CalDateOrDateTime::utc));
+        let (start, end) = bounds(component, timezones)?;
+        instances.push(Instance {
+            component,
+            recurrence_id,
+            start,
+            end,
+        });
+    }
+
+    for master in masters {
+        let (start, end) = bounds(master, timezones)?;
+        let rrules = master.get_named_properties("RRULE");
+        let rdates = master.get_named_properties("RDATE");
+        let Some(dtstart) = start
+            .clone()
+            .filter(|_| !rrules.is_empty() || !rdates.is_empty())
+        else {
+            instances.push(Instance {
+                component: master,
+                recurrence_id: None,
+                start,
+                end,
+            });
+            continue;
+        };
+
+        let duration = end.map(|end| end - &dtstart);
+        let set = recurrence_set(master, &dtstart, timezones)?;
+        let tz = set.get_dt_start().timezone();
+        let set = set
+            .after(after.with_timezone(&tz))
+            .before(before.with_timezone(&tz));
+
+        // DTSTART is usually generated by the RRULE as well
+        for date in set.all(u16::MAX).dates.into_iter().dedup() {
+            let start = recurrence_start(date, &dtstart);
+            if overridden.contains(&start.utc()) {
+                continue;
+            }
+            let end = duration
+                .map(|duration| instance_end(&start, duration))
+                .transpose()?;
+            instances.push(Instance {
+                component: master,
+                recurrence_id: Some(start.clone()),
+                end,
+                start: Some(start),
+            });
+        }
+    }
+
+    instances.retain(|instance| {
+        instance
+            .time()
+            .is_some_and(|time| after <= time && time < before)
+    });
+    instances.sort_by_key(Instance::time);
+    Ok(instances)
+}
+
+/// Expand the instances of a master component and its overrides that overlap `[after, before)`,
+/// ordered by their start.
+///
+/// Instances without a start or an end are left out.
+pub fn expand_overlapping<'c, C: Recurring>(
+    components: impl IntoIterator<Item = &'c C>,
+    timezones: &HashMap<String, Option<Tz>>,
+    after: DateTime<Utc>,
+    before: DateTime<Utc>,
+) -> Result<Vec<Instance<'c, C>>, ParserError> {
+    let components: Vec<_> = components.into_iter().collect();
+    let mut length = Duration::zero();
+    for component in &components {
+        if let (Some(start), Some(end)) = bounds(*component, timezones)? {
+            length = length.max(end - &start);
+        }
+    }
+
+    let after_start = after
+        .checked_sub_signed(length)
+        .ok_or(ParserError::InvalidValue("DURATION"))?;
+    let mut instances = expand(components, timezones, after_start, before)?;
+    instances.retain(|instance| {
+        instance.end.as_ref().is_some_and(|end| end.utc() > after) && instance.start.is_some()
+    });
+    Ok(instances)
+}
@@ -0,0 +1,276 @@
+//! Find free time slots across several calendars.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use chrono::{Duration, NaiveTime, TimeZone, Utc};
+//! use ical::parser::ical::slots::{SlotFinder, WorkingHours};
+//! use ical::types::OlsonResolver;
+//!
+//! let input = "BEGIN:VCALENDAR\r
+//! BEGIN:VEVENT\r
+//! UID:1\r
+//! DTSTART:20250106T100000Z\r
+//! DTEND:20250106T120000Z\r
+//! END:VEVENT\r
+//! END:VCALENDAR\r
+//! ";
+//! let calendar = ical::IcalParser::new(input.as_bytes()).next().unwrap().unwrap();
+//!
+//! let mut finder = SlotFinder::new(
+//!     Utc.with_ymd_and_hms(2025, 1, 6, 0, 0, 0).unwrap(),
+//!     Utc.with_ymd_and_hms(2025, 1, 7, 0, 0, 0).unwrap(),
+//! )
+//! .with_working_hours(WorkingHours::new(
+//!     NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+//!     NaiveTime::from_hms_opt(17, 0, 0).unwrap(),
+//!     chrono_tz::UTC,
+//! ));
+//! finder.add_calendar(&calendar, &OlsonResolver).unwrap();
+//!
+//! let slots = finder.free_slots(Duration::hours(1)).unwrap();
+//! assert_eq!(slots.len(), 2);
+//! assert_eq!(slots[0].end, Utc.with_ymd_and_hms(2025, 1, 6, 10, 0, 0).unwrap());
+//! ```
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, TimeZone, Utc, Weekday};
+use chrono_tz::Tz;
+use itertools::Itertools;
+
+use crate::{
+    parser::{
+        ParserError,
+        ical::component::{
+            BusyTime, CalendarInnerData, FreeBusyType, IcalCalendar, IcalCalendarObject,
+            IcalFreeBusy, event_busy_time,
+        },
+    },
+    types::{CalDateTimeError, TimezoneResolver},
+};
+
+/// The hours of the day slots may lie within.
+///
+/// If `end` isn't after `start` the working hours cross midnight and end on the next day, so
+/// `days` are the days they start on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkingHours {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+    pub days: Vec<Weekday>,
+    pub timezone: Tz,
+}
+
+impl WorkingHours {
+    /// Working hours from Monday to Friday.
+    pub fn new(start: NaiveTime, end: NaiveTime, timezone: Tz) -> Self {
+        Self {
+            start,
+            end,
+            days: vec![
+                Weekday::Mon,
+                Weekday::Tue,
+                Weekday::Wed,
+                Weekday::Thu,
+                Weekday::Fri,
+            ],
+            timezone,
+        }
+    }
+
+    /// The working hours overlapping `[start, end)`.
+    ///
+    /// Fails if the working hours of a day start or end in a gap of the local time.
+    fn intervals(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<Slot>, CalDateTimeError> {
+        let overnight = self.end <= self.start;
+        let mut first = start.with_timezone(&self.timezone).date_naive();
+        if overnight {
+            // The working hours of the previous day might still last
+            first = first.pred_opt().unwrap_or(first);
+        }
+        let last = end.with_timezone(&self.timezone).date_naive();
+        let local = |date: NaiveDate, time| {
+            self.timezone
+                .from_local_datetime(&date.and_time(time))
+                .earliest()
+                .map(|datetime| datetime.to_utc())
+                .ok_or(CalDateTimeError::LocalTimeGap)
+        };
+
+        let mut intervals = Vec::new();
+        for date in first
+            .iter_days()
+            .take_while(|date| *date <= last)
+            .filter(|date| self.days.contains(&date.weekday()))
+        {
+            let end_date = match overnight {
+                true => date.succ_opt().unwrap_or(date),
+                false => date,
+            };
+            let interval = Slot {
+                start: local(date, self.start)?.max(start),
+                end: local(end_date, self.end)?.min(end),
+            };
+            if interval.start < interval.end {
+                intervals.push(interval);
+            }
+        }
+        Ok(intervals)
+    }
+}
+
+/// A free interval of time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Slot {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+/// Collect the busy time of several calendars within a search window to find free slots.
+///
+/// Every FBTYPE but FREE counts as busy.
+#[derive(Debug, Clone)]
+pub struct SlotFinder {
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    working_hours: Option<WorkingHours>,
+    busy: Vec<BusyTime>,
+}
+
+impl SlotFinder {
+    /// Search for slots within `[start, end)`.
+    pub fn new(start: DateTime<Utc>, end: DateTime<Utc>) -> Self {
+        Self {
+            start,
+            end,
+            working_hours: None,
+            busy: Vec::new(),
+        }
+    }
+
+    /// Only return slots within `working_hours`.
+    pub fn with_working_hours(mut self, working_hours: WorkingHours) -> Self {
+        self.working_hours = Some(working_hours);
+        self
+    }
+
+    /// Add the events and free/busy information of a calendar.
+    pub fn add_calendar(
+        &mut self,
+        calendar: &IcalCalendar,
+        resolver: &impl TimezoneResolver,
+    ) -> Result<(), ParserError> {
+        for (_uid, events) in calendar
+            .events
+            .iter()
+            .into_group_map_by(|event| event.get_uid())
+        {
+            event_busy_time(
+                events,
+                &calendar.timezones,
+                resolver,
+                (self.start, self.end),
+                &mut self.busy,
+            )?;
+        }
+        for freebusy in &calendar.free_busys {
+            self.add_freebusy(freebusy);
+        }
+        Ok(())
+    }
+
+    /// Add the events of calendar objects.
+    pub fn add_objects<'o>(
+        &mut self,
+        objects: impl IntoIterator<Item = &'o IcalCalendarObject>,
+        resolver: &impl TimezoneResolver,
+    ) -> Result<(), ParserError> {
+        for object in objects {
+            if let CalendarInnerData::Event(main, overrides) = object.get_inner() {
+                event_busy_time(
+                    std::iter::once(main).chain(overrides).collect(),
+                    object.get_timezones(),
+                    resolver,
+                    (self.start, self.end),
+                    &mut self.busy,
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Add the FREEBUSY periods of a VFREEBUSY.
+    pub fn add_freebusy(&mut self, freebusy: &IcalFreeBusy) {
+        for period in freebusy.get_periods() {
+            if period.fbtype == FreeBusyType::Free {
+                continue;
+            }
+            let start = period.period.start().utc().max(self.start);
+            let end = period.period.end().utc().min(self.end);
+            if start < end {
+                self.busy.push((period.fbtype, start, end));
+            }
+        }
+    }
+
+    /// The maximal free intervals that are at least `duration` long, ordered by their start.
+    ///
+    /// Fails with `CalDateTimeError::LocalTimeGap` if the working hours start or end at a local
+    /// time that is skipped, like during the switch to daylight saving time.
+    pub fn free_slots(&self, duration: Duration) -> Result<Vec<Slot>, ParserError> {
+        let available = match &self.working_hours {
+            Some(working_hours) => working_hours.intervals(self.start, self.end)?,
+            None => vec![Slot {
+                start: self.start,
+                end: self.end,
+            }],
+        };
+        let busy: Vec<_> = self
+            .busy
+            .iter()
+            .map(|(_, start, end)| (*start, *end))
+            .sorted()
+            .collect();
+
+        let mut slots = Vec::new();
+        for Slot { mut start, end } in available {
+            for &(busy_start, busy_end) in &busy {
+                if busy_end <= start || busy_start >= end {
+                    continue;
+                }
+                if busy_start > start {
+                    slots.push(Slot {
+                        start,
+                        end: busy_start,
+                    });
+                }
+                start = start.max(busy_end);
+            }
+            if start < end {
+                slots.push(Slot { start, end });
+            }
+        }
+        slots.retain(|slot| slot.end - slot.start >= duration);
+        Ok(slots)
+    }
+
+    /// Slots of exactly `duration`, starting every `step` within the free intervals.
+    pub fn candidates(&self, duration: Duration, step: Duration) -> Result<Vec<Slot>, ParserError> {
+        let step = step.max(Duration::seconds(1));
+        Ok(self
+            .free_slots(duration)?
+            .into_iter()
+            .flat_map(|free| {
+                std::iter::successors(Some(free.start), move |start| Some(*start + step))
+                    .take_while(move |start| *start + duration <= free.end)
+                    .map(move |start| Slot {
+                        start,
+                        end: start + duration,
+                    })
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,1232 @@
+//! Validate calendars against RFC 5545.
+//!
+//! In contrast to `ComponentMut::verify` the validator never rejects a calendar. It walks every
+//! component and reports each violation of the cardinality, value type and allowed property
+//! rules of [RFC5545](https://tools.ietf.org/html/rfc5545) sections 3.6 and 3.8 as a
+//! `Diagnostic`.
+//!
+//! Calendars can either be validated after parsing with `validate` or directly from their input
+//! with `validate_input`, which also reports the line numbers and lints input the parser would
+//! reject.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use ical::parser::ical::validator::{self, DiagnosticCode, Severity};
+//!
+//! let input = "BEGIN:VCALENDAR\r\nPRODID:-//ical-rs//EN\r\nVERSION:2.0\r\nBEGIN:VEVENT\r\n\
+//!     UID:1\r\nDTSTART:20250101T100000Z\r\nPRIORITY:10\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n";
+//!
+//! let diagnostics = validator::validate_input(input);
+//! assert_eq!(diagnostics.len(), 2);
+//! assert_eq!(diagnostics[0].code, DiagnosticCode::MissingProperty);
+//! assert_eq!(diagnostics[0].severity, Severity::Error);
+//! assert_eq!(
+//!     diagnostics[1].to_string(),
+//!     "Line 7: VCALENDAR/VEVENT[0] PRIORITY: error[invalid-value]: 10 is not a priority between 0 and 9"
+//! );
+//! ```
+
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::fmt;
+
+use chrono::NaiveDate;
+
+use crate::{
+    line::BorrowedLineReader,
+    parser::{
+        Component,
+        ical::component::{
+            IcalCalendar, IcalLocation, IcalParticipant, IcalResource, IcalTimeZoneTransitionType,
+        },
+    },
+    property::{Property, parse_property},
+    types::{CalDateTime, Period, parse_duration},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        })
+    }
+}
+
+/// The kind of a `Diagnostic`.
+///
+/// The codes returned by `as_str` are stable and can be used to filter diagnostics.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DiagnosticCode {
+    /// A line is no valid content line or components aren't properly nested.
+    SyntaxError,
+    /// A required property is missing.
+    MissingProperty,
+    /// A property occurs more often than allowed.
+    DuplicateProperty,
+    /// A property defined by RFC 5545 is used in a component that doesn't allow it.
+    PropertyNotAllowed,
+    /// Properties that exclude each other are used together.
+    PropertyConflict,
+    /// A property value doesn't match its value type or allowed values.
+    InvalidValue,
+    /// A property has a different value type than the property it relates to.
+    ValueTypeMismatch,
+    /// A `TZID` parameter refers to no `VTIMEZONE` of the calendar.
+    UnknownTzid,
+    /// A required sub-component is missing.
+    MissingComponent,
+    /// A component is nested in a component that doesn't allow it.
+    ComponentNotAllowed,
+    /// A component that isn't defined by RFC 5545.
+    UnknownComponent,
+}
+
+impl DiagnosticCode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DiagnosticCode::SyntaxError => "syntax-error",
+            DiagnosticCode::MissingProperty => "missing-property",
+            DiagnosticCode::DuplicateProperty => "duplicate-property",
+            DiagnosticCode::PropertyNotAllowed => "property-not-allowed",
+            DiagnosticCode::PropertyConflict => "property-conflict",
+            DiagnosticCode::InvalidValue => "invalid-value",
+            DiagnosticCode::ValueTypeMismatch => "value-type-mismatch",
+            DiagnosticCode::UnknownTzid => "unknown-tzid",
+            DiagnosticCode::MissingComponent => "missing-component",
+            DiagnosticCode::ComponentNotAllowed => "component-not-allowed",
+            DiagnosticCode::UnknownComponent => "unknown-component",
+        }
+    }
+}
+
+impl fmt::Display for DiagnosticCode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Where a `Diagnostic` was found.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Location {
+    /// Path of the component, e.g. `VCALENDAR/VEVENT[1]/VALARM[0]`.
+    pub component: String,
+    /// Name of the property, if the diagnostic concerns a single property.
+    pub property: Option<String>,
+    /// Line of the property or the start of the component, only known when validating input.
+    pub line: Option<usize>,
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(line) = self.line {
+            write!(f, "Line {line}: ")?;
+        }
+        f.write_str(&self.component)?;
+        if let Some(property) = &self.property {
+            write!(f, " {property}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A single rule violation.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub code: DiagnosticCode,
+    pub location: Location,
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}: {}[{}]: {}",
+            self.location, self.severity, self.code, self.message
+        )
+    }
+}
+
+/// Validate a parsed calendar.
+///
+/// The locations of the diagnostics have no line numbers.
+pub fn validate<const VERIFIED: bool>(calendar: &IcalCalendar<VERIFIED>) -> Vec<Diagnostic> {
+    let mut validator = Validator::default();
+    validator.calendar(&Node::from_calendar(calendar), 0);
+    validator.diagnostics
+}
+
+/// Validate every calendar in `input`.
+///
+/// Lines that can't be parsed are reported as `DiagnosticCode::SyntaxError` and skipped.
+pub fn validate_input(input: &str) -> Vec<Diagnostic> {
+    let mut validator = Validator::default();
+    let roots = Node::parse_input(input, &mut validator.diagnostics);
+
+    let mut index = 0;
+    for root in &roots {
+        if root.name == "VCALENDAR" {
+            validator.calendar(root, index);
+            index += 1;
+        } else {
+            validator.report(
+                Severity::Error,
+                DiagnosticCode::ComponentNotAllowed,
+                root.location(&root.name, None),
+                format!("{} is not a calendar", root.name),
+            );
+        }
+    }
+    validator.diagnostics
+}
+
+/// A generic component to validate, either borrowed from a parsed calendar or read from input.
+#[derive(Debug)]
+struct Node<'a> {
+    name: Cow<'a, str>,
+    line: Option<usize>,
+    properties: Vec<(Option<usize>, Cow<'a, Property>)>,
+    children: Vec<Node<'a>>,
+}
+
+impl<'a> Node<'a> {
+    fn new(name: Cow<'a, str>, line: Option<usize>) -> Self {
+        Self {
+            name,
+            line,
+            properties: Vec::new(),
+            children: Vec::new(),
+        }
+    }
+
+    fn from_component<C: Component>(name: &'a str, component: &'a C) -> Self {
+        Self {
+            properties: component
+                .get_properties()
+                .iter()
+                .map(|prop| (None, Cow::Borrowed(prop)))
+                .collect(),
+            ..Self::new(Cow::Borrowed(name), None)
+        }
+    }
+
+    /// Add the RFC 9073 sub-components of a VEVENT or VTODO.
+    fn with_publishing(
+        mut self,
+        locations: &'a [IcalLocation],
+        resources: &'a [IcalResource],
+        participants: &'a [IcalParticipant],
+    ) -> Self {
+        let location = |location| Node::from_component("VLOCATION", location);
+        let resource = |resource| Node::from_component("VRESOURCE", resource);
+        self.children.extend(locations.iter().map(location));
+        self.children.extend(resources.iter().map(resource));
+        self.children.extend(participants.iter().map(|participant| {
+            let mut node = Node::from_component("PARTICIPANT", participant);
+            node.children
+                .extend(participant.locations.iter().map(location));
+            node.children
+                .extend(participant.resources.iter().map(resource));
+            node
+        }));
+        self
+    }
+
+    fn from_calendar<const VERIFIED: bool>(calendar: &'a IcalCalendar<VERIFIED>) -> Self {
+        let mut node = Node::from_component("VCALENDAR", calendar);
+        node.children.extend(calendar.timezones.iter().map(|tz| {
+            let mut node = Node::from_component("VTIMEZONE", tz);
+            node.children
+                .extend(tz.transitions.iter().map(|transition| {
+                    let name = match transition.transition {
+                        IcalTimeZoneTransitionType::STANDARD => "STANDARD",
+                        IcalTimeZoneTransitionType::DAYLIGHT => "DAYLIGHT",
+                    };
+                    Node::from_component(name, transition)
+                }));
+            node
+        }));
+        node.children.extend(calendar.events.iter().map(|event| {
+            let mut node = Node::from_component("VEVENT", event);
+            node.children.extend(
+                event
+                    .alarms
+                    .iter()
+                    .map(|alarm| Node::from_component("VALARM", alarm)),
+            );
+            node.with_publishing(&event.locations, &event.resources, &event.participants)
+        }));
+        node.children.extend(calendar.todos.iter().map(|todo| {
+            let mut node = Node::from_component("VTODO", todo);
+            node.children.extend(
+                todo.alarms
+                    .iter()
+                    .map(|alarm| Node::from_component("VALARM", alarm)),
+            );
+            node.with_publishing(&todo.locations, &todo.resources, &todo.participants)
+        }));
+        node.children.extend(
+            calendar
+                .journals
+                .iter()
+                .map(|journal| Node::from_component("VJOURNAL", journal)),
+        );
+        node.children.extend(
+            calendar
+                .free_busys
+                .iter()
+                .map(|free_busy| Node::from_component("VFREEBUSY", free_busy)),
+        );
+        node.children
+            .extend(calendar.availabilities.iter().map(|availability| {
+                let mut node = Node::from_component("VAVAILABILITY", availability);
+                node.children.extend(
+                    availability
+                        .available
+                        .iter()
+                        .map(|available| Node::from_component("AVAILABLE", available)),
+                );
+                node
+            }));
+        node.children.extend(
+            calendar
+                .alarms
+                .iter()
+                .map(|alarm| Node::from_component("VALARM", alarm)),
+        );
+        node
+    }
+
+    /// Read the component tree from `input`, reporting syntax errors to `diagnostics`.
+    fn parse_input(input: &str, diagnostics: &mut Vec<Diagnostic>) -> Vec<Node<'static>> {
+        let mut roots = vec![];
+        let mut stack: Vec<Node> = vec![];
+        let syntax_error =
+            |roots: &[Node], stack: &[Node], line: usize, message: String| Diagnostic {
+                severity: Severity::Error,
+                code: DiagnosticCode::SyntaxError,
+                location: Location {
+                    component: stack_path(roots, stack),
+                    property: None,
+                    line: Some(line),
+                },
+                message,
+            };
+
+        for line in BorrowedLineReader::new(input) {
+            let number = line.number();
+            let prop = match parse_property(line.as_str(), number) {
+                Ok(prop) => prop.into_owned(),
+                Err(err) => {
+                    let message = err.to_string();
+                    let message = message
+                        .strip_prefix(&format!("Line {number}: "))
+                        .unwrap_or(&message);
+                    diagnostics.push(syntax_error(&roots, &stack, number, message.to_owned()));
+                    continue;
+                }
+            };
+
+            match (prop.name.as_str(), prop.value) {
+                ("BEGIN", Some(name)) => {
+                    stack.push(Node::new(Cow::Owned(name.to_uppercase()), Some(number)))
+                }
+                ("END", Some(name)) => {
+                    let Some(node) = stack.pop() else {
+                        diagnostics.push(syntax_error(
+                            &roots,
+                            &stack,
+                            number,
+                            format!("END:{name} without BEGIN"),
+                        ));
+                        continue;
+                    };
+                    if !node.name.eq_ignore_ascii_case(&name) {
+                        diagnostics.push(syntax_error(
+                            &roots,
+                            &stack,
+                            number,
+                            format!("END:{name} doesn't match BEGIN:{}", node.name),
+                        ));
+                    }
+                    match stack.last_mut() {
+                        Some(parent) => parent.children.push(node),
+                        None => roots.push(node),
+                    }
+                }
+                ("BEGIN" | "END", None) => diagnostics.push(syntax_error(
+                    &roots,
+                    &stack,
+                    number,
+                    format!("{} without a component name", prop.name),
+                )),
+                (_, value) => match stack.last_mut() {
+                    Some(node) => node
+                        .properties
+                        .push((Some(number), Cow::Owned(Property { value, ..prop }))),
+                    None => diagnostics.push(syntax_error(
+                        &roots,
+                        &stack,
+                        number,
+                        format!("{} outside of a component", prop.name),
+                    )),
+                },
+            }
+        }
+
+        // Validate incomplete components anyway
+        while let Some(node) = stack.pop() {
+            diagnostics.push(syntax_error(
+                &roots,
+                &stack,
+                node.line.unwrap_or_default(),
+                format!("BEGIN:{} without END", node.name),
+            ));
+            match stack.last_mut() {
+                Some(parent) => parent.children.push(node),
+                None => roots.push(node),
+            }
+        }
+
+        roots
+    }
+
+    fn get_property(&self, name: &str) -> Option<&Property> {
+        self.properties
+            .iter()
+            .map(|(_, prop)| prop.as_ref())
+            .find(|prop| prop.name == name)
+    }
+
+    fn count(&self, name: &str) -> usize {
+        self.properties
+            .iter()
+            .filter(|(_, prop)| prop.name == name)
+            .count()
+    }
+
+    fn has_child(&self, name: &str) -> bool {
+        self.children.iter().any(|child| child.name == name)
+    }
+
+    /// Location of the component or one of its properties.
+    fn location(&self, path: &str, property: Option<(Option<usize>, &Property)>) -> Location {
+        Location {
+            component: path.to_owned(),
+            property: property.map(|(_, prop)| prop.name.clone()),
+            line: match property {
+                Some((line, _)) => line,
+                None => self.line,
+            },
+        }
+    }
+
+    /// Location of a property that is missing from or conflicts within the component.
+    fn property_location(&self, path: &str, name: &str) -> Location {
+        let line = self
+            .properties
+            .iter()
+            .find(|(_, prop)| prop.name == name)
+            .map_or(self.line, |(line, _)| *line);
+        Location {
+            component: path.to_owned(),
+            property: Some(name.to_owned()),
+            line,
+        }
+    }
+}
+
+/// The path of the innermost component that is still being read.
+fn stack_path(roots: &[Node], stack: &[Node]) -> String {
+    let mut siblings = roots;
+    let mut path = vec![];
+    for (depth, node) in stack.iter().enumerate() {
+        let index = siblings
+            .iter()
+            .filter(|sibling| sibling.name == node.name)
+            .count();
+        path.push(match (depth, index) {
+            (0, 0) => node.name.to_string(),
+            _ => format!("{}[{index}]", node.name),
+        });
+        siblings = &node.children;
+    }
+    path.join("/")
+}
+
+/// How often a property may occur within a component.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Occurrence {
+    /// Exactly once.
+    Required,
+    /// At most once.
+    Optional,
+    /// At most once, but more occurrences are only discouraged.
+    ShouldOnce,
+    Many,
+}
+
+use Occurrence::{Many, Optional, Required, ShouldOnce};
+
+/// A property name and how often it may occur.
+type PropertyRule = (&'static str, Occurrence);
+
+const CALENDAR: &[PropertyRule] = &[
+    ("PRODID", Required),
+    ("VERSION", Required),
+    ("CALSCALE", Optional),
+    ("METHOD", Optional),
+    // RFC 7986
+    ("UID", Optional),
+    ("LAST-MODIFIED", Optional),
+    ("URL", Optional),
+    ("REFRESH-INTERVAL", Optional),
+    ("SOURCE", Optional),
+    ("COLOR", Optional),
+    ("NAME", Many),
+    ("DESCRIPTION", Many),
+    ("CATEGORIES", Many),
+    ("IMAGE", Many),
+];
+
+const EVENT: &[PropertyRule] = &[
+    ("DTSTAMP", Required),
+    ("UID", Required),
+    ("DTSTART", Optional),
+    ("CLASS", Optional),
+    ("CREATED", Optional),
+    ("DESCRIPTION", Optional),
+    ("GEO", Optional),
+    ("LAST-MODIFIED", Optional),
+    ("LOCATION", Optional),
+    ("ORGANIZER", Optional),
+    ("PRIORITY", Optional),
+    ("SEQUENCE", Optional),
+    ("STATUS", Optional),
+    ("SUMMARY", Optional),
+    ("TRANSP", Optional),
+    ("URL", Optional),
+    ("RECURRENCE-ID", Optional),
+    ("RRULE", ShouldOnce),
+    ("DTEND", Optional),
+    ("DURATION", Optional),
+    ("ATTACH", Many),
+    ("ATTENDEE", Many),
+    ("CATEGORIES", Many),
+    ("COMMENT", Many),
+    ("CONTACT", Many),
+    ("EXDATE", Many),
+    ("RSTATUS", Many),
+    ("RELATED-TO", Many),
+    ("RESOURCES", Many),
+    ("RDATE", Many),
+    // RFC 7986
+    ("COLOR", Optional),
+    ("IMAGE", Many),
+    ("CONFERENCE", Many),
+    // RFC 9073
+    ("STRUCTURED-DATA", Many),
+    ("STYLED-DESCRIPTION", Many),
+];
+
+const TODO: &[PropertyRule] = &[
+    ("DTSTAMP", Required),
+    ("UID", Required),
+    ("CLASS", Optional),
+    ("COMPLETED", Optional),
+    ("CREATED", Optional),
+    ("DESCRIPTION", Optional),
+    ("DTSTART", Optional),
+    ("GEO", Optional),
+    ("LAST-MODIFIED", Optional),
+    ("LOCATION", Optional),
+    ("ORGANIZER", Optional),
+    ("PERCENT-COMPLETE", Optional),
+    ("PRIORITY", Optional),
+    ("RECURRENCE-ID", Optional),
+    ("SEQUENCE", Optional),
+    ("STATUS", Optional),
+    ("SUMMARY", Optional),
+    ("URL", Optional),
+    ("RRULE", ShouldOnce),
+    ("DUE", Optional),
+    ("DURATION", Optional),
+    ("ATTACH", Many),
+    ("ATTENDEE", Many),
+    ("CATEGORIES", Many),
+    ("COMMENT", Many),
+    ("CONTACT", Many),
+    ("EXDATE", Many),
+    ("RSTATUS", Many),
+    ("RELATED-TO", Many),
+    ("RESOURCES", Many),
+    ("RDATE", Many),
+    // RFC 7986
+    ("COLOR", Optional),
+    ("IMAGE", Many),
+    ("CONFERENCE", Many),
+    // RFC 9073
+    ("STRUCTURED-DATA", Many),
+    ("STYLED-DESCRIPTION", Many),
+];
+
+const JOURNAL: &[PropertyRule] = &[
+    ("DTSTAMP", Required),
+    ("UID", Required),
+    ("CLASS", Optional),
+    ("CREATED", Optional),
+    ("DTSTART", Optional),
+    ("LAST-MODIFIED", Optional),
+    ("ORGANIZER", Optional),
+    ("RECURRENCE-ID", Optional),
+    ("SEQUENCE", Optional),
+    ("STATUS", Optional),
+    ("SUMMARY", Optional),
+    ("URL", Optional),
+    ("RRULE", ShouldOnce),
+    ("ATTACH", Many),
+    ("ATTENDEE", Many),
+    ("CATEGORIES", Many),
+    ("COMMENT", Many),
+    ("CONTACT", Many),
+    ("DESCRIPTION", Many),
+    ("EXDATE", Many),
+    ("RELATED-TO", Many),
+    ("RDATE", Many),
+    ("RSTATUS", Many),
+    // RFC 7986
+    ("COLOR", Optional),
+    ("IMAGE", Many),
+];
+
+const FREEBUSY: &[PropertyRule] = &[
+    ("DTSTAMP", Required),
+    ("UID", Required),
+    ("CONTACT", Optional),
+    ("DTSTART", Optional),
+    ("DTEND", Optional),
+    ("ORGANIZER", Optional),
+    ("URL", Optional),
+    ("ATTENDEE", Many),
+    ("COMMENT", Many),
+    ("FREEBUSY", Many),
+    ("RSTATUS", Many),
+];
+
+// RFC 9073
+const LOCATION: &[PropertyRule] = &[
+    ("UID", Required),
+    ("DESCRIPTION", Optional),
+    ("GEO", Optional),
+    ("LOCATION-TYPE", Optional),
+    ("NAME", Optional),
+    ("URL", Optional),
+    ("STRUCTURED-DATA", Many),
+];
+
+// RFC 9073
+const RESOURCE: &[PropertyRule] = &[
+    ("UID", Required),
+    ("DESCRIPTION", Optional),
+    ("GEO", Optional),
+    ("NAME", Optional),
+    ("RESOURCE-TYPE", Optional),
+    ("STRUCTURED-DATA", Many),
+];
+
+// RFC 9073
+const PARTICIPANT: &[PropertyRule] = &[
+    ("PARTICIPANT-TYPE", Required),
+    ("UID", Required),
+    ("CALENDAR-ADDRESS", Optional),
+    ("CREATED", Optional),
+    ("DESCRIPTION", Optional),
+    ("DTSTAMP", Optional),
+    ("GEO", Optional),
+    ("LAST-MODIFIED", Optional),
+    ("PRIORITY", Optional),
+    ("SEQUENCE", Optional),
+    ("STATUS", Optional),
+    ("SUMMARY", Optional),
+    ("URL", Optional),
+    ("ATTACH", Many),
+    ("CATEGORIES", Many),
+    ("COMMENT", Many),
+    ("CONTACT", Many),
+    ("LOCATION", Many),
+    ("RELATED-TO", Many),
+    ("RESOURCES", Many),
+    ("RSTATUS", Many),
+    ("STRUCTURED-DATA", Many),
+    ("STYLED-DESCRIPTION", Many),
+];
+
+// RFC 7953
+const AVAILABILITY: &[PropertyRule] = &[
+    ("DTSTAMP", Required),
+    ("UID", Required),
+    ("BUSYTYPE", Optional),
+    ("CLASS", Optional),
+    ("CREATED", Optional),
+    ("DESCRIPTION", Optional),
+    ("DTSTART", Optional),
+    ("LAST-MODIFIED", Optional),
+    ("LOCATION", Optional),
+    ("ORGANIZER", Optional),
+    ("PRIORITY", Optional),
+    ("SEQUENCE", Optional),
+    ("SUMMARY", Optional),
+    ("URL", Optional),
+    ("DTEND", Optional),
+    ("DURATION", Optional),
+    ("CATEGORIES", Many),
+    ("COMMENT", Many),
+    ("CONTACT", Many),
+];
+
+// RFC 7953
+const AVAILABLE: &[PropertyRule] = &[
+    ("DTSTAMP", Required),
+    ("DTSTART", Required),
+    ("UID", Required),
+    ("DTEND", Optional),
+    ("DURATION", Optional),
+    ("CREATED", Optional),
+    ("DESCRIPTION", Optional),
+    ("LAST-MODIFIED", Optional),
+    ("LOCATION", Optional),
+    ("RECURRENCE-ID", Optional),
+    ("RRULE", ShouldOnce),
+    ("SUMMARY", Optional),
+    ("CATEGORIES", Many),
+    ("COMMENT", Many),
+    ("CONTACT", Many),
+    ("EXDATE", Many),
+    ("RDATE", Many),
+];
+
+const TIMEZONE: &[PropertyRule] = &[
+    ("TZID", Required),
+    ("LAST-MODIFIED", Optional),
+    ("TZURL", Optional),
+];
+
+const TIMEZONE_TRANSITION: &[PropertyRule] = &[
+    ("DTSTART", Required),
+    ("TZOFFSETTO", Required),
+    ("TZOFFSETFROM", Required),
+    ("RRULE", ShouldOnce),
+    ("COMMENT", Many),
+    ("RDATE", Many),
+    ("TZNAME", Many),
+];
+
+const ALARM: &[PropertyRule] = &[
+    ("ACTION", Required),
+    ("TRIGGER", Required),
+    ("DURATION", Optional),
+    ("REPEAT", Optional),
+    ("ATTACH", Many),
+    ("DESCRIPTION", Optional),
+    ("SUMMARY", Optional),
+    ("ATTENDEE", Many),
+    // RFC 9074
+    ("UID", Optional),
+    ("ACKNOWLEDGED", Optional),
+    ("RELATED-TO", Many),
+];
+
+/// The property rules and allowed sub-components of a component.
+fn component_rules(name: &str) -> Option<(&'static [PropertyRule], &'static [&'static str])> {
+    Some(match name {
+        "VCALENDAR" => (
+            CALENDAR,
+            &[
+                "VEVENT",
+                "VTODO",
+                "VJOURNAL",
+                "VFREEBUSY",
+                "VTIMEZONE",
+                "VAVAILABILITY",
+            ][..],
+        ),
+        "VEVENT" => (
+            EVENT,
+            &["VALARM", "VLOCATION", "VRESOURCE", "PARTICIPANT"][..],
+        ),
+        "VTODO" => (
+            TODO,
+            &["VALARM", "VLOCATION", "VRESOURCE", "PARTICIPANT"][..],
+        ),
+        "VLOCATION" => (LOCATION, &[][..]),
+        "VRESOURCE" => (RESOURCE, &[][..]),
+        "PARTICIPANT" => (PARTICIPANT, &["VLOCATION", "VRESOURCE"][..]),
+        "VJOURNAL" => (JOURNAL, &[][..]),
+        "VFREEBUSY" => (FREEBUSY, &[][..]),
+        "VAVAILABILITY" => (AVAILABILITY, &["AVAILABLE"][..]),
+        "AVAILABLE" => (AVAILABLE, &[][..]),
+        "VTIMEZONE" => (TIMEZONE, &["STANDARD", "DAYLIGHT"][..]),
+        "STANDARD" | "DAYLIGHT" => (TIMEZONE_TRANSITION, &[][..]),
+        "VALARM" => (ALARM, &[][..]),
+        _ => return None,
+    })
+}
+
+/// Whether the property is defined by RFC 5545 for any component.
+fn is_known_property(name: &str) -> bool {
+    [
+        CALENDAR,
+        EVENT,
+        TODO,
+        JOURNAL,
+        FREEBUSY,
+        LOCATION,
+        RESOURCE,
+        PARTICIPANT,
+        AVAILABILITY,
+        AVAILABLE,
+        TIMEZONE,
+        TIMEZONE_TRANSITION,
+        ALARM,
+    ]
+    .iter()
+    .any(|rules| rules.iter().any(|(rule, _)| *rule == name))
+}
+
+#[derive(Debug, Default)]
+struct Validator {
+    diagnostics: Vec<Diagnostic>,
+    /// The TZIDs defined by the current calendar.
+    tzids: HashSet<String>,
+    /// Whether the current calendar has a METHOD.
+    has_method: bool,
+}
+
+impl Validator {
+    fn report(
+        &mut self,
+        severity: Severity,
+        code: DiagnosticCode,
+        location: Location,
+        message: String,
+    ) {
+        self.diagnostics.push(Diagnostic {
+            severity,
+            code,
+            location,
+            message,
+        });
+    }
+
+    fn calendar(&mut self, calendar: &Node, index: usize) {
+        self.tzids = calendar
+            .children
+            .iter()
+            .filter(|child| child.name == "VTIMEZONE")
+            .filter_map(|tz| tz.get_property("TZID")?.value.clone())
+            .collect();
+        self.has_method = calendar.get_property("METHOD").is_some();
+
+        let path = match index {
+            0 => "VCALENDAR".to_owned(),
+            _ => format!("VCALENDAR[{index}]"),
+        };
+        self.component(calendar, &path);
+    }
+
+    fn component(&mut self, node: &Node, path: &str) {
+        let Some((rules, sub_components)) = component_rules(&node.name) else {
+            return;
+        };
+
+        self.cardinality(node, path, rules);
+        for (line, prop) in &node.properties {
+            let location = node.location(path, Some((*line, prop)));
+            if !rules.iter().any(|(name, _)| *name == prop.name) && is_known_property(&prop.name) {
+                self.report(
+                    Severity::Error,
+                    DiagnosticCode::PropertyNotAllowed,
+                    location,
+                    format!("{} is not allowed in {}", prop.name, node.name),
+                );
+                continue;
+            }
+            self.value(&node.name, prop, location);
+        }
+
+        match node.name.as_ref() {
+            "VCALENDAR" => self.calendar_rules(node, path),
+            "VEVENT" => self.event_rules(node, path),
+            "VTODO" => self.todo_rules(node, path),
+            "VTIMEZONE" => self.timezone_rules(node, path),
+            "VALARM" => self.alarm_rules(node, path),
+            "VAVAILABILITY" | "AVAILABLE" => self.availability_rules(node, path),
+            _ => {}
+        }
+
+        for (index, child) in node.children.iter().enumerate() {
+            let same_name = node.children[..index]
+                .iter()
+                .filter(|sibling| sibling.name == child.name)
+                .count();
+            let child_path = format!("{path}/{}[{same_name}]", child.name);
+
+            if sub_components.contains(&child.name.as_ref()) {
+                self.component(child, &child_path);
+            } else if node.name != "VCALENDAR" || component_rules(&child.name).is_some() {
+                self.report(
+                    Severity::Error,
+                    DiagnosticCode::ComponentNotAllowed,
+                    child.location(&child_path, None),
+                    format!("{} is not allowed in {}", child.name, node.name),
+                );
+            } else if !child.name.starts_with("X-") {
+                self.report(
+                    Severity::Warning,
+                    DiagnosticCode::UnknownComponent,
+                    child.location(&child_path, None),
+                    format!("{} is not defined by RFC 5545", child.name),
+                );
+            }
+        }
+    }
+
+    fn cardinality(&mut self, node: &Node, path: &str, rules: &[PropertyRule]) {
+        for (name, occurrence) in rules {
+            let count = node.count(name);
+            match occurrence {
+                Required if count == 0 => self.report(
+                    Severity::Error,
+                    DiagnosticCode::MissingProperty,
+                    node.location(path, None),
+                    format!("{} requires {name}", node.name),
+                ),
+                Required | Optional if count > 1 => {
+                    self.duplicate(node, path, name, Severity::Error)
+                }
+                ShouldOnce if count > 1 => self.duplicate(node, path, name, Severity::Warning),
+                _ => {}
+            }
+        }
+    }
+
+    fn duplicate(&mut self, node: &Node, path: &str, name: &str, severity: Severity) {
+        let (line, prop) = node
+            .properties
+            .iter()
+            .filter(|(_, prop)| prop.name == name)
+            .nth(1)
+            .expect("property occurs more than once");
+        let location = node.location(path, Some((*line, prop)));
+        let message = match severity {
+            Severity::Error => format!("{name} must not occur more than once"),
+            Severity::Warning => format!("{name} should not occur more than once"),
+        };
+        self.report(
+            severity,
+            DiagnosticCode::DuplicateProperty,
+            location,
+            message,
+        );
+    }
+
+    /// Check the value of a property against its value type.
+    fn value(&mut self, component: &str, prop: &Property, location: Location) {
+        if let Some(tzid) = prop.get_tzid()
+            && !self.tzids.contains(tzid)
+        {
+            self.report(
+                Severity::Error,
+                DiagnosticCode::UnknownTzid,
+                location.clone(),
+                format!("TZID {tzid} refers to no VTIMEZONE"),
+            );
+        }
+
+        // Empty values are parsed as no value
+        let value = prop.value.as_deref().unwrap_or_default();
+        let value_type = value_type(prop, default_value_type(&prop.name));
+
+        let result = match prop.name.as_str() {
+            "DTSTAMP" | "CREATED" | "LAST-MODIFIED" | "COMPLETED" => check_utc_date_time(value),
+            "DTSTART" | "DTEND" | "DUE" | "RECURRENCE-ID" | "EXDATE" => {
+                check_list(value, |value| check_date_or_date_time(&value_type, value))
+            }
+            "RDATE" if value_type == "PERIOD" => check_list(value, check_period),
+            "RDATE" => check_list(value, |value| check_date_or_date_time(&value_type, value)),
+            "FREEBUSY" => check_list(value, check_period),
+            "DURATION" | "REFRESH-INTERVAL" => check_duration(value),
+            "TRIGGER" if value_type == "DATE-TIME" => check_utc_date_time(value),
+            "TRIGGER" => check_duration(value),
+            "PRIORITY" => check_integer(value, 0..=9, "a priority between 0 and 9"),
+            "PERCENT-COMPLETE" => check_integer(value, 0..=100, "a percentage between 0 and 100"),
+            "SEQUENCE" | "REPEAT" => {
+                check_integer(value, 0..=i64::from(i32::MAX), "a non-negative integer")
+            }
+            "GEO" => check_geo(value),
+            "TZOFFSETFROM" | "TZOFFSETTO" => check_utc_offset(value),
+            "VERSION" => check_enum(value, &["2.0"]),
+            "TRANSP" => check_enum(value, &["OPAQUE", "TRANSPARENT"]),
+            "STATUS" => match component {
+                "VEVENT" => check_enum(value, &["TENTATIVE", "CONFIRMED", "CANCELLED"]),
+                "VTODO" => check_enum(
+                    value,
+                    &["NEEDS-ACTION", "COMPLETED", "IN-PROCESS", "CANCELLED"],
+                ),
+                "VJOURNAL" => check_enum(value, &["DRAFT", "FINAL", "CANCELLED"]),
+                _ => Ok(()),
+            },
+            "RRULE" => value
+                .parse::<rrule::RRule<rrule::Unvalidated>>()
+                .map(|_| ())
+                .map_err(|err| format!("{value} is not a valid recurrence rule: {err}")),
+            _ => Ok(()),
+        };
+
+        if let Err(message) = result {
+            self.report(
+                Severity::Error,
+                DiagnosticCode::InvalidValue,
+                location,
+                message,
+            );
+        }
+    }
+
+    fn calendar_rules(&mut self, node: &Node, path: &str) {
+        if node.children.is_empty() {
+            self.report(
+                Severity::Error,
+                DiagnosticCode::MissingComponent,
+                node.location(path, None),
+                "VCALENDAR requires at least one component".to_owned(),
+            );
+        }
+    }
+
+    fn event_rules(&mut self, node: &Node, path: &str) {
+        if !self.has_method && node.get_property("DTSTART").is_none() {
+            self.report(
+                Severity::Error,
+                DiagnosticCode::MissingProperty,
+                node.location(path, None),
+                "VEVENT requires DTSTART if the calendar has no METHOD".to_owned(),
+            );
+        }
+        self.conflict(node, path, "DTEND", "DURATION");
+        self.type_mismatch(node, path, "DTEND");
+    }
+
+    fn todo_rules(&mut self, node: &Node, path: &str) {
+        self.conflict(node, path, "DUE", "DURATION");
+        if node.get_property("DURATION").is_some() && node.get_property("DTSTART").is_none() {
+            self.report(
+                Severity::Error,
+                DiagnosticCode::MissingProperty,
+                node.property_location(path, "DURATION"),
+                "DURATION requires DTSTART".to_owned(),
+            );
+        }
+        self.type_mismatch(node, path, "DUE");
+    }
+
+    fn availability_rules(&mut self, node: &Node, path: &str) {
+        self.conflict(node, path, "DTEND", "DURATION");
+        if node.get_property("DURATION").is_some() && node.get_property("DTSTART").is_none() {
+            self.report(
+                Severity::Error,
+                DiagnosticCode::MissingProperty,
+                node.property_location(path, "DURATION"),
+                "DURATION requires DTSTART".to_owned(),
+            );
+        }
+        self.type_mismatch(node, path, "DTEND");
+    }
+
+    fn timezone_rules(&mut self, node: &Node, path: &str) {
+        if !node.has_child("STANDARD") && !node.has_child("DAYLIGHT") {
+            self.report(
+                Severity::Error,
+                DiagnosticCode::MissingComponent,
+                node.location(path, None),
+                "VTIMEZONE requires a STANDARD or DAYLIGHT component".to_owned(),
+            );
+        }
+    }
+
+    fn alarm_rules(&mut self, node: &Node, path: &str) {
+        match (node.get_property("DURATION"), node.get_property("REPEAT")) {
+            (Some(_), None) => self.report(
+                Severity::Error,
+                DiagnosticCode::MissingProperty,
+                node.property_location(path, "DURATION"),
+                "DURATION requires REPEAT".to_owned(),
+            ),
+            (None, Some(_)) => self.report(
+                Severity::Error,
+                DiagnosticCode::MissingProperty,
+                node.property_location(path, "REPEAT"),
+                "REPEAT requires DURATION".to_owned(),
+            ),
+            _ => {}
+        }
+
+        let action = node
+            .get_property("ACTION")
+            .and_then(|prop| prop.value.as_deref())
+            .map(str::to_uppercase);
+        let required: &[&str] = match action.as_deref() {
+            Some("DISPLAY") => &["DESCRIPTION"],
+            Some("EMAIL") => &["DESCRIPTION", "SUMMARY", "ATTENDEE"],
+            Some("AUDIO") => {
+                if node.count("ATTACH") > 1 {
+                    self.duplicate(node, path, "ATTACH", Severity::Error);
+                }
+                &[]
+            }
+            _ => &[],
+        };
+        for name in required {
+            if node.get_property(name).is_none() {
+                self.report(
+                    Severity::Error,
+                    DiagnosticCode::MissingProperty,
+                    node.location(path, None),
+                    format!(
+                        "{} alarms require {name}",
+                        action.as_deref().unwrap_or_default()
+                    ),
+                );
+            }
+        }
+    }
+
+    fn conflict(&mut self, node: &Node, path: &str, first: &str, second: &str) {
+        if node.get_property(first).is_some() && node.get_property(second).is_some() {
+            self.report(
+                Severity::Error,
+                DiagnosticCode::PropertyConflict,
+                node.property_location(path, second),
+                format!("{first} and {second} must not occur together"),
+            );
+        }
+    }
+
+    /// Check that the property `name` has the same value type as DTSTART.
+    fn type_mismatch(&mut self, node: &Node, path: &str, name: &str) {
+        let (Some(start), Some(end)) = (node.get_property("DTSTART"), node.get_property(name))
+        else {
+            return;
+        };
+        let (start_type, end_type) = (value_type(start, "DATE-TIME"), value_type(end, "DATE-TIME"));
+        if start_type != end_type {
+            self.report(
+                Severity::Error,
+                DiagnosticCode::ValueTypeMismatch,
+                node.property_location(path, name),
+                format!("{name} is a {end_type} but DTSTART is a {start_type}"),
+            );
+        }
+    }
+}
+
+/// The value type from the VALUE parameter or the default value type.
+fn value_type(prop: &Property, default: &str) -> String {
+    prop.get_value_type()
+        .map_or_else(|| default.to_owned(), str::to_uppercase)
+}
+
+/// The default value type of the properties whose value type can be changed.
+fn default_value_type(name: &str) -> &'static str {
+    match name {
+        "TRIGGER" => "DURATION",
+        _ => "DATE-TIME",
+    }
+}
+
+fn check_list(value: &str, check: impl Fn(&str) -> Result<(), String>) -> Result<(), String> {
+    value.split(',').try_for_each(check)
+}
+
+fn check_date_or_date_time(value_type: &str, value: &str) -> Result<(), String> {
+    match value_type {
+        "DATE" => NaiveDate::parse_from_str(value, "%Y%m%d")
+            .map(|_| ())
+            .map_err(|_| format!("{value} is not a DATE")),
+        "DATE-TIME" => CalDateTime::parse(value, None)
+            .map(|_| ())
+            .map_err(|_| format!("{value} is not a DATE-TIME")),
+        _ => Err(format!("value type {value_type} is not allowed")),
+    }
+}
+
+fn check_utc_date_time(value: &str) -> Result<(), String> {
+    match CalDateTime::parse(value, None) {
+        Ok(_) if value.ends_with('Z') => Ok(()),
+        _ => Err(format!("{value} is not a DATE-TIME in UTC")),
+    }
+}
+
+fn check_duration(value: &str) -> Result<(), String> {
+    parse_duration(value)
+        .map(|_| ())
+        .map_err(|_| format!("{value} is not a DURATION"))
+}
+
+fn check_period(value: &str) -> Result<(), String> {
+    Period::parse(value, None)
+        .map(|_| ())
+        .map_err(|_| format!("{value} is not a PERIOD"))
+}
+
+fn check_integer(
+    value: &str,
+    range: std::ops::RangeInclusive<i64>,
+    expected: &str,
+) -> Result<(), String> {
+    match value.parse::<i64>() {
+        Ok(number) if range.contains(&number) => Ok(()),
+        _ => Err(format!("{value} is not {expected}")),
+    }
+}
+
+fn check_geo(value: &str) -> Result<(), String> {
+    let valid = value.split_once(';').is_some_and(|(lat, lon)| {
+        matches!(lat.parse::<f64>(), Ok(lat) if (-90.0..=90.0).contains(&lat))
+            && matches!(lon.parse::<f64>(), Ok(lon) if (-180.0..=180.0).contains(&lon))
+    });
+    match valid {
+        true => Ok(()),
+        false => Err(format!("{value} is not a latitude and longitude")),
+    }
+}
+
+fn check_utc_offset(value: &str) -> Result<(), String> {
+    let valid = matches!(value.len(), 5 | 7)
+        && (value.starts_with('+') || value.starts_with('-'))
+        && value[1..].bytes().all(|b| b.is_ascii_digit())
+        && value[3..5] < *"60"
+        && value.get(5..).is_none_or(|seconds| seconds < "60")
+        // -0000 is not allowed
+        && !(value.starts_with('-') && value[1..].bytes().all(|b| b == b'0'));
+    match valid {
+        true => Ok(()),
+        false => Err(format!("{value} is not a UTC offset")),
+    }
+}
+
+fn check_enum(value: &str, allowed: &[&str]) -> Result<(), String> {
+    match allowed.contains(&value.to_uppercase().as_str()) {
+        true => Ok(()),
+        false => Err(format!("{value} is not one of {}", allowed.join(", "))),
+    }
+}
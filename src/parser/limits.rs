@@ -0,0 +1,135 @@
+use crate::property::{Property, PropertyError};
+
+/// Limits for parsing untrusted input.
+///
+/// Every limit is disabled by default. Exceeding one of them stops parsing with an error instead
+/// of reading or allocating without bound:
+/// * `max_line_length` and `max_bytes` are enforced by the `LineReader` and yield
+///   `LineError::LineTooLong` and `LineError::InputTooLarge`.
+/// * The structural limits apply to every component returned by a `ComponentParser` and yield a
+///   `ParserError::LimitExceeded`.
+///
+/// # Examples
+///
+/// ```rust
+/// use ical::parser::{LimitError, ParserError, ParserLimits};
+///
+/// let input = "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nBEGIN:VALARM\r\n";
+/// let limits = ParserLimits {
+///     max_depth: Some(2),
+///     ..Default::default()
+/// };
+/// let mut parser = ical::IcalParser::with_limits(input.as_bytes(), limits);
+/// assert_eq!(
+///     parser.next().unwrap().unwrap_err(),
+///     ParserError::LimitExceeded(LimitError::TooDeep(2))
+/// );
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ParserLimits {
+    /// Maximum length of an unfolded line in bytes.
+    pub max_line_length: Option<usize>,
+    /// Maximum number of properties of a single component.
+    pub max_properties: Option<usize>,
+    /// Maximum nesting depth of components, the top-level component having a depth of 1.
+    pub max_depth: Option<usize>,
+    /// Maximum number of sub-components within a top-level component.
+    pub max_components: Option<usize>,
+    /// Maximum size of the whole input in bytes.
+    pub max_bytes: Option<usize>,
+}
+
+#[derive(Debug, Clone, thiserror::Error, PartialEq, Eq)]
+pub enum LimitError {
+    #[error("more than {0} properties in a component")]
+    TooManyProperties(usize),
+    #[error("components nested deeper than {0} levels")]
+    TooDeep(usize),
+    #[error("more than {0} sub-components")]
+    TooManyComponents(usize),
+}
+
+/// Track the structure of a top-level component against the `ParserLimits`.
+#[derive(Debug)]
+pub(crate) struct LimitTracker<'l> {
+    limits: &'l ParserLimits,
+    /// The number of properties of every open component.
+    properties: Vec<usize>,
+    components: usize,
+}
+
+impl<'l> LimitTracker<'l> {
+    /// Start tracking after the header of a top-level component.
+    pub(crate) fn new(limits: &'l ParserLimits) -> Self {
+        Self {
+            limits,
+            properties: vec![0],
+            components: 0,
+        }
+    }
+
+    /// Check the next property of the component.
+    pub(crate) fn check(&mut self, prop: &Property) -> Result<(), LimitError> {
+        if prop.name.eq_ignore_ascii_case("BEGIN") {
+            self.components += 1;
+            if let Some(max) = self.limits.max_components
+                && self.components > max
+            {
+                return Err(LimitError::TooManyComponents(max));
+            }
+            self.properties.push(0);
+            if let Some(max) = self.limits.max_depth
+                && self.properties.len() > max
+            {
+                return Err(LimitError::TooDeep(max));
+            }
+        } else if prop.name.eq_ignore_ascii_case("END") {
+            self.properties.pop();
+        } else if let Some(count) = self.properties.last_mut() {
+            *count += 1;
+            if let Some(max) = self.limits.max_properties
+                && *count > max
+            {
+                return Err(LimitError::TooManyProperties(max));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A `PropertySource` that ends once a limit is exceeded.
+///
+/// The error is kept to replace the `ParserError::NotComplete` of the interrupted component.
+pub(crate) struct LimitedSource<'p, 'l, P> {
+    source: &'p mut P,
+    tracker: LimitTracker<'l>,
+    pub(crate) error: Option<LimitError>,
+}
+
+impl<'p, 'l, P> LimitedSource<'p, 'l, P> {
+    pub(crate) fn new(source: &'p mut P, limits: &'l ParserLimits) -> Self {
+        Self {
+            source,
+            tracker: LimitTracker::new(limits),
+            error: None,
+        }
+    }
+}
+
+impl<P: Iterator<Item = Result<Property, PropertyError>>> Iterator for LimitedSource<'_, '_, P> {
+    type Item = Result<Property, PropertyError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.error.is_some() {
+            return None;
+        }
+        let item = self.source.next()?;
+        if let Ok(prop) = &item
+            && let Err(err) = self.tracker.check(prop)
+        {
+            self.error = Some(err);
+            return None;
+        }
+        Some(item)
+    }
+}
@@ -6,11 +6,17 @@
 //!
 //!
 
+mod borrowed;
 pub mod ical;
+mod limits;
+mod options;
 pub mod vcard;
+pub use borrowed::*;
+pub use limits::*;
+pub use options::*;
 
 // Sys mods
-use crate::types::InvalidDuration;
+use crate::types::{CalDateTimeError, InvalidDuration};
 use std::io::BufRead;
 use std::marker::PhantomData;
 // Internal mods
@@ -19,6 +25,9 @@ use crate::{
     property::{Property, PropertyError, PropertyParser},
 };
 
+#[cfg(feature = "tokio")]
+use crate::{line::AsyncLineReader, property::AsyncPropertyParser};
+
 #[derive(Debug, thiserror::Error, PartialEq, Eq)]
 pub enum ParserError {
     #[error("invalid component")]
@@ -35,8 +44,21 @@ pub enum ParserError {
     PropertyConflict(&'static str),
     #[error(transparent)]
     InvalidDuration(#[from] InvalidDuration),
+    #[error(transparent)]
+    InvalidDateTime(#[from] CalDateTimeError),
+    #[error("invalid value for property: {0}")]
+    InvalidValue(&'static str),
+    #[error("limit exceeded: {0}")]
+    LimitExceeded(#[from] LimitError),
 }
 
+/// A source of parsed properties that components are filled from.
+///
+/// This is implemented for every iterator over property results, most notably `PropertyParser`.
+pub trait PropertySource: Iterator<Item = Result<Property, PropertyError>> {}
+
+impl<I: Iterator<Item = Result<Property, PropertyError>>> PropertySource for I {}
+
 /// An immutable interface for an Ical/Vcard component.
 /// This is also implemented by verified components
 pub trait Component: Clone {
@@ -67,10 +89,11 @@ pub trait ComponentMut: Component + Default {
     type Verified: Component<Unverified = Self>;
 
     /// Add the givent sub component.
-    fn add_sub_component<B: BufRead>(
+    fn add_sub_component<P: PropertySource>(
         &mut self,
         value: &str,
-        line_parser: &mut PropertyParser<B>,
+        line_parser: &mut P,
+        options: &VerifyOptions,
     ) -> Result<(), ParserError>;
 
     fn get_properties_mut(&mut self) -> &mut Vec<Property>;
@@ -94,12 +117,23 @@ pub trait ComponentMut: Component + Default {
         self.add_property(prop);
     }
 
-    fn verify(self) -> Result<Self::Verified, ParserError>;
+    fn verify(self) -> Result<Self::Verified, ParserError> {
+        self.verify_with(&VerifyOptions::default())
+    }
+
+    /// Verify the component with the checks configured by `options`.
+    fn verify_with(self, options: &VerifyOptions) -> Result<Self::Verified, ParserError>;
 
     /// Parse the content from `line_parser` and fill the component with.
-    fn parse<B: BufRead>(
+    fn parse<P: PropertySource>(&mut self, line_parser: &mut P) -> Result<(), ParserError> {
+        self.parse_with(line_parser, &VerifyOptions::default())
+    }
+
+    /// Parse the content from `line_parser`, verifying sub-components with `options`.
+    fn parse_with<P: PropertySource>(
         &mut self,
-        line_parser: &mut PropertyParser<B>,
+        line_parser: &mut P,
+        options: &VerifyOptions,
     ) -> Result<(), ParserError> {
         loop {
             let line = line_parser.next().ok_or(ParserError::NotComplete)??;
@@ -107,7 +141,7 @@ pub trait ComponentMut: Component + Default {
             match line.name.to_uppercase().as_str() {
                 "END" => break,
                 "BEGIN" => match line.value {
-                    Some(v) => self.add_sub_component(v.as_str(), line_parser)?,
+                    Some(v) => self.add_sub_component(v.as_str(), line_parser, options)?,
                     None => return Err(ParserError::NotComplete),
                 },
 
@@ -117,9 +151,16 @@ pub trait ComponentMut: Component + Default {
         Ok(())
     }
 
-    fn from_parser<B: BufRead>(line_parser: &mut PropertyParser<B>) -> Result<Self, ParserError> {
+    fn from_parser<P: PropertySource>(line_parser: &mut P) -> Result<Self, ParserError> {
+        Self::from_parser_with(line_parser, &VerifyOptions::default())
+    }
+
+    fn from_parser_with<P: PropertySource>(
+        line_parser: &mut P,
+        options: &VerifyOptions,
+    ) -> Result<Self, ParserError> {
         let mut out = Self::default();
-        out.parse(line_parser)?;
+        out.parse_with(line_parser, options)?;
         Ok(out)
     }
 }
@@ -127,21 +168,62 @@ pub trait ComponentMut: Component + Default {
 /// Reader returning `IcalCalendar` object from a `BufRead`.
 pub struct ComponentParser<B: BufRead, T: Component> {
     line_parser: PropertyParser<B>,
+    limits: ParserLimits,
+    options: VerifyOptions,
     _t: PhantomData<T>,
 }
 
 impl<B: BufRead, T: Component> ComponentParser<B, T> {
     /// Return a new `IcalParser` from a `Reader`.
     pub fn new(reader: B) -> ComponentParser<B, T> {
-        let line_reader = LineReader::new(reader);
-        let line_parser = PropertyParser::new(line_reader);
+        Self::from_line_reader(LineReader::new(reader))
+    }
+
+    /// Return a new `IcalParser` from a `Reader` that enforces `limits`.
+    pub fn with_limits(reader: B, limits: ParserLimits) -> ComponentParser<B, T> {
+        Self::new(reader).limits(limits)
+    }
 
+    /// Return a new `IcalParser` from a configured `LineReader`.
+    pub fn from_line_reader(line_reader: LineReader<B>) -> ComponentParser<B, T> {
         ComponentParser {
-            line_parser,
+            line_parser: PropertyParser::new(line_reader),
+            limits: ParserLimits::default(),
+            options: VerifyOptions::default(),
             _t: Default::default(),
         }
     }
 
+    /// Verify the components with `options` instead of the default `VerifyOptions`.
+    pub fn with_verify_options(mut self, options: VerifyOptions) -> ComponentParser<B, T> {
+        self.options = options;
+        self
+    }
+
+    /// Enforce `limits`, e.g. on a parser created with `from_line_reader`.
+    ///
+    /// The line limits that are set replace the ones of the `LineReader`.
+    pub fn limits(self, limits: ParserLimits) -> ComponentParser<B, T> {
+        let mut line_reader = self.line_parser.into_line_reader();
+        if let Some(max) = limits.max_line_length {
+            line_reader = line_reader.with_max_line_length(max);
+        }
+        if let Some(max) = limits.max_bytes {
+            line_reader = line_reader.with_max_bytes(max);
+        }
+
+        ComponentParser {
+            line_parser: PropertyParser::new(line_reader),
+            limits,
+            ..self
+        }
+    }
+
+    /// Return the underlying `LineReader`.
+    pub fn line_reader(&self) -> &LineReader<B> {
+        self.line_parser.line_reader()
+    }
+
     /// Read the next line and check if it's a valid VCALENDAR start.
     fn check_header(&mut self) -> Result<Option<()>, ParserError> {
         let line = match self.line_parser.next() {
@@ -149,16 +231,21 @@ impl<B: BufRead, T: Component> ComponentParser<B, T> {
             None => return Ok(None),
         };
 
-        if line.name.to_uppercase() != "BEGIN"
-            || line.value.is_none()
-            || !T::NAMES.contains(&line.value.as_ref().unwrap().to_uppercase().as_str())
-            || !line.params.is_empty()
-        {
-            return Err(ParserError::MissingHeader);
-        }
+        check_header::<T>(&line).map(Some)
+    }
+}
 
-        Ok(Some(()))
+/// Check if the line is a valid start of the component `T`.
+fn check_header<T: Component>(line: &Property) -> Result<(), ParserError> {
+    if line.name.to_uppercase() != "BEGIN"
+        || line.value.is_none()
+        || !T::NAMES.contains(&line.value.as_ref().unwrap().to_uppercase().as_str())
+        || !line.params.is_empty()
+    {
+        return Err(ParserError::MissingHeader);
     }
+
+    Ok(())
 }
 
 impl<B: BufRead, T: Component> Iterator for ComponentParser<B, T> {
@@ -171,9 +258,10 @@ impl<B: BufRead, T: Component> Iterator for ComponentParser<B, T> {
         };
 
         let mut comp = T::Unverified::default();
-        let result = match comp.parse(&mut self.line_parser) {
-            Ok(_) => comp.verify(),
-            Err(err) => Err(err),
+        let mut source = LimitedSource::new(&mut self.line_parser, &self.limits);
+        let result = match comp.parse_with(&mut source, &self.options) {
+            Ok(_) => comp.verify_with(&self.options),
+            Err(err) => Err(source.error.map_or(err, ParserError::LimitExceeded)),
         };
 
         #[cfg(feature = "test")]
@@ -188,3 +276,136 @@ impl<B: BufRead, T: Component> Iterator for ComponentParser<B, T> {
         Some(result)
     }
 }
+
+/// Reader returning `IcalCalendar` object from an `AsyncBufRead`.
+///
+/// This is the asynchronous counterpart to `ComponentParser`. The properties of a component are
+/// read asynchronously and then assembled and verified like in `ComponentParser`.
+#[cfg(feature = "tokio")]
+pub struct AsyncComponentParser<B: tokio::io::AsyncBufRead + Unpin, T: Component> {
+    line_parser: AsyncPropertyParser<B>,
+    limits: ParserLimits,
+    options: VerifyOptions,
+    _t: PhantomData<T>,
+}
+
+#[cfg(feature = "tokio")]
+impl<B: tokio::io::AsyncBufRead + Unpin, T: Component> AsyncComponentParser<B, T> {
+    /// Return a new `AsyncComponentParser` from a `Reader`.
+    pub fn new(reader: B) -> AsyncComponentParser<B, T> {
+        Self::from_line_reader(AsyncLineReader::new(reader))
+    }
+
+    /// Return a new `AsyncComponentParser` from a `Reader` that enforces `limits`.
+    pub fn with_limits(reader: B, limits: ParserLimits) -> AsyncComponentParser<B, T> {
+        Self::new(reader).limits(limits)
+    }
+
+    /// Return a new `AsyncComponentParser` from a configured `AsyncLineReader`.
+    pub fn from_line_reader(line_reader: AsyncLineReader<B>) -> AsyncComponentParser<B, T> {
+        AsyncComponentParser {
+            line_parser: AsyncPropertyParser::new(line_reader),
+            limits: ParserLimits::default(),
+            options: VerifyOptions::default(),
+            _t: Default::default(),
+        }
+    }
+
+    /// Verify the components with `options` instead of the default `VerifyOptions`.
+    pub fn with_verify_options(mut self, options: VerifyOptions) -> AsyncComponentParser<B, T> {
+        self.options = options;
+        self
+    }
+
+    /// Enforce `limits`, e.g. on a parser created with `from_line_reader`.
+    ///
+    /// The line limits that are set replace the ones of the `AsyncLineReader`.
+    pub fn limits(self, limits: ParserLimits) -> AsyncComponentParser<B, T> {
+        let mut line_reader = self.line_parser.into_line_reader();
+        if let Some(max) = limits.max_line_length {
+            line_reader = line_reader.with_max_line_length(max);
+        }
+        if let Some(max) = limits.max_bytes {
+            line_reader = line_reader.with_max_bytes(max);
+        }
+
+        AsyncComponentParser {
+            line_parser: AsyncPropertyParser::new(line_reader),
+            limits,
+            ..self
+        }
+    }
+
+    /// Return the underlying `AsyncLineReader`.
+    pub fn line_reader(&self) -> &AsyncLineReader<B> {
+        self.line_parser.line_reader()
+    }
+
+    /// Read the next line and check if it's a valid VCALENDAR start.
+    async fn check_header(&mut self) -> Result<Option<()>, ParserError> {
+        let line = match self.line_parser.next().await {
+            Some(val) => val.map_err(ParserError::PropertyError)?,
+            None => return Ok(None),
+        };
+
+        check_header::<T>(&line).map(Some)
+    }
+
+    /// Read all properties up to the `END` of the current component.
+    ///
+    /// Reading stops early at the first error or if the input ends.
+    async fn read_component(&mut self) -> Result<Vec<Result<Property, PropertyError>>, LimitError> {
+        let mut properties = vec![];
+        let mut depth = 0usize;
+        let mut tracker = LimitTracker::new(&self.limits);
+
+        while let Some(result) = self.line_parser.next().await {
+            if let Ok(prop) = &result {
+                tracker.check(prop)?;
+            }
+            let done = match &result {
+                Ok(prop) if prop.name.eq_ignore_ascii_case("BEGIN") => {
+                    depth += 1;
+                    false
+                }
+                Ok(prop) if prop.name.eq_ignore_ascii_case("END") => match depth.checked_sub(1) {
+                    Some(new_depth) => {
+                        depth = new_depth;
+                        false
+                    }
+                    None => true,
+                },
+                Ok(_) => false,
+                Err(_) => true,
+            };
+            properties.push(result);
+            if done {
+                break;
+            }
+        }
+
+        Ok(properties)
+    }
+
+    /// Read, parse and verify the next component.
+    pub async fn next(
+        &mut self,
+    ) -> Option<Result<<T::Unverified as ComponentMut>::Verified, ParserError>> {
+        match self.check_header().await {
+            Ok(res) => res?,
+            Err(err) => return Some(Err(err)),
+        };
+
+        let properties = match self.read_component().await {
+            Ok(properties) => properties,
+            Err(err) => return Some(Err(err.into())),
+        };
+        let mut comp = T::Unverified::default();
+        let result = match comp.parse_with(&mut properties.into_iter(), &self.options) {
+            Ok(_) => comp.verify_with(&self.options),
+            Err(err) => Err(err),
+        };
+
+        Some(result)
+    }
+}
@@ -0,0 +1,117 @@
+use crate::parser::{Component, ParserError};
+
+/// How the `METHOD` property of a calendar is treated.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MethodPolicy {
+    #[default]
+    Allowed,
+    /// Scheduling messages ([RFC5546](https://tools.ietf.org/html/rfc5546)) must have a METHOD.
+    Required,
+    /// Calendar object resources of CalDAV servers
+    /// ([RFC4791](https://tools.ietf.org/html/rfc4791#section-4.1)) must not have a METHOD.
+    Forbidden,
+}
+
+/// Options for the checks done by `ComponentMut::verify_with`.
+///
+/// The default is the `lenient` profile, which matches `ComponentMut::verify`. Checks that the
+/// getters of verified components rely on, like the UID of events, can't be relaxed.
+///
+/// # Examples
+///
+/// ```rust
+/// use ical::parser::{ParserError, VerifyOptions};
+///
+/// let input = "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nUID:1\r\nDTSTART:20250101T100000Z\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n";
+///
+/// let mut parser = ical::IcalParser::new(input.as_bytes());
+/// assert!(parser.next().unwrap().is_ok());
+///
+/// let mut parser =
+///     ical::IcalParser::new(input.as_bytes()).with_verify_options(VerifyOptions::strict());
+/// assert_eq!(
+///     parser.next().unwrap().unwrap_err(),
+///     ParserError::MissingProperty("DTSTAMP")
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifyOptions {
+    /// Require DTSTAMP in VEVENT and VFREEBUSY. VTODO and VJOURNAL always require it.
+    pub require_dtstamp: bool,
+    /// Require DTSTART in VEVENT. Scheduling messages like REFRESH and DECLINECOUNTER only refer
+    /// to an event and may leave it out.
+    pub require_dtstart: bool,
+    /// Allow VALARM as a direct sub-component of VCALENDAR.
+    pub allow_calendar_alarms: bool,
+    /// Require the ACTION and a valid TRIGGER of a VALARM, REPEAT and DURATION to occur
+    /// together and the properties the ACTION depends on, like the DESCRIPTION of a DISPLAY
+    /// alarm.
+    pub require_alarm_properties: bool,
+    /// Reject malformed and out-of-range values of properties that are ignored otherwise, like a
+    /// PRIORITY above 9.
+    pub check_values: bool,
+    pub method: MethodPolicy,
+}
+
+impl Default for VerifyOptions {
+    fn default() -> Self {
+        Self::lenient()
+    }
+}
+
+impl VerifyOptions {
+    /// Follow [RFC5545](https://tools.ietf.org/html/rfc5545) as closely as possible.
+    pub fn strict() -> Self {
+        Self {
+            require_dtstamp: true,
+            require_dtstart: true,
+            allow_calendar_alarms: false,
+            require_alarm_properties: true,
+            check_values: true,
+            method: MethodPolicy::Allowed,
+        }
+    }
+
+    /// Accept the common deviations of calendar producers.
+    pub fn lenient() -> Self {
+        Self {
+            require_dtstamp: false,
+            require_dtstart: true,
+            allow_calendar_alarms: true,
+            require_alarm_properties: false,
+            check_values: false,
+            method: MethodPolicy::Allowed,
+        }
+    }
+
+    /// Accept what CalDAV clients upload to a calendar collection.
+    pub fn caldav() -> Self {
+        Self {
+            method: MethodPolicy::Forbidden,
+            ..Self::lenient()
+        }
+    }
+
+    /// Check scheduling messages of [RFC5546](https://tools.ietf.org/html/rfc5546).
+    pub fn itip() -> Self {
+        Self {
+            require_dtstart: false,
+            method: MethodPolicy::Required,
+            ..Self::strict()
+        }
+    }
+}
+
+/// Check the METHOD of a calendar against the `MethodPolicy` of `options`.
+pub(crate) fn check_method<T: Component>(
+    calendar: &T,
+    options: &VerifyOptions,
+) -> Result<(), ParserError> {
+    match (options.method, calendar.get_property("METHOD")) {
+        (MethodPolicy::Required, None) => Err(ParserError::MissingProperty("METHOD")),
+        (MethodPolicy::Forbidden, Some(_)) => Err(ParserError::PropertyConflict(
+            "METHOD is not allowed in calendar object resources",
+        )),
+        _ => Ok(()),
+    }
+}
@@ -1,6 +1,5 @@
-use crate::parser::{Component, ComponentMut, ParserError};
-use crate::property::{Property, PropertyParser};
-use std::io::BufRead;
+use crate::parser::{Component, ComponentMut, ParserError, PropertySource, VerifyOptions};
+use crate::property::Property;
 
 #[derive(Debug, Clone, Default, Eq, PartialEq, Hash)]
 pub struct VcardContact<const VERIFIED: bool = true> {
@@ -44,15 +43,16 @@ impl ComponentMut for VcardContact<false> {
         &mut self.properties
     }
 
-    fn add_sub_component<B: BufRead>(
+    fn add_sub_component<P: PropertySource>(
         &mut self,
         _: &str,
-        _: &mut PropertyParser<B>,
+        _: &mut P,
+        _: &VerifyOptions,
     ) -> Result<(), ParserError> {
         Err(ParserError::InvalidComponent)
     }
 
-    fn verify(self) -> Result<Self::Verified, ParserError> {
+    fn verify_with(self, _options: &VerifyOptions) -> Result<Self::Verified, ParserError> {
         let verified = VcardContact {
             properties: self.properties,
         };
@@ -32,7 +32,10 @@
 //! ```
 
 pub mod component;
-use crate::parser::ComponentParser;
+use crate::parser::{BorrowedComponentParser, ComponentParser};
 use component::VcardContact;
 
 pub type VcardParser<B> = ComponentParser<B, VcardContact>;
+pub type BorrowedVcardParser<'a> = BorrowedComponentParser<'a, VcardContact>;
+#[cfg(feature = "tokio")]
+pub type AsyncVcardParser<B> = crate::parser::AsyncComponentParser<B, VcardContact>;
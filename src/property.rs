@@ -38,6 +38,7 @@
 //! ```
 
 // Sys mods
+use std::borrow::Cow;
 use std::fmt;
 use std::io::BufRead;
 use std::iter::Iterator;
@@ -45,9 +46,12 @@ use std::iter::Iterator;
 // Internal mods
 use crate::{
     PARAM_DELIMITER, PARAM_NAME_DELIMITER, PARAM_QUOTE, PARAM_VALUE_DELIMITER, VALUE_DELIMITER,
-    line::{Line, LineReader},
+    line::{BorrowedLineReader, Line, LineError, LineReader},
 };
 
+#[cfg(feature = "tokio")]
+use crate::line::AsyncLineReader;
+
 #[derive(Debug, thiserror::Error, PartialEq, Eq)]
 pub enum PropertyError {
     #[error("Line {0}: Missing property name.")]
@@ -62,6 +66,8 @@ pub enum PropertyError {
     MissingParamKey(usize),
     #[error("Line {0}: Missing value.")]
     MissingValue(usize),
+    #[error(transparent)]
+    Line(#[from] LineError),
 }
 
 /// A VCARD/ICAL property.
@@ -115,6 +121,85 @@ impl fmt::Display for Property {
     }
 }
 
+/// A VCARD/ICAL property borrowing from the parsed input.
+///
+/// Each part only owns its content if the line had to be unfolded or the parameter name had to
+/// be uppercased.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Hash)]
+pub struct BorrowedProperty<'a> {
+    /// Property name.
+    pub name: Cow<'a, str>,
+    /// Property list of parameters.
+    pub params: Vec<(Cow<'a, str>, Vec<Cow<'a, str>>)>,
+    /// Property value.
+    pub value: Option<Cow<'a, str>>,
+}
+
+impl BorrowedProperty<'_> {
+    pub fn get_param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(key, _)| name == key)
+            .and_then(|(_, value)| value.iter().map(Cow::as_ref).next())
+    }
+
+    pub fn get_tzid(&self) -> Option<&str> {
+        self.get_param("TZID")
+    }
+
+    pub fn get_value_type(&self) -> Option<&str> {
+        self.get_param("VALUE")
+    }
+
+    /// Convert into an owned `Property`.
+    pub fn into_owned(self) -> Property {
+        Property {
+            name: self.name.into_owned(),
+            params: self
+                .params
+                .into_iter()
+                .map(|(key, values)| {
+                    (
+                        key.into_owned(),
+                        values.into_iter().map(Cow::into_owned).collect(),
+                    )
+                })
+                .collect(),
+            value: self.value.map(Cow::into_owned),
+        }
+    }
+}
+
+impl From<BorrowedProperty<'_>> for Property {
+    fn from(value: BorrowedProperty<'_>) -> Self {
+        value.into_owned()
+    }
+}
+
+impl From<Property> for BorrowedProperty<'static> {
+    fn from(value: Property) -> Self {
+        BorrowedProperty {
+            name: value.name.into(),
+            params: value
+                .params
+                .into_iter()
+                .map(|(key, values)| (key.into(), values.into_iter().map(Cow::Owned).collect()))
+                .collect(),
+            value: value.value.map(Cow::Owned),
+        }
+    }
+}
+
+impl fmt::Display for BorrowedProperty<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "name: {}\nparams: {:?}\nvalue: {:?}",
+            self.name, self.params, self.value
+        )
+    }
+}
+
 pub struct PropertyParser<B: BufRead>(LineReader<B>);
 
 impl<B: BufRead> PropertyParser<B> {
@@ -126,98 +211,187 @@ impl<B: BufRead> PropertyParser<B> {
         PropertyParser(LineReader::new(reader))
     }
 
+    /// Return the underlying `LineReader`.
+    pub fn line_reader(&self) -> &LineReader<B> {
+        &self.0
+    }
+
+    pub(crate) fn into_line_reader(self) -> LineReader<B> {
+        self.0
+    }
+
     fn parse(&self, line: Line) -> Result<Property, PropertyError> {
-        let to_parse = line.as_str();
+        parse_property(line.as_str(), line.number()).map(BorrowedProperty::into_owned)
+    }
+}
+
+impl<B: BufRead> Iterator for PropertyParser<B> {
+    type Item = Result<Property, PropertyError>;
 
-        // Find end of parameter name
-        let Some(end_name_index) = to_parse.find([PARAM_DELIMITER, VALUE_DELIMITER]) else {
-            return Err(PropertyError::MissingName(line.number()));
+    fn next(&mut self) -> Option<Result<Property, PropertyError>> {
+        self.0.next().map(|line| {
+            line.map_err(PropertyError::from)
+                .and_then(|line| self.parse(line))
+        })
+    }
+}
+
+/// Parse the result of `BorrowedLineReader` into `BorrowedProperty`.
+pub struct BorrowedPropertyParser<'a>(BorrowedLineReader<'a>);
+
+impl<'a> BorrowedPropertyParser<'a> {
+    pub fn new(line_reader: BorrowedLineReader<'a>) -> BorrowedPropertyParser<'a> {
+        BorrowedPropertyParser(line_reader)
+    }
+
+    pub fn from_input(input: &'a str) -> BorrowedPropertyParser<'a> {
+        BorrowedPropertyParser(BorrowedLineReader::new(input))
+    }
+}
+
+impl<'a> Iterator for BorrowedPropertyParser<'a> {
+    type Item = Result<BorrowedProperty<'a>, PropertyError>;
+
+    fn next(&mut self) -> Option<Result<BorrowedProperty<'a>, PropertyError>> {
+        let line = self.0.next()?;
+        let number = line.number();
+        Some(match line.into_inner() {
+            Cow::Borrowed(line) => parse_property(line, number),
+            // Unfolded lines are owned so the property has to own its parts too
+            Cow::Owned(line) => parse_property(&line, number).map(|prop| prop.into_owned().into()),
+        })
+    }
+}
+
+/// Parse the result of `AsyncLineReader` into `Property`.
+///
+/// This is the asynchronous counterpart to `PropertyParser`.
+#[cfg(feature = "tokio")]
+pub struct AsyncPropertyParser<B: tokio::io::AsyncBufRead + Unpin>(AsyncLineReader<B>);
+
+#[cfg(feature = "tokio")]
+impl<B: tokio::io::AsyncBufRead + Unpin> AsyncPropertyParser<B> {
+    pub fn new(line_reader: AsyncLineReader<B>) -> AsyncPropertyParser<B> {
+        AsyncPropertyParser(line_reader)
+    }
+
+    pub fn from_reader(reader: B) -> AsyncPropertyParser<B> {
+        AsyncPropertyParser(AsyncLineReader::new(reader))
+    }
+
+    /// Return the underlying `AsyncLineReader`.
+    pub fn line_reader(&self) -> &AsyncLineReader<B> {
+        &self.0
+    }
+
+    pub(crate) fn into_line_reader(self) -> AsyncLineReader<B> {
+        self.0
+    }
+
+    /// Read and parse the next `Property`.
+    pub async fn next(&mut self) -> Option<Result<Property, PropertyError>> {
+        let line = match self.0.next().await? {
+            Ok(line) => line,
+            Err(err) => return Some(Err(err.into())),
         };
-        let (prop_name, mut to_parse) = to_parse.split_at(end_name_index);
-        if prop_name.is_empty() {
-            return Err(PropertyError::MissingName(line.number()));
+        Some(parse_property(line.as_str(), line.number()).map(BorrowedProperty::into_owned))
+    }
+}
+
+/// Uppercase a case-insensitive identifier, only allocating if it isn't already uppercase.
+fn to_uppercase(value: &str) -> Cow<'_, str> {
+    if value.chars().any(char::is_lowercase) {
+        Cow::Owned(value.to_uppercase())
+    } else {
+        Cow::Borrowed(value)
+    }
+}
+
+/// Split an unfolded line into a `BorrowedProperty` borrowing from it.
+pub(crate) fn parse_property(
+    to_parse: &str,
+    line_number: usize,
+) -> Result<BorrowedProperty<'_>, PropertyError> {
+    // Find end of parameter name
+    let Some(end_name_index) = to_parse.find([PARAM_DELIMITER, VALUE_DELIMITER]) else {
+        return Err(PropertyError::MissingName(line_number));
+    };
+    let (prop_name, mut to_parse) = to_parse.split_at(end_name_index);
+    if prop_name.is_empty() {
+        return Err(PropertyError::MissingName(line_number));
+    }
+
+    // remainder either starts with ; or :
+    // Fetch all parameters
+    let mut params = vec![];
+    while to_parse.starts_with(PARAM_DELIMITER) {
+        to_parse = to_parse.split_at(1).1;
+
+        // Split the param key and the rest of the line
+        let Some((key, remainder)) = to_parse.split_once(PARAM_NAME_DELIMITER) else {
+            return Err(PropertyError::MissingDelimiter(
+                line_number,
+                PARAM_NAME_DELIMITER,
+            ));
+        };
+        if key.is_empty() {
+            return Err(PropertyError::MissingParamKey(line_number));
         }
+        to_parse = remainder;
 
-        // remainder either starts with ; or :
-        // Fetch all parameters
-        let mut params = vec![];
-        while to_parse.starts_with(PARAM_DELIMITER) {
-            to_parse = to_parse.split_at(1).1;
-
-            // Split the param key and the rest of the line
-            let Some((key, remainder)) = to_parse.split_once(PARAM_NAME_DELIMITER) else {
-                return Err(PropertyError::MissingDelimiter(
-                    line.number(),
-                    PARAM_NAME_DELIMITER,
-                ));
-            };
-            if key.is_empty() {
-                return Err(PropertyError::MissingParamKey(line.number()));
-            }
-            to_parse = remainder;
-
-            let mut values = Vec::new();
-
-            // Parse parameter value.
-            loop {
-                if to_parse.starts_with('"') {
-                    // This is a dquoted value. (NAME:Foo="Bar":value)
-                    let mut elements = to_parse.splitn(3, PARAM_QUOTE).skip(1);
-                    // unwrap is safe here as we have already check above if there is on '"'.
-                    values.push(
-                        elements
-                            .next()
-                            .ok_or_else(|| PropertyError::MissingClosingQuote(line.number()))?
-                            .to_string(),
-                    );
-
-                    to_parse = elements
+        let mut values = Vec::new();
+
+        // Parse parameter value.
+        loop {
+            if to_parse.starts_with('"') {
+                // This is a dquoted value. (NAME:Foo="Bar":value)
+                let mut elements = to_parse.splitn(3, PARAM_QUOTE).skip(1);
+                // unwrap is safe here as we have already check above if there is on '"'.
+                values.push(
+                    elements
                         .next()
-                        .ok_or_else(|| PropertyError::MissingClosingQuote(line.number()))?
-                } else {
-                    // This is a 'raw' value. (NAME;Foo=Bar:value)
-                    // Try to find the next param separator.
-                    let Some(end_param_value) =
-                        to_parse.find([PARAM_DELIMITER, VALUE_DELIMITER, PARAM_VALUE_DELIMITER])
-                    else {
-                        return Err(PropertyError::MissingContentAfter(
-                            line.number(),
-                            PARAM_NAME_DELIMITER,
-                        ));
-                    };
-
-                    let elements = to_parse.split_at(end_param_value);
-                    values.push(elements.0.to_string());
-                    to_parse = elements.1;
-                }
-
-                if !to_parse.starts_with(PARAM_VALUE_DELIMITER) {
-                    break;
-                }
-
-                to_parse = to_parse.trim_start_matches(PARAM_VALUE_DELIMITER);
+                        .ok_or(PropertyError::MissingClosingQuote(line_number))?
+                        .into(),
+                );
+
+                to_parse = elements
+                    .next()
+                    .ok_or(PropertyError::MissingClosingQuote(line_number))?
+            } else {
+                // This is a 'raw' value. (NAME;Foo=Bar:value)
+                // Try to find the next param separator.
+                let Some(end_param_value) =
+                    to_parse.find([PARAM_DELIMITER, VALUE_DELIMITER, PARAM_VALUE_DELIMITER])
+                else {
+                    return Err(PropertyError::MissingContentAfter(
+                        line_number,
+                        PARAM_NAME_DELIMITER,
+                    ));
+                };
+
+                let elements = to_parse.split_at(end_param_value);
+                values.push(elements.0.into());
+                to_parse = elements.1;
             }
 
-            params.push((key.to_uppercase(), values));
-        }
+            if !to_parse.starts_with(PARAM_VALUE_DELIMITER) {
+                break;
+            }
 
-        // Parse value
-        if !to_parse.starts_with(VALUE_DELIMITER) {
-            return Err(PropertyError::MissingValue(line.number()));
+            to_parse = to_parse.trim_start_matches(PARAM_VALUE_DELIMITER);
         }
-        to_parse = to_parse.split_at(1).1;
-        Ok(Property {
-            name: prop_name.to_string(),
-            params,
-            value: (!to_parse.is_empty()).then_some(to_parse.to_string()),
-        })
-    }
-}
 
-impl<B: BufRead> Iterator for PropertyParser<B> {
-    type Item = Result<Property, PropertyError>;
+        params.push((to_uppercase(key), values));
+    }
 
-    fn next(&mut self) -> Option<Result<Property, PropertyError>> {
-        self.0.next().map(|line| self.parse(line))
+    // Parse value
+    if !to_parse.starts_with(VALUE_DELIMITER) {
+        return Err(PropertyError::MissingValue(line_number));
     }
+    to_parse = to_parse.split_at(1).1;
+    Ok(BorrowedProperty {
+        name: prop_name.into(),
+        params,
+        value: (!to_parse.is_empty()).then_some(to_parse.into()),
+    })
 }
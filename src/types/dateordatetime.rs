@@ -24,12 +24,20 @@ impl CalDateOrDateTime {
         Ok(match prop.get_value_type() {
             Some("DATE") => Self::Date(CalDate::parse_prop(prop, timezones)?),
             Some("DATE-TIME") | None => Self::DateTime(CalDateTime::parse_prop(prop, timezones)?),
-            _ => {
-                panic!()
+            Some(value_type) => {
+                return Err(CalDateTimeError::InvalidValueType(value_type.to_string()));
             }
         })
     }
 
+    #[must_use]
+    pub fn format(&self) -> String {
+        match self {
+            Self::DateTime(datetime) => datetime.format(),
+            Self::Date(date) => date.format(),
+        }
+    }
+
     pub fn is_date(&self) -> bool {
         matches!(self, Self::Date(_))
     }
@@ -61,6 +69,11 @@ impl CalDateOrDateTime {
             Self::Date(date) => date.naive_date().and_time(NaiveTime::default()).and_utc(),
         }
     }
+
+    /// The date-time `duration` later, or `None` if that is out of range.
+    pub fn checked_add_signed(&self, duration: Duration) -> Option<CalDateTime> {
+        CalDateTime::from(self.clone()).checked_add_signed(duration)
+    }
 }
 
 impl Sub<&CalDateOrDateTime> for CalDateOrDateTime {
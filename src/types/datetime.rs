@@ -4,7 +4,7 @@ use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, NaiveDateTime, Utc}
 use chrono_tz::Tz;
 use std::{collections::HashMap, ops::Add};
 
-const LOCAL_DATE_TIME: &str = "%Y%m%dT%H%M%S";
+pub(crate) const LOCAL_DATE_TIME: &str = "%Y%m%dT%H%M%S";
 const UTC_DATE_TIME: &str = "%Y%m%dT%H%M%SZ";
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
@@ -117,6 +117,12 @@ impl CalDateTime {
         self.0.to_utc()
     }
 
+    /// The date-time `duration` later, or `None` if that is out of range.
+    #[must_use]
+    pub fn checked_add_signed(&self, duration: Duration) -> Option<Self> {
+        self.0.clone().checked_add_signed(duration).map(Self)
+    }
+
     #[must_use]
     pub fn timezone(&self) -> Timezone {
         self.0.timezone()
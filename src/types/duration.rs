@@ -47,21 +47,24 @@ pub fn parse_duration(string: &str) -> Result<Duration, InvalidDuration> {
         .ok_or(InvalidDuration(string.to_owned()))?;
 
     let mut duration = Duration::zero();
-    if let Some(weeks) = captures.name("W") {
-        duration += Duration::weeks(weeks.as_str().parse().unwrap());
-    }
-    if let Some(days) = captures.name("D") {
-        duration += Duration::days(days.as_str().parse().unwrap());
-    }
-    if let Some(hours) = captures.name("H") {
-        duration += Duration::hours(hours.as_str().parse().unwrap());
-    }
-    if let Some(minutes) = captures.name("M") {
-        duration += Duration::minutes(minutes.as_str().parse().unwrap());
-    }
-    if let Some(seconds) = captures.name("S") {
-        duration += Duration::seconds(seconds.as_str().parse().unwrap());
-    }
+    let mut add = |name, unit: fn(i64) -> Option<Duration>| {
+        if let Some(value) = captures.name(name) {
+            // Values that don't fit into a `Duration` are errors instead of panics
+            duration = value
+                .as_str()
+                .parse()
+                .ok()
+                .and_then(unit)
+                .and_then(|value| duration.checked_add(&value))
+                .ok_or_else(|| InvalidDuration(string.to_owned()))?;
+        }
+        Ok(())
+    };
+    add("W", Duration::try_weeks)?;
+    add("D", Duration::try_days)?;
+    add("H", Duration::try_hours)?;
+    add("M", Duration::try_minutes)?;
+    add("S", Duration::try_seconds)?;
     if let Some(sign) = captures.name("sign")
         && sign.as_str() == "-"
     {
@@ -71,9 +74,41 @@ pub fn parse_duration(string: &str) -> Result<Duration, InvalidDuration> {
     Ok(duration)
 }
 
+/// Format a duration as a DURATION value, using weeks only if it consists of whole weeks.
+pub fn format_duration(duration: Duration) -> String {
+    let sign = if duration < Duration::zero() { "-" } else { "" };
+    let duration = duration.abs();
+    if duration.is_zero() {
+        return "PT0S".to_owned();
+    }
+    if duration.num_seconds() % Duration::weeks(1).num_seconds() == 0 {
+        return format!("{sign}P{}W", duration.num_weeks());
+    }
+
+    let mut value = format!("{sign}P");
+    if duration.num_days() > 0 {
+        value += &format!("{}D", duration.num_days());
+    }
+    let time = duration.num_seconds() % Duration::days(1).num_seconds();
+    if time > 0 {
+        value.push('T');
+        let (hours, minutes, seconds) = (time / 3600, time / 60 % 60, time % 60);
+        if hours > 0 {
+            value += &format!("{hours}H");
+        }
+        if minutes > 0 {
+            value += &format!("{minutes}M");
+        }
+        if seconds > 0 {
+            value += &format!("{seconds}S");
+        }
+    }
+    value
+}
+
 #[cfg(test)]
 mod tests {
-    use super::parse_duration;
+    use super::{format_duration, parse_duration};
     use chrono::Duration;
 
     #[test]
@@ -95,7 +130,27 @@ mod tests {
             Duration::days(2) + Duration::minutes(10) + Duration::seconds(12)
         );
         assert!(parse_duration("PT10S12M").is_err());
+        // Values that don't fit into a duration
+        assert!(parse_duration("P99999999999999999999W").is_err());
+        assert!(parse_duration("P9999999999999W").is_err());
+        assert!(parse_duration("P106751991167DT7H").is_ok());
+        assert!(parse_duration("P106751991167DT8H").is_err());
         // This should yield an error but it's easier to just let it slip through as 0s
         assert_eq!(parse_duration("P").unwrap(), Duration::zero());
     }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::zero()), "PT0S");
+        assert_eq!(format_duration(Duration::weeks(2)), "P2W");
+        assert_eq!(format_duration(-Duration::days(1)), "-P1D");
+        assert_eq!(format_duration(Duration::hours(12)), "PT12H");
+        assert_eq!(
+            format_duration(Duration::days(2) + Duration::minutes(10) + Duration::seconds(12)),
+            "P2DT10M12S"
+        );
+        for value in ["P1W", "P8D", "PT1H30M", "-PT15M"] {
+            assert_eq!(format_duration(parse_duration(value).unwrap()), value);
+        }
+    }
 }
@@ -27,4 +27,8 @@ pub enum CalDateTimeError {
     ParseError(String),
     #[error("Duration string {0} has an invalid format")]
     InvalidDurationFormat(String),
+    #[error("Period string {0} has an invalid format")]
+    InvalidPeriodFormat(String),
+    #[error("Value type {0} is not supported here")]
+    InvalidValueType(String),
 }
@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 
-use chrono::{DateTime, Duration};
+use chrono::Duration;
 use chrono_tz::Tz;
 
 use crate::{
@@ -8,6 +8,7 @@ use crate::{
     types::{CalDateOrDateTime, CalDateTime, CalDateTimeError, parse_duration},
 };
 
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum DateTimeOrDuration {
     DateTime(CalDateTime),
     Duration(Duration),
@@ -18,11 +19,15 @@ impl DateTimeOrDuration {
         if let Ok(datetime) = CalDateTime::parse(value, timezone) {
             return Ok(Self::DateTime(datetime));
         }
-        Ok(Self::Duration(parse_duration(value).unwrap()))
+        parse_duration(value)
+            .map(Self::Duration)
+            .map_err(|_| CalDateTimeError::InvalidDurationFormat(value.to_string()))
     }
 }
 
-pub struct Period(CalDateTime, DateTimeOrDuration);
+/// A period of time, given by its start and either its end or its duration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Period(pub CalDateTime, pub DateTimeOrDuration);
 
 impl Period {
     pub fn parse_prop(
@@ -52,12 +57,43 @@ impl Period {
     }
 
     pub fn parse(value: &str, timezone: Option<Tz>) -> Result<Self, CalDateTimeError> {
-        let (start, end) = value.split_once('/').unwrap();
+        let (start, end) = value
+            .split_once('/')
+            .ok_or_else(|| CalDateTimeError::InvalidPeriodFormat(value.to_string()))?;
 
         let start = CalDateTime::parse(start, timezone)?;
-        let end = DateTimeOrDuration::parse(end, timezone)?;
+        let end = match DateTimeOrDuration::parse(end, timezone)? {
+            // The end of a period must be a valid date-time
+            DateTimeOrDuration::Duration(duration)
+                if start.checked_add_signed(duration).is_none() =>
+            {
+                return Err(CalDateTimeError::InvalidDurationFormat(end.to_string()));
+            }
+            end => end,
+        };
         Ok(Self(start, end))
     }
+
+    #[must_use]
+    pub fn start(&self) -> &CalDateTime {
+        &self.0
+    }
+
+    #[must_use]
+    pub fn end(&self) -> CalDateTime {
+        match &self.1 {
+            DateTimeOrDuration::DateTime(end) => end.clone(),
+            DateTimeOrDuration::Duration(duration) => self.0.clone() + *duration,
+        }
+    }
+
+    #[must_use]
+    pub fn duration(&self) -> Duration {
+        match &self.1 {
+            DateTimeOrDuration::DateTime(end) => end.utc() - self.0.utc(),
+            DateTimeOrDuration::Duration(duration) => *duration,
+        }
+    }
 }
 
 pub enum DateOrDateTimeOrPeriod {
@@ -76,7 +112,7 @@ impl DateOrDateTimeOrPeriod {
                 prop, timezones,
             )?)),
             "PERIOD" => Ok(Self::Period(Period::parse_prop(prop, timezones)?)),
-            _ => panic!(),
+            value_type => Err(CalDateTimeError::InvalidValueType(value_type.to_string())),
         }
     }
 
@@ -26,6 +26,31 @@ impl From<rrule::Tz> for Timezone {
     }
 }
 
+/// Resolve the TZID parameter of a property to a timezone.
+///
+/// The VTIMEZONE components of a calendar are only used for TZIDs the resolver doesn't know,
+/// since they are often incomplete or stripped entirely
+/// ([RFC7809](https://tools.ietf.org/html/rfc7809)). Closures can be used as resolvers.
+pub trait TimezoneResolver {
+    fn resolve(&self, tzid: &str) -> Option<Tz>;
+}
+
+impl<F: Fn(&str) -> Option<Tz>> TimezoneResolver for F {
+    fn resolve(&self, tzid: &str) -> Option<Tz> {
+        self(tzid)
+    }
+}
+
+/// Resolve TZIDs that are names of the IANA timezone database, like `Europe/Berlin`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OlsonResolver;
+
+impl TimezoneResolver for OlsonResolver {
+    fn resolve(&self, tzid: &str) -> Option<Tz> {
+        tzid.parse().ok()
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Display)]
 pub enum CalTimezoneOffset {
     Local(chrono::FixedOffset),
@@ -34,22 +34,4491 @@ pub mod property {
 pub mod line {
     extern crate ical;
 
+    use ical::charset::{Charset, CharsetDetection, MAX_TRANSCODINGS, Transcoding};
+    use ical::line::LineError;
     use insta::assert_snapshot;
     use itertools::Itertools;
 
     #[test]
     fn ical() {
         let input = include_bytes!("./resources/ical_multiple.ics");
-        let lines = ical::LineReader::new(input.as_slice()).join("\n");
+        let lines = ical::LineReader::new(input.as_slice())
+            .map(Result::unwrap)
+            .join("\n");
         assert_snapshot!(lines);
     }
 
     #[test]
     fn vcard() {
         let input = include_bytes!("./resources/vcard_input.vcf");
-        let lines = ical::LineReader::new(input.as_slice()).join("\n");
+        let lines = ical::LineReader::new(input.as_slice())
+            .map(Result::unwrap)
+            .join("\n");
         assert_snapshot!(lines);
     }
+
+    #[test]
+    fn invalid_utf8() {
+        let input = b"BEGIN:VCARD\r\nFN:Ren\xe9\r\nEND:VCARD\r\n";
+        let mut reader = ical::LineReader::new(input.as_slice());
+        assert_eq!(reader.next().unwrap().unwrap().as_str(), "BEGIN:VCARD");
+        assert_eq!(
+            reader.next().unwrap().unwrap_err(),
+            LineError::InvalidUtf8(2, 19)
+        );
+        assert_eq!(reader.next().unwrap().unwrap().as_str(), "END:VCARD");
+        assert!(reader.next().is_none());
+
+        let err = ical::VcardParser::new(input.as_slice())
+            .next()
+            .unwrap()
+            .unwrap_err();
+        assert_eq!(
+            err,
+            ical::parser::ParserError::PropertyError(LineError::InvalidUtf8(2, 19).into())
+        );
+    }
+
+    #[test]
+    fn io_error() {
+        struct FailingReader(usize);
+
+        impl std::io::Read for FailingReader {
+            fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+                if self.0 == 0 {
+                    return Err(std::io::Error::other("connection reset"));
+                }
+                let line = b"X-LINE:value\r\n";
+                self.0 -= 1;
+                buf[..line.len()].copy_from_slice(line);
+                Ok(line.len())
+            }
+        }
+
+        let mut reader = ical::LineReader::new(std::io::BufReader::new(FailingReader(2)));
+        assert!(reader.next().unwrap().is_ok());
+        assert!(reader.next().unwrap().is_ok());
+        assert_eq!(
+            reader.next().unwrap().unwrap_err(),
+            LineError::Io(3, std::io::ErrorKind::Other, "connection reset".to_owned())
+        );
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn line_too_long() {
+        let input = "BEGIN:VCARD\r\nNOTE:0123456789\r\nNOTE:01234\r\n 56789\r\n 0\r\nFN:Alice\r\nEND:VCARD\r\n";
+        let mut reader = ical::LineReader::new(input.as_bytes()).with_max_line_length(12);
+        assert_eq!(reader.next().unwrap().unwrap().as_str(), "BEGIN:VCARD");
+        assert_eq!(
+            reader.next().unwrap().unwrap_err(),
+            LineError::LineTooLong(2, 12)
+        );
+        assert_eq!(
+            reader.next().unwrap().unwrap_err(),
+            LineError::LineTooLong(3, 12)
+        );
+        assert_eq!(
+            reader.next().unwrap().unwrap().to_string(),
+            "Line 6: FN:Alice"
+        );
+        assert_eq!(reader.next().unwrap().unwrap().as_str(), "END:VCARD");
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn long_first_line_continuations() {
+        let input =
+            "BEGIN:VCARD\r\nNOTE:0123456789\r\n 0123\r\n\t4567\r\nFN:Alice\r\nEND:VCARD\r\n";
+        let mut reader = ical::LineReader::new(input.as_bytes()).with_max_line_length(12);
+        assert_eq!(reader.next().unwrap().unwrap().as_str(), "BEGIN:VCARD");
+        assert_eq!(
+            reader.next().unwrap().unwrap_err(),
+            LineError::LineTooLong(2, 12)
+        );
+        assert_eq!(
+            reader.next().unwrap().unwrap().to_string(),
+            "Line 5: FN:Alice"
+        );
+        assert_eq!(reader.next().unwrap().unwrap().as_str(), "END:VCARD");
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn long_line_invalid_utf8_offset() {
+        let input =
+            b"BEGIN:VCARD\r\nNOTE:0123456789012345678901234567890123456789\r\nFN:Ren\xe9\r\n";
+        let mut reader = ical::LineReader::new(input.as_slice()).with_max_line_length(12);
+        assert!(reader.next().unwrap().is_ok());
+        assert_eq!(
+            reader.next().unwrap().unwrap_err(),
+            LineError::LineTooLong(2, 12)
+        );
+        // The offset includes the skipped part of the overlong line
+        assert_eq!(
+            reader.next().unwrap().unwrap_err(),
+            LineError::InvalidUtf8(3, 66)
+        );
+    }
+
+    #[test]
+    fn utf8_bom() {
+        let input = b"\xef\xbb\xbfBEGIN:VCARD\r\nEND:VCARD\r\n";
+        let mut reader = ical::LineReader::new(input.as_slice())
+            .with_charset_detection(CharsetDetection::default());
+        assert_eq!(reader.next().unwrap().unwrap().as_str(), "BEGIN:VCARD");
+        assert_eq!(reader.next().unwrap().unwrap().as_str(), "END:VCARD");
+        assert!(reader.next().is_none());
+        assert_eq!(reader.transcodings(), &[Transcoding::Bom(Charset::Utf8)]);
+    }
+
+    #[rstest::rstest]
+    #[case(Charset::Utf16Le)]
+    #[case(Charset::Utf16Be)]
+    fn utf16_bom(#[case] charset: Charset) {
+        let text = "BEGIN:VCARD\r\nFN:Ren\u{e9}\r\n  M\u{fc}ller\r\nEND:VCARD\r\n";
+        let mut input = vec![];
+        for unit in std::iter::once(0xFEFF).chain(text.encode_utf16()) {
+            input.extend(match charset {
+                Charset::Utf16Le => unit.to_le_bytes(),
+                _ => unit.to_be_bytes(),
+            });
+        }
+        let reader = ical::LineReader::new(input.as_slice())
+            .with_charset_detection(CharsetDetection::default());
+        let lines: Vec<String> = reader.map(|line| line.unwrap().to_string()).collect();
+        assert_eq!(
+            lines,
+            [
+                "Line 1: BEGIN:VCARD",
+                "Line 2: FN:René Müller",
+                "Line 4: END:VCARD"
+            ]
+        );
+    }
+
+    #[test]
+    fn declared_charset() {
+        let input = b"BEGIN:VCARD\r\nN;CHARSET=ISO-8859-1:M\xfcller\r\nEND:VCARD\r\n";
+        let mut reader = ical::LineReader::new(input.as_slice())
+            .with_charset_detection(CharsetDetection::default());
+        reader.next();
+        assert_eq!(
+            reader.next().unwrap().unwrap().as_str(),
+            "N;CHARSET=ISO-8859-1:Müller"
+        );
+        assert_eq!(
+            reader.transcodings(),
+            &[Transcoding::Declared(2, Charset::Latin1)]
+        );
+    }
+
+    #[test]
+    fn fallback_charset() {
+        let input = b"BEGIN:VCARD\r\nFN:Ren\xe9\r\nNOTE:\x80 5\r\nEND:VCARD\r\n";
+
+        let mut reader = ical::LineReader::new(input.as_slice())
+            .with_charset_detection(CharsetDetection::default());
+        reader.next();
+        assert_eq!(
+            reader.next().unwrap().unwrap_err(),
+            LineError::InvalidUtf8(2, 19)
+        );
+
+        let mut parser = ical::VcardParser::from_line_reader(
+            ical::LineReader::new(input.as_slice())
+                .with_charset_detection(CharsetDetection::with_fallback(Charset::Windows1252)),
+        );
+        let card = parser.next().unwrap().unwrap();
+        assert_eq!(card.properties[0].value.as_deref(), Some("René"));
+        assert_eq!(card.properties[1].value.as_deref(), Some("€ 5"));
+        assert_eq!(
+            parser.line_reader().transcodings(),
+            &[
+                Transcoding::Fallback(2, Charset::Windows1252),
+                Transcoding::Fallback(3, Charset::Windows1252)
+            ]
+        );
+    }
+
+    #[test]
+    fn transcodings_limit() {
+        let input = b"FN:Ren\xe9\r\n".repeat(MAX_TRANSCODINGS + 6);
+        let mut reader = ical::LineReader::new(input.as_slice())
+            .with_charset_detection(CharsetDetection::with_fallback(Charset::Latin1));
+        assert_eq!(reader.by_ref().count(), MAX_TRANSCODINGS + 6);
+        assert_eq!(reader.transcodings().len(), MAX_TRANSCODINGS);
+        assert_eq!(
+            reader.transcodings().last(),
+            Some(&Transcoding::Fallback(MAX_TRANSCODINGS, Charset::Latin1))
+        );
+        assert_eq!(reader.transcoding_count(), MAX_TRANSCODINGS + 6);
+    }
+}
+
+pub mod borrowed {
+    extern crate ical;
+
+    use std::borrow::Cow;
+
+    use itertools::Itertools;
+
+    #[rstest::rstest]
+    #[case(include_str!("./resources/ical_multiple.ics"))]
+    #[case(include_str!("./resources/ical_everything.ics"))]
+    #[case(include_str!("./resources/vcard_input.vcf"))]
+    #[case(include_str!("./resources/property_error.vcf"))]
+    fn same_as_owned(#[case] input: &str) {
+        let lines = ical::LineReader::new(input.as_bytes());
+        let borrowed_lines = ical::BorrowedLineReader::new(input);
+        for (line, borrowed) in lines.zip_eq(borrowed_lines) {
+            assert_eq!(line.unwrap().to_string(), borrowed.to_string());
+        }
+
+        let props = ical::PropertyParser::from_reader(input.as_bytes());
+        let borrowed_props = ical::BorrowedPropertyParser::from_input(input);
+        for (prop, borrowed) in props.zip_eq(borrowed_props) {
+            assert_eq!(prop, borrowed.map(Into::into));
+        }
+    }
+
+    #[test]
+    fn borrows_unfolded_lines() {
+        let input = "BEGIN:VCALENDAR\r\nX-FOLDED;x-param=a:hello\r\n  world\r\nX-PLAIN;X-PARAM=\"b\":value\r\nEND:VCALENDAR\r\n";
+        let props: Vec<_> = ical::BorrowedPropertyParser::from_input(input)
+            .map(Result::unwrap)
+            .collect();
+
+        let folded = &props[1];
+        assert_eq!(folded.value.as_deref(), Some("hello world"));
+        assert!(matches!(folded.value, Some(Cow::Owned(_))));
+
+        let plain = &props[2];
+        assert!(matches!(plain.name, Cow::Borrowed("X-PLAIN")));
+        assert!(matches!(plain.params[0].0, Cow::Borrowed("X-PARAM")));
+        assert!(matches!(plain.params[0].1[0], Cow::Borrowed("b")));
+        assert!(matches!(plain.value, Some(Cow::Borrowed("value"))));
+    }
+
+    #[test]
+    fn component_parser() {
+        let input = include_str!("./resources/ical_everything.ics");
+        let cal = ical::IcalParser::new(input.as_bytes())
+            .next()
+            .unwrap()
+            .unwrap();
+        let borrowed = ical::BorrowedIcalParser::new(input)
+            .next()
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(borrowed.name, "VCALENDAR");
+        assert_eq!(borrowed.properties.len(), cal.properties.len());
+        assert_eq!(
+            borrowed.get_named_components("VEVENT").len(),
+            cal.events.len()
+        );
+        let event = borrowed.get_named_components("VEVENT")[0];
+        assert_eq!(
+            event
+                .get_property("UID")
+                .and_then(|prop| prop.value.as_deref()),
+            Some(cal.events[0].get_uid())
+        );
+        assert_eq!(
+            event.get_named_components("VALARM").len(),
+            cal.events[0].alarms.len()
+        );
+
+        let input = include_str!("./resources/vcard_input.vcf");
+        let cards = ical::VcardParser::new(input.as_bytes()).map(Result::unwrap);
+        let borrowed_cards = ical::BorrowedVcardParser::new(input).map(Result::unwrap);
+        for (card, borrowed) in cards.zip_eq(borrowed_cards) {
+            let props: Vec<ical::property::Property> =
+                borrowed.properties.into_iter().map(Into::into).collect();
+            assert_eq!(card.properties, props);
+        }
+    }
+
+    #[test]
+    fn component_parser_errors() {
+        assert_eq!(
+            ical::BorrowedVcardParser::new("BEGIN:VCARD\r\nFN:Alice\r\n").next(),
+            Some(Err(ical::parser::ParserError::NotComplete))
+        );
+        assert!(
+            ical::BorrowedIcalParser::new("BEGIN:VCARD\r\nEND:VCARD\r\n")
+                .next()
+                .unwrap()
+                .is_err()
+        );
+    }
+}
+
+#[cfg(feature = "tokio")]
+pub mod asynchronous {
+    extern crate ical;
+    use ical::generator::Emitter;
+
+    #[rstest::rstest]
+    #[case(include_str!("./resources/ical_multiple.ics"))]
+    #[case(include_str!("./resources/ical_everything.ics"))]
+    #[case(include_str!("./resources/ical_example_rrule.ics"))]
+    #[case(include_str!("./resources/ical_freebusy.ics"))]
+    #[case(include_str!("./resources/o365_meeting.ics"))]
+    #[tokio::test]
+    async fn ical_same_as_sync(#[case] input: &str) {
+        let mut lines = ical::AsyncLineReader::new(input.as_bytes());
+        for line in ical::LineReader::new(input.as_bytes()) {
+            assert_eq!(
+                Some(line.unwrap().to_string()),
+                lines.next().await.map(|l| l.unwrap().to_string())
+            );
+        }
+        assert!(lines.next().await.is_none());
+
+        let mut reader = ical::AsyncIcalParser::new(input.as_bytes());
+        for cal in ical::IcalParser::new(input.as_bytes()) {
+            let async_cal = reader.next().await.unwrap();
+            similar_asserts::assert_eq!(cal.unwrap().generate(), async_cal.unwrap().generate());
+        }
+        assert!(reader.next().await.is_none());
+    }
+
+    #[rstest::rstest]
+    #[case(include_str!("./resources/ical_todos.ics"))]
+    #[case(include_str!("./resources/ical_events.ics"))]
+    #[case(include_str!("./resources/ical_freebusy.ics"))]
+    #[tokio::test]
+    async fn object_same_as_sync(#[case] input: &str) {
+        let mut reader = ical::AsyncIcalObjectParser::new(input.as_bytes());
+        for obj in ical::IcalObjectParser::new(input.as_bytes()) {
+            let async_obj = reader.next().await.unwrap();
+            match obj {
+                Ok(obj) => {
+                    similar_asserts::assert_eq!(obj.generate(), async_obj.unwrap().generate())
+                }
+                Err(err) => {
+                    // The async parser skips the rest of an invalid component
+                    assert_eq!(err, async_obj.unwrap_err());
+                    break;
+                }
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn vcard() {
+        let input = include_str!("./resources/vcard_input.vcf");
+        let mut props = ical::AsyncPropertyParser::from_reader(input.as_bytes());
+        for prop in ical::PropertyParser::from_reader(input.as_bytes()) {
+            assert_eq!(Some(prop), props.next().await);
+        }
+
+        let mut reader = ical::AsyncVcardParser::new(input.as_bytes());
+        for card in ical::VcardParser::new(input.as_bytes()) {
+            assert_eq!(card, reader.next().await.unwrap());
+        }
+
+        let input = include_str!("./resources/vcard_invalid.vcf");
+        let mut reader = ical::AsyncVcardParser::new(input.as_bytes());
+        assert!(reader.next().await.unwrap().is_err());
+    }
+
+    #[tokio::test]
+    async fn line_errors() {
+        use ical::line::LineError;
+
+        let input = b"BEGIN:VCARD\r\nFN:Ren\xe9\r\nNOTE:0123456789ABCDEF\r\n 0123\r\nEND:VCARD\r\n";
+        let mut reader = ical::AsyncLineReader::new(input.as_slice()).with_max_line_length(16);
+        assert!(reader.next().await.unwrap().is_ok());
+        assert_eq!(
+            reader.next().await.unwrap().unwrap_err(),
+            LineError::InvalidUtf8(2, 19)
+        );
+        assert_eq!(
+            reader.next().await.unwrap().unwrap_err(),
+            LineError::LineTooLong(3, 16)
+        );
+        assert_eq!(
+            reader.next().await.unwrap().unwrap().to_string(),
+            "Line 5: END:VCARD"
+        );
+        assert!(reader.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn incomplete() {
+        let mut reader = ical::AsyncIcalParser::new(
+            "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nUID:1\r\nDTSTART:20250101T000000Z\r\nEND:VEVENT\r\n".as_bytes(),
+        );
+        assert_eq!(
+            reader.next().await.unwrap().unwrap_err(),
+            ical::parser::ParserError::NotComplete
+        );
+    }
+}
+
+pub mod limits {
+    extern crate ical;
+
+    use ical::charset::{Charset, CharsetDetection, Transcoding};
+    use ical::generator::Emitter;
+    use ical::line::LineError;
+    use ical::parser::{LimitError, ParserError, ParserLimits};
+    use ical::property::PropertyError;
+
+    const INPUT: &str = include_str!("./resources/ical_everything.ics");
+
+    #[rstest::rstest]
+    #[case(ParserLimits { max_properties: Some(4), ..Default::default() }, LimitError::TooManyProperties(4).into())]
+    #[case(ParserLimits { max_depth: Some(2), ..Default::default() }, LimitError::TooDeep(2).into())]
+    #[case(ParserLimits { max_components: Some(2), ..Default::default() }, LimitError::TooManyComponents(2).into())]
+    #[case(ParserLimits { max_line_length: Some(40), ..Default::default() }, PropertyError::Line(LineError::LineTooLong(3, 40)).into())]
+    #[case(ParserLimits { max_bytes: Some(1000), ..Default::default() }, PropertyError::Line(LineError::InputTooLarge(48, 1000)).into())]
+    fn exceeded(#[case] limits: ParserLimits, #[case] expected: ParserError) {
+        let mut parser = ical::IcalParser::with_limits(INPUT.as_bytes(), limits);
+        assert_eq!(parser.next().unwrap().unwrap_err(), expected);
+    }
+
+    #[test]
+    fn within_limits() {
+        let limits = ParserLimits {
+            max_line_length: Some(100),
+            max_properties: Some(20),
+            max_depth: Some(3),
+            max_components: Some(10),
+            max_bytes: Some(INPUT.len()),
+        };
+        let mut parser = ical::IcalParser::with_limits(INPUT.as_bytes(), limits);
+        let expected = ical::IcalParser::new(INPUT.as_bytes()).next().unwrap();
+        assert_eq!(
+            parser.next().unwrap().unwrap().generate(),
+            expected.unwrap().generate()
+        );
+        assert!(parser.next().is_none());
+    }
+
+    #[test]
+    fn with_line_reader() {
+        let input = b"BEGIN:VCARD\r\nFN:Ren\xe9\r\nNOTE:0123456789\r\nEND:VCARD\r\n";
+        let line_reader = ical::LineReader::new(input.as_slice())
+            .with_charset_detection(CharsetDetection::with_fallback(Charset::Latin1));
+        let limits = ParserLimits {
+            max_line_length: Some(12),
+            ..Default::default()
+        };
+        let mut parser = ical::VcardParser::from_line_reader(line_reader).limits(limits);
+        assert_eq!(
+            parser.next().unwrap().unwrap_err(),
+            PropertyError::Line(LineError::LineTooLong(3, 12)).into()
+        );
+        assert_eq!(
+            parser.line_reader().transcodings(),
+            &[Transcoding::Fallback(2, Charset::Latin1)]
+        );
+
+        let line_reader = ical::LineReader::new(input.as_slice())
+            .with_charset_detection(CharsetDetection::with_fallback(Charset::Latin1));
+        let limits = ParserLimits {
+            max_properties: Some(1),
+            ..Default::default()
+        };
+        let mut parser = ical::VcardParser::from_line_reader(line_reader).limits(limits);
+        assert_eq!(
+            parser.next().unwrap().unwrap_err(),
+            LimitError::TooManyProperties(1).into()
+        );
+    }
+
+    #[test]
+    fn input_too_large_ends_reading() {
+        let input =
+            "BEGIN:VCARD\r\nFN:Alice\r\nEND:VCARD\r\nBEGIN:VCARD\r\nFN:Bob\r\nEND:VCARD\r\n";
+        let mut reader = ical::LineReader::new(input.as_bytes()).with_max_bytes(30);
+        assert_eq!(reader.next().unwrap().unwrap().as_str(), "BEGIN:VCARD");
+        assert_eq!(reader.next().unwrap().unwrap().as_str(), "FN:Alice");
+        assert_eq!(
+            reader.next().unwrap().unwrap_err(),
+            LineError::InputTooLarge(3, 30)
+        );
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn input_too_large_with_long_lines() {
+        let input = "NOTE:0123456789\r\n".repeat(100);
+        let mut reader = ical::LineReader::new(input.as_bytes())
+            .with_max_line_length(8)
+            .with_max_bytes(100);
+        for number in 1..=5 {
+            assert_eq!(
+                reader.next().unwrap().unwrap_err(),
+                LineError::LineTooLong(number, 8)
+            );
+        }
+        // The skipped bytes count towards the maximum input size
+        assert_eq!(
+            reader.next().unwrap().unwrap_err(),
+            LineError::InputTooLarge(6, 100)
+        );
+        assert!(reader.next().is_none());
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn async_input_too_large_with_long_lines() {
+        let input = "NOTE:0123456789\r\n".repeat(100);
+        let mut reader = ical::AsyncLineReader::new(input.as_bytes())
+            .with_max_line_length(8)
+            .with_max_bytes(100);
+        for number in 1..=5 {
+            assert_eq!(
+                reader.next().await.unwrap().unwrap_err(),
+                LineError::LineTooLong(number, 8)
+            );
+        }
+        assert_eq!(
+            reader.next().await.unwrap().unwrap_err(),
+            LineError::InputTooLarge(6, 100)
+        );
+        assert!(reader.next().await.is_none());
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn asynchronous() {
+        let limits = ParserLimits {
+            max_depth: Some(2),
+            ..Default::default()
+        };
+        let mut parser = ical::AsyncIcalParser::with_limits(INPUT.as_bytes(), limits);
+        assert_eq!(
+            parser.next().await.unwrap().unwrap_err(),
+            LimitError::TooDeep(2).into()
+        );
+
+        let line_reader = ical::AsyncLineReader::new(INPUT.as_bytes())
+            .with_charset_detection(CharsetDetection::default());
+        let limits = ParserLimits {
+            max_line_length: Some(40),
+            ..Default::default()
+        };
+        let mut parser = ical::AsyncIcalParser::from_line_reader(line_reader).limits(limits);
+        assert_eq!(
+            parser.next().await.unwrap().unwrap_err(),
+            PropertyError::Line(LineError::LineTooLong(3, 40)).into()
+        );
+    }
+}
+
+pub mod validator {
+    extern crate ical;
+
+    use ical::parser::ical::validator::{self, DiagnosticCode, Severity};
+
+    #[rstest::rstest]
+    #[case(include_str!("./resources/ical_events.ics"))]
+    #[case(include_str!("./resources/ical_todos.ics"))]
+    #[case(include_str!("./resources/ical_journals.ics"))]
+    #[case(include_str!("./resources/ical_example_1.ics"))]
+    #[case(include_str!("./resources/o365_meeting.ics"))]
+    #[case(include_str!("./resources/ical_availability.ics"))]
+    #[case(include_str!("./resources/ical_publishing.ics"))]
+    #[case(include_str!("./resources/ical_metadata.ics"))]
+    fn valid(#[case] input: &str) {
+        assert_eq!(validator::validate_input(input), vec![]);
+    }
+
+    #[test]
+    fn invalid_input() {
+        let input = include_str!("./resources/ical_invalid.ics");
+        let diagnostics = validator::validate_input(input)
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join("\n");
+        insta::assert_snapshot!(diagnostics);
+    }
+
+    #[test]
+    fn values_out_of_range() {
+        // Durations that don't fit into a date-time are reported instead of panicking
+        let input = "BEGIN:VCALENDAR\r\nPRODID:-//Example//EN\r\nVERSION:2.0\r\n\
+            BEGIN:VEVENT\r\nUID:1\r\nDTSTAMP:20250101T000000Z\r\nDTSTART:20250101T100000Z\r\n\
+            DURATION:P99999999999999W\r\nEND:VEVENT\r\n\
+            BEGIN:VFREEBUSY\r\nUID:2\r\nDTSTAMP:20250101T000000Z\r\n\
+            FREEBUSY:20250101T000000Z/P99999999W\r\nEND:VFREEBUSY\r\nEND:VCALENDAR\r\n";
+        let diagnostics = validator::validate_input(input);
+        assert_eq!(
+            diagnostics
+                .iter()
+                .map(|diagnostic| (diagnostic.code, diagnostic.message.as_str()))
+                .collect::<Vec<_>>(),
+            [
+                (
+                    DiagnosticCode::InvalidValue,
+                    "P99999999999999W is not a DURATION"
+                ),
+                (
+                    DiagnosticCode::InvalidValue,
+                    "20250101T000000Z/P99999999W is not a PERIOD"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn parsed_calendar() {
+        let input = include_str!("./resources/ical_everything.ics");
+        let calendar = ical::IcalParser::new(input.as_bytes())
+            .next()
+            .unwrap()
+            .unwrap();
+
+        let diagnostics = validator::validate(&calendar);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, Severity::Error);
+        assert_eq!(diagnostics[0].code, DiagnosticCode::ComponentNotAllowed);
+        assert_eq!(diagnostics[0].code.as_str(), "component-not-allowed");
+        assert_eq!(diagnostics[0].location.component, "VCALENDAR/VALARM[0]");
+        assert_eq!(diagnostics[0].location.line, None);
+
+        let mut from_input = validator::validate_input(input);
+        from_input[0].location.line = None;
+        assert_eq!(diagnostics, from_input);
+    }
+}
+
+pub mod verify_options {
+    extern crate ical;
+
+    use ical::builder::calendar::IcalCalendarBuilder;
+    use ical::parser::{ParserError, VerifyOptions};
+    use ical::property::Property;
+    use ical::{IcalObjectParser, IcalParser, ical_property};
+
+    const NO_DTSTAMP: &str = "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nUID:1\r\nDTSTART:20250101T100000Z\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n";
+    const CALENDAR_ALARM: &str = "BEGIN:VCALENDAR\r\nBEGIN:VALARM\r\nACTION:DISPLAY\r\nTRIGGER:-PT5M\r\nEND:VALARM\r\nEND:VCALENDAR\r\n";
+    const WITH_METHOD: &str = "BEGIN:VCALENDAR\r\nMETHOD:REQUEST\r\nBEGIN:VEVENT\r\nUID:1\r\nDTSTAMP:20250101T100000Z\r\nDTSTART:20250101T100000Z\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n";
+
+    #[rstest::rstest]
+    #[case(VerifyOptions::lenient(), NO_DTSTAMP, None)]
+    #[case(VerifyOptions::caldav(), NO_DTSTAMP, None)]
+    #[case(
+        VerifyOptions::strict(),
+        NO_DTSTAMP,
+        Some(ParserError::MissingProperty("DTSTAMP"))
+    )]
+    #[case(VerifyOptions::lenient(), CALENDAR_ALARM, None)]
+    #[case(
+        VerifyOptions::strict(),
+        CALENDAR_ALARM,
+        Some(ParserError::InvalidComponent)
+    )]
+    #[case(VerifyOptions::strict(), WITH_METHOD, None)]
+    #[case(VerifyOptions::itip(), WITH_METHOD, None)]
+    #[case(
+        VerifyOptions::itip(),
+        NO_DTSTAMP,
+        Some(ParserError::MissingProperty("DTSTAMP"))
+    )]
+    #[case(
+        VerifyOptions::caldav(),
+        WITH_METHOD,
+        Some(ParserError::PropertyConflict("METHOD is not allowed in calendar object resources"))
+    )]
+    fn profiles(
+        #[case] options: VerifyOptions,
+        #[case] input: &str,
+        #[case] expected: Option<ParserError>,
+    ) {
+        let mut parser = IcalParser::new(input.as_bytes()).with_verify_options(options);
+        assert_eq!(parser.next().unwrap().err(), expected);
+    }
+
+    #[test]
+    fn calendar_object() {
+        let mut parser = IcalObjectParser::new(WITH_METHOD.as_bytes())
+            .with_verify_options(VerifyOptions::caldav());
+        assert!(matches!(
+            parser.next().unwrap(),
+            Err(ParserError::PropertyConflict(_))
+        ));
+    }
+
+    #[test]
+    fn builder() {
+        let builder = || {
+            IcalCalendarBuilder::version("2.0")
+                .gregorian()
+                .prodid("-//ical-rs//EN")
+        };
+        assert_eq!(
+            builder().build_with(&VerifyOptions::itip()).unwrap_err(),
+            ParserError::MissingProperty("METHOD")
+        );
+        assert!(
+            builder()
+                .set(ical_property!("METHOD", "PUBLISH"))
+                .build_with(&VerifyOptions::itip())
+                .is_ok()
+        );
+    }
+}
+
+pub mod alarm {
+    extern crate ical;
+
+    use chrono::{Duration, TimeZone, Utc};
+    use ical::parser::ical::component::{AlarmAction, AlarmTrigger, IcalAlarm, TriggerRelation};
+    use ical::parser::{ComponentMut, ParserError, VerifyOptions};
+    use ical::property::Property;
+
+    fn alarm(lines: &[&str]) -> IcalAlarm<false> {
+        let mut alarm = IcalAlarm::new();
+        for line in lines {
+            let (name, value) = line.split_once(':').unwrap();
+            let mut params = name.split(';');
+            alarm.add_property(Property {
+                name: params.next().unwrap().to_owned(),
+                params: params
+                    .map(|param| {
+                        let (key, value) = param.split_once('=').unwrap();
+                        (key.to_owned(), vec![value.to_owned()])
+                    })
+                    .collect(),
+                value: Some(value.to_owned()),
+            });
+        }
+        alarm
+    }
+
+    #[test]
+    fn getters() {
+        let verified = alarm(&[
+            "ACTION:display",
+            "TRIGGER;RELATED=END:-PT15M",
+            "REPEAT:2",
+            "DURATION:PT5M",
+        ])
+        .verify()
+        .unwrap();
+        assert_eq!(verified.get_action(), Some(AlarmAction::Display));
+        assert_eq!(
+            verified.get_trigger(),
+            Some(AlarmTrigger::Relative(
+                Duration::minutes(-15),
+                TriggerRelation::End
+            ))
+        );
+        assert_eq!(verified.get_repeat(), Some(2));
+        assert_eq!(verified.get_duration(), Some(Duration::minutes(5)));
+
+        let verified = alarm(&[
+            "ACTION:X-CUSTOM",
+            "TRIGGER;VALUE=DATE-TIME:20250101T100000Z",
+        ])
+        .verify()
+        .unwrap();
+        assert_eq!(
+            verified.get_action(),
+            Some(AlarmAction::Other("X-CUSTOM".to_owned()))
+        );
+        assert_eq!(
+            verified.get_trigger(),
+            Some(AlarmTrigger::Absolute(
+                Utc.with_ymd_and_hms(2025, 1, 1, 10, 0, 0).unwrap()
+            ))
+        );
+        assert_eq!(verified.get_repeat(), None);
+        assert_eq!(verified.get_duration(), None);
+
+        let verified = alarm(&["TRIGGER;RELATED=MIDDLE:-PT5M", "REPEAT:-1", "DURATION:5M"])
+            .verify()
+            .unwrap();
+        assert_eq!(verified.get_action(), None);
+        assert_eq!(verified.get_trigger(), None);
+        assert_eq!(verified.get_repeat(), None);
+        assert_eq!(verified.get_duration(), None);
+    }
+
+    #[rstest::rstest]
+    #[case(&["TRIGGER:-PT5M"], ParserError::MissingProperty("ACTION"))]
+    #[case(&["ACTION:AUDIO"], ParserError::MissingProperty("TRIGGER"))]
+    #[case(&["ACTION:AUDIO", "TRIGGER;RELATED=MIDDLE:-PT5M"], ParserError::InvalidValue("TRIGGER"))]
+    #[case(&["ACTION:AUDIO", "TRIGGER:-PT5M", "REPEAT:2"], ParserError::MissingProperty("DURATION"))]
+    #[case(&["ACTION:AUDIO", "TRIGGER:-PT5M", "DURATION:PT5M"], ParserError::MissingProperty("REPEAT"))]
+    #[case(&["ACTION:AUDIO", "TRIGGER:-PT5M", "DURATION:PT5M", "REPEAT:-1"], ParserError::InvalidValue("REPEAT"))]
+    fn invalid(#[case] lines: &[&str], #[case] expected: ParserError) {
+        assert_eq!(
+            alarm(lines)
+                .verify_with(&VerifyOptions::strict())
+                .unwrap_err(),
+            expected
+        );
+        assert!(alarm(lines).verify().is_ok());
+    }
+
+    #[rstest::rstest]
+    #[case(&["ACTION:DISPLAY", "TRIGGER:-PT5M"], Some(ParserError::MissingProperty("DESCRIPTION")))]
+    #[case(&["ACTION:DISPLAY", "TRIGGER:-PT5M", "DESCRIPTION:Meeting"], None)]
+    #[case(&["ACTION:EMAIL", "TRIGGER:-PT5M", "DESCRIPTION:Meeting", "SUMMARY:Reminder"], Some(ParserError::MissingProperty("ATTENDEE")))]
+    #[case(&["ACTION:EMAIL", "TRIGGER:-PT5M", "DESCRIPTION:Meeting", "SUMMARY:Reminder", "ATTENDEE:mailto:a@example.com"], None)]
+    #[case(&["ACTION:AUDIO", "TRIGGER:-PT5M", "ATTACH:a.wav", "ATTACH:b.wav"], Some(ParserError::PropertyConflict("ATTACH must not occur more than once")))]
+    #[case(&["ACTION:PROCEDURE", "TRIGGER:-PT5M"], Some(ParserError::MissingProperty("ATTACH")))]
+    #[case(&["ACTION:NONE", "TRIGGER:-PT5M"], None)]
+    fn action_properties(#[case] lines: &[&str], #[case] expected: Option<ParserError>) {
+        assert_eq!(
+            alarm(lines).verify_with(&VerifyOptions::strict()).err(),
+            expected
+        );
+        // Only required by the strict profiles
+        assert!(alarm(lines).verify().is_ok());
+    }
+}
+
+pub mod alarm_schedule {
+    extern crate ical;
+
+    use chrono::{DateTime, TimeZone, Utc};
+    use ical::parser::Component;
+    use ical::parser::ical::component::{AlarmOccurrence, AlarmSource};
+    use ical::types::OlsonResolver;
+
+    fn window(from: u32, to: u32) -> (DateTime<Utc>, DateTime<Utc>) {
+        (
+            Utc.with_ymd_and_hms(2025, 1, from, 0, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2025, 1, to, 0, 0, 0).unwrap(),
+        )
+    }
+
+    fn describe(occurrences: &[AlarmOccurrence]) -> Vec<String> {
+        occurrences
+            .iter()
+            .map(|occurrence| {
+                let summary = match occurrence.source {
+                    AlarmSource::Calendar => None,
+                    AlarmSource::Event(event) => event.get_property("SUMMARY"),
+                    AlarmSource::Todo(todo) => todo.get_property("SUMMARY"),
+                }
+                .and_then(|prop| prop.value.as_deref())
+                .unwrap_or_default();
+                format!(
+                    "{} {summary} {:?} {}",
+                    occurrence.time.to_rfc3339(),
+                    occurrence
+                        .recurrence_id
+                        .as_ref()
+                        .map(|id| id.utc().to_rfc3339()),
+                    occurrence.repetition
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn calendar() {
+        let input = include_str!("./resources/ical_alarms.ics");
+        let calendar = ical::IcalParser::new(input.as_bytes())
+            .next()
+            .unwrap()
+            .unwrap();
+
+        let (after, before) = window(6, 9);
+        let alarms = calendar.next_alarms(after, before, &OlsonResolver).unwrap();
+        similar_asserts::assert_eq!(
+            describe(&alarms),
+            [
+                "2025-01-06T07:50:00+00:00 Standup Some(\"2025-01-06T08:00:00+00:00\") 0",
+                "2025-01-06T07:55:00+00:00 Standup Some(\"2025-01-06T08:00:00+00:00\") 1",
+                "2025-01-06T08:00:00+00:00 Launch None 0",
+                "2025-01-07T10:30:00+00:00 Moved standup Some(\"2025-01-07T08:00:00+00:00\") 0",
+                "2025-01-08T07:50:00+00:00 Standup Some(\"2025-01-08T08:00:00+00:00\") 0",
+                "2025-01-08T07:55:00+00:00 Standup Some(\"2025-01-08T08:00:00+00:00\") 1",
+                "2025-01-08T16:00:00+00:00 Report None 0",
+            ]
+        );
+
+        // The window is half-open
+        let (after, _) = window(8, 9);
+        let before = Utc.with_ymd_and_hms(2025, 1, 8, 7, 55, 0).unwrap();
+        let alarms = calendar.next_alarms(after, before, &OlsonResolver).unwrap();
+        assert_eq!(alarms.len(), 1);
+        assert_eq!(
+            alarms[0].time,
+            Utc.with_ymd_and_hms(2025, 1, 8, 7, 50, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn resolver() {
+        let input = include_str!("./resources/ical_alarms.ics");
+        let calendar = ical::IcalParser::new(input.as_bytes())
+            .next()
+            .unwrap()
+            .unwrap();
+
+        // The resolver takes precedence over the VTIMEZONE of the calendar
+        let resolver = |tzid: &str| (tzid == "Office").then_some(chrono_tz::Asia::Tokyo);
+        let (after, before) = window(5, 7);
+        let alarms = calendar.next_alarms(after, before, &resolver).unwrap();
+        assert_eq!(
+            alarms[0].time,
+            Utc.with_ymd_and_hms(2025, 1, 5, 23, 50, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn calendar_object() {
+        let input = "BEGIN:VCALENDAR\r
+BEGIN:VEVENT\r
+UID:1\r
+DTSTART;VALUE=DATE:20250106\r
+RRULE:FREQ=WEEKLY;COUNT=2\r
+BEGIN:VALARM\r
+ACTION:AUDIO\r
+TRIGGER;RELATED=END:-PT1H\r
+END:VALARM\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+        let object = ical::IcalObjectParser::new(input.as_bytes())
+            .next()
+            .unwrap()
+            .unwrap();
+
+        let (after, before) = window(1, 31);
+        let times: Vec<_> = object
+            .next_alarms(after, before, &OlsonResolver)
+            .unwrap()
+            .into_iter()
+            .map(|occurrence| occurrence.time)
+            .collect();
+        assert_eq!(
+            times,
+            [
+                Utc.with_ymd_and_hms(2025, 1, 6, 23, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2025, 1, 13, 23, 0, 0).unwrap(),
+            ]
+        );
+    }
+
+    fn repeating(repeat: &str, duration: &str) -> ical::parser::ical::component::IcalCalendar {
+        let input = format!(
+            "BEGIN:VCALENDAR\r
+BEGIN:VEVENT\r
+UID:1\r
+DTSTAMP:20250101T000000Z\r
+DTSTART:20250101T000000Z\r
+BEGIN:VALARM\r
+ACTION:AUDIO\r
+TRIGGER:PT0S\r
+REPEAT:{repeat}\r
+DURATION:{duration}\r
+END:VALARM\r
+END:VEVENT\r
+END:VCALENDAR\r
+"
+        );
+        ical::IcalParser::new(input.as_bytes())
+            .next()
+            .unwrap()
+            .unwrap()
+    }
+
+    #[test]
+    fn overflowing_repetitions() {
+        let calendar = repeating("4000000000", "P1000W");
+        let (after, before) = window(1, 31);
+        let alarms = calendar.next_alarms(after, before, &OlsonResolver).unwrap();
+        assert_eq!(alarms.len(), 1);
+        assert_eq!(alarms[0].repetition, 0);
+
+        // Later repetitions lie past the end of representable times
+        let before = DateTime::<Utc>::MAX_UTC;
+        let after = before - chrono::Duration::weeks(2000);
+        let alarms = calendar.next_alarms(after, before, &OlsonResolver).unwrap();
+        assert_eq!(alarms.len(), 2);
+        for occurrence in alarms {
+            assert_eq!(
+                occurrence.time,
+                window(1, 1).0 + chrono::Duration::weeks(1000) * occurrence.repetition as i32
+            );
+        }
+    }
+
+    #[test]
+    fn many_repetitions() {
+        // Only the repetitions within the window are visited
+        let calendar = repeating("2000000000", "PT1S");
+        let after = Utc.with_ymd_and_hms(2070, 1, 1, 0, 0, 0).unwrap();
+        let before = after + chrono::Duration::seconds(3);
+        let alarms = calendar.next_alarms(after, before, &OlsonResolver).unwrap();
+        assert_eq!(
+            alarms
+                .iter()
+                .map(|occurrence| (occurrence.time, occurrence.repetition))
+                .collect::<Vec<_>>(),
+            (0..3)
+                .map(|second| (
+                    after + chrono::Duration::seconds(second),
+                    1_420_070_400 + second as u32
+                ))
+                .collect::<Vec<_>>()
+        );
+    }
+}
+
+pub mod todo {
+    extern crate ical;
+
+    use chrono::{TimeZone, Utc};
+    use ical::parser::ical::component::{IcalTodo, TodoStatus};
+    use ical::parser::{ComponentMut, ParserError, VerifyOptions};
+    use ical::property::Property;
+    use ical::types::{CalDateOrDateTime, CalDateTimeError};
+
+    fn todo(lines: &[&str]) -> IcalTodo<false> {
+        let mut todo = IcalTodo::new();
+        for line in ["UID:1", "DTSTAMP:20250101T000000Z"].iter().chain(lines) {
+            let (name, value) = line.split_once(':').unwrap();
+            let mut params = name.split(';');
+            todo.add_property(Property {
+                name: params.next().unwrap().to_owned(),
+                params: params
+                    .map(|param| {
+                        let (key, value) = param.split_once('=').unwrap();
+                        (key.to_owned(), vec![value.to_owned()])
+                    })
+                    .collect(),
+                value: Some(value.to_owned()),
+            });
+        }
+        todo
+    }
+
+    #[test]
+    fn getters() {
+        let verified = todo(&[
+            "DTSTART:20250101T100000Z",
+            "DURATION:PT2H",
+            "STATUS:in-process",
+            "PERCENT-COMPLETE:40",
+            "PRIORITY:1",
+            "COMPLETED:20250101T113000Z",
+        ])
+        .verify()
+        .unwrap();
+        assert_eq!(verified.get_status(), Some(TodoStatus::InProcess));
+        assert_eq!(verified.get_percent_complete(), Some(40));
+        assert_eq!(verified.get_priority(), Some(1));
+        assert_eq!(
+            verified.get_completed(),
+            Some(Utc.with_ymd_and_hms(2025, 1, 1, 11, 30, 0).unwrap())
+        );
+        assert_eq!(
+            verified
+                .get_due_time(&Default::default())
+                .unwrap()
+                .map(|due| due.utc()),
+            Some(Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap())
+        );
+
+        let verified = todo(&["DUE;VALUE=DATE:20250819"]).verify().unwrap();
+        assert_eq!(verified.get_status(), None);
+        assert_eq!(verified.get_percent_complete(), None);
+        assert_eq!(verified.get_priority(), None);
+        assert_eq!(verified.get_completed(), None);
+        assert!(matches!(
+            verified.get_due_time(&Default::default()).unwrap(),
+            Some(CalDateOrDateTime::Date(_))
+        ));
+    }
+
+    #[rstest::rstest]
+    #[case(&["DTSTART:20250101T100000Z", "DUE:20250101T120000Z", "DURATION:PT2H"], ParserError::PropertyConflict("both DUE and DURATION are defined"))]
+    #[case(&["DURATION:PT2H"], ParserError::MissingProperty("DTSTART"))]
+    #[case(&["DTSTART:20250101T100000Z", "DUE:20250101T090000Z"], ParserError::PropertyConflict("DUE must not be earlier than DTSTART"))]
+    #[case(&["DTSTART;TZID=Europe/Berlin:20250101T100000", "DUE:20250101T083000Z"], ParserError::PropertyConflict("DUE must not be earlier than DTSTART"))]
+    fn invalid(#[case] lines: &[&str], #[case] expected: ParserError) {
+        assert_eq!(todo(lines).verify().unwrap_err(), expected);
+    }
+
+    #[rstest::rstest]
+    #[case(&["PERCENT-COMPLETE:101"], ParserError::InvalidValue("PERCENT-COMPLETE"))]
+    #[case(&["PRIORITY:high"], ParserError::InvalidValue("PRIORITY"))]
+    #[case(&["PRIORITY:10"], ParserError::InvalidValue("PRIORITY"))]
+    #[case(&["COMPLETED:yesterday"], ParserError::InvalidDateTime(CalDateTimeError::InvalidDatetimeFormat("yesterday".to_owned())))]
+    fn invalid_values(#[case] lines: &[&str], #[case] expected: ParserError) {
+        assert_eq!(
+            todo(lines)
+                .verify_with(&VerifyOptions::strict())
+                .unwrap_err(),
+            expected
+        );
+
+        // Only checked by the strict profiles
+        let verified = todo(lines).verify().unwrap();
+        assert_eq!(verified.get_percent_complete(), None);
+        assert_eq!(verified.get_priority(), None);
+        assert_eq!(verified.get_completed(), None);
+    }
+
+    #[rstest::rstest]
+    #[case(&["DTSTART:20250101T100000Z", "DUE:20250101T100000Z"])]
+    #[case(&["DTSTART;TZID=Europe/Berlin:20250101T100000", "DUE:20250101T093000Z"])]
+    #[case(&["DTSTART;VALUE=DATE:20250101", "DUE;VALUE=DATE:20250102"])]
+    // Only defined by a VTIMEZONE of the calendar
+    #[case(&["DTSTART;TZID=Custom Time:20250101T100000", "DUE:20250101T083000Z"])]
+    fn valid(#[case] lines: &[&str]) {
+        assert!(todo(lines).verify().is_ok());
+    }
+}
+
+pub mod todo_completion {
+    extern crate ical;
+
+    use chrono::{TimeZone, Utc};
+    use ical::parser::Component;
+    use ical::parser::ParserError;
+    use ical::parser::ical::component::{
+        CalendarInnerData, CompletionStrategy, IcalCalendarObject, IcalTodo,
+    };
+    use ical::parser::ical::recurrence::{expand, resolve_timezones};
+    use ical::types::OlsonResolver;
+
+    const SERIES: &str = "BEGIN:VCALENDAR\r
+BEGIN:VTODO\r
+UID:plants\r
+DTSTAMP:20250101T000000Z\r
+DTSTART:20250106T090000Z\r
+DUE:20250106T170000Z\r
+RRULE:FREQ=WEEKLY;COUNT=3\r
+SUMMARY:Water the plants\r
+END:VTODO\r
+END:VCALENDAR\r
+";
+
+    fn parse(input: &str) -> IcalCalendarObject {
+        ical::IcalObjectParser::new(input.as_bytes())
+            .next()
+            .unwrap()
+            .unwrap()
+    }
+
+    fn todos(object: &IcalCalendarObject) -> (&IcalTodo, &Vec<IcalTodo>) {
+        match object.get_inner() {
+            CalendarInnerData::Todo(main, overrides) => (main, overrides),
+            _ => panic!("expected a to-do"),
+        }
+    }
+
+    fn values<'t>(todo: &'t IcalTodo, names: &[&str]) -> Vec<Option<&'t str>> {
+        names
+            .iter()
+            .map(|name| {
+                todo.get_property(name)
+                    .and_then(|prop| prop.value.as_deref())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn advance() {
+        let mut object = parse(SERIES);
+        let time = Utc.with_ymd_and_hms(2025, 1, 6, 12, 0, 0).unwrap();
+        let complete = |object: &mut IcalCalendarObject| {
+            object.complete_todo(time, CompletionStrategy::Advance, &OlsonResolver)
+        };
+        let names = ["DTSTART", "DUE", "RRULE", "STATUS", "SEQUENCE"];
+
+        complete(&mut object).unwrap();
+        let (main, overrides) = todos(&object);
+        assert!(overrides.is_empty());
+        assert_eq!(
+            values(main, &names),
+            [
+                Some("20250113T090000Z"),
+                Some("20250113T170000Z"),
+                Some("FREQ=WEEKLY;COUNT=2"),
+                Some("NEEDS-ACTION"),
+                Some("1"),
+            ]
+        );
+
+        complete(&mut object).unwrap();
+        complete(&mut object).unwrap();
+        let (main, _) = todos(&object);
+        assert_eq!(
+            values(main, &names),
+            [
+                Some("20250120T090000Z"),
+                Some("20250120T170000Z"),
+                Some("FREQ=WEEKLY;COUNT=1"),
+                Some("COMPLETED"),
+                Some("3"),
+            ]
+        );
+        assert_eq!(
+            values(main, &["COMPLETED", "PERCENT-COMPLETE"]),
+            [Some("20250106T120000Z"), Some("100")]
+        );
+
+        assert_eq!(
+            complete(&mut object),
+            Err(ParserError::PropertyConflict(
+                "the to-do is already completed"
+            ))
+        );
+    }
+
+    #[test]
+    fn advance_rdates() {
+        let mut object = parse(
+            "BEGIN:VCALENDAR\r
+BEGIN:VTODO\r
+UID:1\r
+DTSTAMP:20250101T000000Z\r
+DTSTART:20250106T090000Z\r
+RDATE:20250108T090000Z,20250110T090000Z\r
+RDATE:20250112T090000Z\r
+END:VTODO\r
+END:VCALENDAR\r
+",
+        );
+        let time = Utc.with_ymd_and_hms(2025, 1, 6, 12, 0, 0).unwrap();
+        for _ in 0..2 {
+            object
+                .complete_todo(time, CompletionStrategy::Advance, &OlsonResolver)
+                .unwrap();
+        }
+        let (main, _) = todos(&object);
+        assert_eq!(
+            values(main, &["DTSTART", "STATUS"]),
+            [Some("20250110T090000Z"), Some("NEEDS-ACTION")]
+        );
+        assert_eq!(
+            main.get_named_properties("RDATE")
+                .into_iter()
+                .map(|prop| prop.value.as_deref())
+                .collect::<Vec<_>>(),
+            [Some("20250110T090000Z"), Some("20250112T090000Z")]
+        );
+
+        // The completed instances don't come back
+        let timezones = resolve_timezones(&main.properties, &[], &OlsonResolver);
+        let starts: Vec<_> = expand(
+            [main],
+            &timezones,
+            Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2025, 2, 1, 0, 0, 0).unwrap(),
+        )
+        .unwrap()
+        .into_iter()
+        .map(|instance| instance.start.unwrap().utc())
+        .collect();
+        assert_eq!(
+            starts,
+            [
+                Utc.with_ymd_and_hms(2025, 1, 10, 9, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2025, 1, 12, 9, 0, 0).unwrap(),
+            ]
+        );
+    }
+
+    #[test]
+    fn advance_dates() {
+        let mut object = parse(
+            "BEGIN:VCALENDAR\r
+BEGIN:VTODO\r
+UID:1\r
+DTSTAMP:20250101T000000Z\r
+DTSTART;VALUE=DATE:20250131\r
+DUE;VALUE=DATE:20250201\r
+RRULE:FREQ=MONTHLY;BYMONTHDAY=-1\r
+END:VTODO\r
+END:VCALENDAR\r
+",
+        );
+        object
+            .complete_todo(Utc::now(), CompletionStrategy::Advance, &OlsonResolver)
+            .unwrap();
+        let (main, _) = todos(&object);
+        assert_eq!(
+            values(main, &["DTSTART", "DUE", "RRULE"]),
+            [
+                Some("20250228"),
+                Some("20250301"),
+                Some("FREQ=MONTHLY;BYMONTHDAY=-1"),
+            ]
+        );
+    }
+
+    #[test]
+    fn completed_override() {
+        let mut object = parse(SERIES);
+        let time = Utc.with_ymd_and_hms(2025, 1, 6, 12, 0, 0).unwrap();
+        let complete = |object: &mut IcalCalendarObject| {
+            object.complete_todo(time, CompletionStrategy::Override, &OlsonResolver)
+        };
+
+        complete(&mut object).unwrap();
+        complete(&mut object).unwrap();
+        let (main, overrides) = todos(&object);
+        assert_eq!(main.get_property("STATUS"), None);
+        assert_eq!(main.get_property("SEQUENCE"), None);
+        assert_eq!(
+            overrides
+                .iter()
+                .map(|todo| values(
+                    todo,
+                    &[
+                        "RECURRENCE-ID",
+                        "DTSTART",
+                        "DUE",
+                        "RRULE",
+                        "STATUS",
+                        "COMPLETED",
+                        "PERCENT-COMPLETE",
+                        "SEQUENCE"
+                    ]
+                ))
+                .collect::<Vec<_>>(),
+            [
+                [
+                    Some("20250106T090000Z"),
+                    Some("20250106T090000Z"),
+                    Some("20250106T170000Z"),
+                    None,
+                    Some("COMPLETED"),
+                    Some("20250106T120000Z"),
+                    Some("100"),
+                    Some("1"),
+                ],
+                [
+                    Some("20250113T090000Z"),
+                    Some("20250113T090000Z"),
+                    Some("20250113T170000Z"),
+                    None,
+                    Some("COMPLETED"),
+                    Some("20250106T120000Z"),
+                    Some("100"),
+                    Some("1"),
+                ],
+            ]
+        );
+
+        // Completing the last instance completes the series
+        complete(&mut object).unwrap();
+        let (main, overrides) = todos(&object);
+        assert_eq!(overrides.len(), 3);
+        assert_eq!(
+            values(main, &["STATUS", "COMPLETED", "SEQUENCE"]),
+            [Some("COMPLETED"), Some("20250106T120000Z"), Some("1")]
+        );
+        assert_eq!(
+            complete(&mut object),
+            Err(ParserError::PropertyConflict(
+                "the to-do is already completed"
+            ))
+        );
+    }
+
+    #[test]
+    fn completed_overrides_complete_series() {
+        let mut object = parse(&SERIES.replace(
+            "END:VTODO\r\nEND:VCALENDAR",
+            "END:VTODO\r
+BEGIN:VTODO\r
+UID:plants\r
+DTSTAMP:20250101T000000Z\r
+RECURRENCE-ID:20250120T090000Z\r
+DTSTART:20250120T090000Z\r
+DUE:20250120T170000Z\r
+STATUS:COMPLETED\r
+END:VTODO\r
+END:VCALENDAR",
+        ));
+        let time = Utc.with_ymd_and_hms(2025, 1, 6, 12, 0, 0).unwrap();
+        for _ in 0..2 {
+            object
+                .complete_todo(time, CompletionStrategy::Override, &OlsonResolver)
+                .unwrap();
+        }
+        let (main, overrides) = todos(&object);
+        assert_eq!(overrides.len(), 3);
+        assert_eq!(values(main, &["STATUS"]), [Some("COMPLETED")]);
+    }
+
+    #[test]
+    fn cancelled_override_is_skipped() {
+        let mut object = parse(&SERIES.replace(
+            "END:VTODO\r\nEND:VCALENDAR",
+            "END:VTODO\r
+BEGIN:VTODO\r
+UID:plants\r
+DTSTAMP:20250101T000000Z\r
+RECURRENCE-ID:20250106T090000Z\r
+DTSTART:20250106T090000Z\r
+DUE:20250106T170000Z\r
+STATUS:CANCELLED\r
+END:VTODO\r
+END:VCALENDAR",
+        ));
+        let time = Utc.with_ymd_and_hms(2025, 1, 6, 12, 0, 0).unwrap();
+        let complete = |object: &mut IcalCalendarObject| {
+            object.complete_todo(time, CompletionStrategy::Override, &OlsonResolver)
+        };
+
+        // The cancelled first instance stays cancelled, and the second one is completed
+        complete(&mut object).unwrap();
+        let (main, overrides) = todos(&object);
+        assert!(!main.is_completed());
+        assert_eq!(
+            overrides
+                .iter()
+                .map(|todo| values(todo, &["RECURRENCE-ID", "STATUS"]))
+                .collect::<Vec<_>>(),
+            [
+                [Some("20250106T090000Z"), Some("CANCELLED")],
+                [Some("20250113T090000Z"), Some("COMPLETED")],
+            ]
+        );
+
+        // Once every other instance is completed, so is the series
+        complete(&mut object).unwrap();
+        let (main, overrides) = todos(&object);
+        assert!(main.is_completed());
+        assert_eq!(values(&overrides[0], &["STATUS"]), [Some("CANCELLED")]);
+    }
+
+    #[test]
+    fn advance_drops_past_overrides() {
+        let mut object = parse(&SERIES.replace(
+            "END:VTODO\r\nEND:VCALENDAR",
+            "END:VTODO\r
+BEGIN:VTODO\r
+UID:plants\r
+DTSTAMP:20250101T000000Z\r
+RECURRENCE-ID:20250106T090000Z\r
+DTSTART:20250106T100000Z\r
+SUMMARY:Water the plants later\r
+END:VTODO\r
+BEGIN:VTODO\r
+UID:plants\r
+DTSTAMP:20250101T000000Z\r
+RECURRENCE-ID:20250113T090000Z\r
+DTSTART:20250113T100000Z\r
+SUMMARY:Water the plants later\r
+END:VTODO\r
+END:VCALENDAR",
+        ));
+        let time = Utc.with_ymd_and_hms(2025, 1, 6, 12, 0, 0).unwrap();
+        object
+            .complete_todo(time, CompletionStrategy::Advance, &OlsonResolver)
+            .unwrap();
+        let (main, overrides) = todos(&object);
+        assert_eq!(values(main, &["DTSTART"]), [Some("20250113T090000Z")]);
+        assert_eq!(
+            overrides
+                .iter()
+                .map(|todo| values(todo, &["RECURRENCE-ID"]))
+                .collect::<Vec<_>>(),
+            [[Some("20250113T090000Z")]]
+        );
+    }
+
+    #[rstest::rstest]
+    #[case(CompletionStrategy::Advance)]
+    #[case(CompletionStrategy::Override)]
+    fn single(#[case] strategy: CompletionStrategy) {
+        let mut object = parse(
+            "BEGIN:VCALENDAR\r
+BEGIN:VTODO\r
+UID:1\r
+DTSTAMP:20250101T000000Z\r
+SEQUENCE:4\r
+STATUS:IN-PROCESS\r
+PERCENT-COMPLETE:50\r
+END:VTODO\r
+END:VCALENDAR\r
+",
+        );
+        let time = Utc.with_ymd_and_hms(2025, 1, 6, 12, 0, 0).unwrap();
+        object
+            .complete_todo(time, strategy, &OlsonResolver)
+            .unwrap();
+        let (main, overrides) = todos(&object);
+        assert!(overrides.is_empty());
+        assert_eq!(
+            values(
+                main,
+                &["STATUS", "COMPLETED", "PERCENT-COMPLETE", "SEQUENCE"]
+            ),
+            [
+                Some("COMPLETED"),
+                Some("20250106T120000Z"),
+                Some("100"),
+                Some("5"),
+            ]
+        );
+    }
+
+    #[test]
+    fn not_a_todo() {
+        let mut object = parse(
+            "BEGIN:VCALENDAR\r
+BEGIN:VEVENT\r
+UID:1\r
+DTSTART:20250106T090000Z\r
+END:VEVENT\r
+END:VCALENDAR\r
+",
+        );
+        assert_eq!(
+            object.complete_todo(Utc::now(), CompletionStrategy::Advance, &OlsonResolver),
+            Err(ParserError::InvalidComponent)
+        );
+    }
+}
+
+pub mod hierarchy {
+    extern crate ical;
+
+    use ical::parser::Component;
+    use ical::parser::ical::component::{CalendarInnerData, IcalTodo};
+    use ical::parser::ical::hierarchy::{
+        HierarchyError, HierarchyIssue, RelationType, TodoHierarchy, reparent, reparent_objects,
+    };
+
+    fn todos(relations: &[(&str, &[&str])]) -> Vec<IcalTodo> {
+        let input = relations
+            .iter()
+            .map(|(uid, related)| {
+                format!(
+                    "BEGIN:VTODO\r\nUID:{uid}\r\nDTSTAMP:20250101T000000Z\r\n{}END:VTODO\r\n",
+                    related
+                        .iter()
+                        .map(|line| format!("RELATED-TO{line}\r\n"))
+                        .collect::<String>()
+                )
+            })
+            .collect::<String>();
+        let input = format!("BEGIN:VCALENDAR\r\n{input}END:VCALENDAR\r\n");
+        ical::IcalParser::new(input.as_bytes())
+            .next()
+            .unwrap()
+            .unwrap()
+            .todos
+    }
+
+    fn relations(todo: &IcalTodo) -> Vec<String> {
+        todo.get_named_properties("RELATED-TO")
+            .into_iter()
+            .map(|prop| {
+                format!(
+                    "{}:{}",
+                    prop.get_param("RELTYPE").unwrap_or("PARENT"),
+                    prop.value.as_deref().unwrap()
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn build() {
+        let todos = todos(&[
+            ("a", &[";RELTYPE=CHILD:c"]),
+            ("b", &[":a", ";RELTYPE=CHILD:h"]),
+            ("c", &[]),
+            ("d", &[";RELTYPE=SIBLING:b"]),
+            ("e", &[";RELTYPE=PARENT:ghost"]),
+            ("f", &[":g"]),
+            ("g", &[":f"]),
+            ("h", &[":a"]),
+        ]);
+        let hierarchy = TodoHierarchy::new(&todos);
+
+        assert_eq!(hierarchy.roots(), ["a", "d", "e"]);
+        assert_eq!(hierarchy.children("a"), ["b", "c"]);
+        assert_eq!(hierarchy.children("b"), ["h"]);
+        assert_eq!(hierarchy.parent("c"), Some("a"));
+        assert_eq!(hierarchy.parent("e"), None);
+        assert_eq!(hierarchy.siblings("b"), ["d"]);
+        assert_eq!(hierarchy.siblings("d"), ["b"]);
+        assert_eq!(hierarchy.descendants("a"), ["b", "h", "c"]);
+        assert_eq!(hierarchy.descendants("f"), ["g"]);
+        assert_eq!(hierarchy.get("c").unwrap().get_uid(), "c");
+        assert_eq!(
+            hierarchy.issues(),
+            [
+                HierarchyIssue::MissingRelative {
+                    uid: "e".to_owned(),
+                    related: "ghost".to_owned(),
+                    reltype: RelationType::Parent
+                },
+                HierarchyIssue::MultipleParents {
+                    uid: "h".to_owned(),
+                    parents: vec!["b".to_owned(), "a".to_owned()]
+                },
+                HierarchyIssue::Cycle(vec!["f".to_owned(), "g".to_owned()]),
+            ]
+        );
+    }
+
+    #[test]
+    fn reparent_todos() {
+        let mut todos = todos(&[("a", &[";RELTYPE=CHILD:c"]), ("b", &[":a"]), ("c", &[])]);
+
+        reparent(&mut todos, "c", Some("b")).unwrap();
+        assert_eq!(relations(&todos[0]), Vec::<String>::new());
+        assert_eq!(relations(&todos[1]), ["PARENT:a", "CHILD:c"]);
+        assert_eq!(relations(&todos[2]), ["PARENT:b"]);
+        assert_eq!(TodoHierarchy::new(&todos).descendants("a"), ["b", "c"]);
+
+        assert_eq!(
+            reparent(&mut todos, "b", Some("c")),
+            Err(HierarchyError::Cycle("b".to_owned()))
+        );
+        assert_eq!(
+            reparent(&mut todos, "b", Some("ghost")),
+            Err(HierarchyError::UnknownTodo("ghost".to_owned()))
+        );
+
+        reparent(&mut todos, "b", None).unwrap();
+        assert_eq!(relations(&todos[1]), ["CHILD:c"]);
+        assert_eq!(TodoHierarchy::new(&todos).roots(), ["a", "b"]);
+    }
+
+    #[test]
+    fn objects() {
+        let input = "BEGIN:VCALENDAR\r
+BEGIN:VTODO\r
+UID:parent\r
+DTSTAMP:20250101T000000Z\r
+END:VTODO\r
+END:VCALENDAR\r
+BEGIN:VCALENDAR\r
+BEGIN:VTODO\r
+UID:child\r
+DTSTAMP:20250101T000000Z\r
+DTSTART:20250106T090000Z\r
+RRULE:FREQ=DAILY\r
+END:VTODO\r
+BEGIN:VTODO\r
+UID:child\r
+DTSTAMP:20250101T000000Z\r
+RECURRENCE-ID:20250107T090000Z\r
+DTSTART:20250107T100000Z\r
+END:VTODO\r
+END:VCALENDAR\r
+";
+        let mut objects: Vec<_> = ical::IcalObjectParser::new(input.as_bytes())
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        reparent_objects(&mut objects, "child", Some("parent")).unwrap();
+        let hierarchy = TodoHierarchy::from_objects(&objects);
+        assert_eq!(hierarchy.children("parent"), ["child"]);
+        let CalendarInnerData::Todo(main, overrides) = objects[1].get_inner() else {
+            panic!("expected a to-do");
+        };
+        assert_eq!(relations(main), ["PARENT:parent"]);
+        assert_eq!(relations(&overrides[0]), ["PARENT:parent"]);
+    }
+}
+
+pub mod freebusy {
+    extern crate ical;
+
+    use chrono::{Duration, TimeZone, Utc};
+    use ical::generator::Emitter;
+    use ical::parser::ical::component::{
+        FreeBusyPeriod, FreeBusyType, IcalCalendar, IcalFreeBusy, busy_periods,
+    };
+    use ical::parser::ical::validator;
+    use ical::parser::{Component, ComponentMut, ParserError, VerifyOptions};
+    use ical::property::Property;
+    use ical::types::{CalDateTimeError, OlsonResolver, Period};
+
+    #[test]
+    fn getters() {
+        let input = include_str!("./resources/ical_freebusy.ics");
+        let calendar = ical::IcalParser::new(input.as_bytes())
+            .next()
+            .unwrap()
+            .unwrap();
+        let freebusy = &calendar.free_busys[0];
+
+        assert_eq!(
+            freebusy.get_dtstart(),
+            Some(Utc.with_ymd_and_hms(1998, 3, 13, 14, 17, 11).unwrap())
+        );
+        assert_eq!(
+            freebusy.get_dtend(),
+            Some(Utc.with_ymd_and_hms(1998, 4, 10, 14, 17, 11).unwrap())
+        );
+        assert_eq!(freebusy.get_organizer(), Some("mailto:jsmith@example.com"));
+        assert!(freebusy.get_attendees().is_empty());
+
+        let periods = freebusy.get_periods();
+        assert_eq!(periods.len(), 3);
+        assert!(periods.iter().all(|fb| fb.fbtype == FreeBusyType::Busy));
+        assert_eq!(
+            periods[0].period.start().utc(),
+            Utc.with_ymd_and_hms(1998, 3, 14, 23, 30, 0).unwrap()
+        );
+        assert_eq!(periods[0].period.duration(), Duration::hours(1));
+    }
+
+    #[test]
+    fn fbtype() {
+        let input = "BEGIN:VCALENDAR\r
+BEGIN:VFREEBUSY\r
+ATTENDEE:mailto:a@example.com\r
+ATTENDEE:mailto:b@example.com\r
+FREEBUSY;FBTYPE=FREE:19970308T160000Z/PT3H,19970308T200000Z/PT1H\r
+FREEBUSY;FBTYPE=BUSY-TENTATIVE:19970308T230000Z/19970309T000000Z\r
+FREEBUSY;FBTYPE=BUSY-UNAVAILABLE:19970309T010000Z/PT30M\r
+FREEBUSY;FBTYPE=X-OUT-OF-OFFICE:19970309T020000Z/PT30M\r
+END:VFREEBUSY\r
+END:VCALENDAR\r
+";
+        let calendar = ical::IcalParser::new(input.as_bytes())
+            .next()
+            .unwrap()
+            .unwrap();
+        let freebusy = &calendar.free_busys[0];
+
+        assert_eq!(
+            freebusy.get_attendees(),
+            ["mailto:a@example.com", "mailto:b@example.com"]
+        );
+        assert_eq!(
+            freebusy
+                .get_periods()
+                .into_iter()
+                .map(|FreeBusyPeriod { fbtype, period }| (fbtype, period.end().utc()))
+                .collect::<Vec<_>>(),
+            [
+                (
+                    FreeBusyType::Free,
+                    Utc.with_ymd_and_hms(1997, 3, 8, 19, 0, 0).unwrap()
+                ),
+                (
+                    FreeBusyType::Free,
+                    Utc.with_ymd_and_hms(1997, 3, 8, 21, 0, 0).unwrap()
+                ),
+                (
+                    FreeBusyType::BusyTentative,
+                    Utc.with_ymd_and_hms(1997, 3, 9, 0, 0, 0).unwrap()
+                ),
+                (
+                    FreeBusyType::BusyUnavailable,
+                    Utc.with_ymd_and_hms(1997, 3, 9, 1, 30, 0).unwrap()
+                ),
+                (
+                    FreeBusyType::Other("X-OUT-OF-OFFICE".to_owned()),
+                    Utc.with_ymd_and_hms(1997, 3, 9, 2, 30, 0).unwrap()
+                ),
+            ]
+        );
+    }
+
+    #[rstest::rstest]
+    #[case("19970308T160000Z", CalDateTimeError::InvalidPeriodFormat("19970308T160000Z".to_owned()))]
+    #[case("1997-03-08/PT1H", CalDateTimeError::InvalidDatetimeFormat("1997-03-08".to_owned()))]
+    #[case("19970308T160000Z/PX", CalDateTimeError::InvalidDurationFormat("PX".to_owned()))]
+    #[case(
+        "19970308T160000Z/P99999999999999999999W",
+        CalDateTimeError::InvalidDurationFormat("P99999999999999999999W".to_owned())
+    )]
+    #[case(
+        "19970308T160000Z/P9999999999999W",
+        CalDateTimeError::InvalidDurationFormat("P9999999999999W".to_owned())
+    )]
+    #[case(
+        "19970308T160000Z/P99999999W",
+        CalDateTimeError::InvalidDurationFormat("P99999999W".to_owned())
+    )]
+    fn invalid_period(#[case] value: &str, #[case] expected: CalDateTimeError) {
+        assert_eq!(Period::parse(value, None).unwrap_err(), expected);
+
+        let input = format!(
+            "BEGIN:VCALENDAR\r\nBEGIN:VFREEBUSY\r\nDTSTAMP:19970101T000000Z\r\n\
+            FREEBUSY:{value},19970308T200000Z/PT1H\r\nEND:VFREEBUSY\r\nEND:VCALENDAR\r\n"
+        );
+        assert_eq!(
+            ical::IcalParser::new(input.as_bytes())
+                .with_verify_options(VerifyOptions::strict())
+                .next()
+                .unwrap()
+                .unwrap_err(),
+            ParserError::InvalidDateTime(expected)
+        );
+
+        // Lenient parsing skips the malformed period
+        let calendar = ical::IcalParser::new(input.as_bytes())
+            .next()
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            calendar.free_busys[0]
+                .get_periods()
+                .into_iter()
+                .map(|period| period.period.start().utc())
+                .collect::<Vec<_>>(),
+            [Utc.with_ymd_and_hms(1997, 3, 8, 20, 0, 0).unwrap()]
+        );
+    }
+
+    #[test]
+    fn invalid_dates() {
+        let input = "BEGIN:VCALENDAR\r\nBEGIN:VFREEBUSY\r\nDTSTART:tomorrow\r\n\
+            DTEND:19970309T000000Z\r\nEND:VFREEBUSY\r\nEND:VCALENDAR\r\n";
+        let calendar = ical::IcalParser::new(input.as_bytes())
+            .next()
+            .unwrap()
+            .unwrap();
+        assert_eq!(calendar.free_busys[0].get_dtstart(), None);
+        assert_eq!(
+            calendar.free_busys[0].get_dtend(),
+            Some(Utc.with_ymd_and_hms(1997, 3, 9, 0, 0, 0).unwrap())
+        );
+    }
+
+    #[test]
+    fn aggregate() {
+        let input = include_str!("./resources/ical_freebusy_events.ics");
+        let objects: Vec<_> = ical::IcalObjectParser::new(input.as_bytes())
+            .collect::<Result<_, _>>()
+            .unwrap();
+        let start = Utc.with_ymd_and_hms(2025, 1, 5, 0, 0, 0).unwrap();
+        let end = Utc.with_ymd_and_hms(2025, 1, 21, 0, 0, 0).unwrap();
+
+        let freebusy =
+            IcalFreeBusy::aggregate(&objects, "busy@example.com", start, end, &OlsonResolver)
+                .unwrap();
+        assert_eq!(
+            freebusy
+                .get_property("UID")
+                .and_then(|prop| prop.value.as_deref()),
+            Some("busy@example.com")
+        );
+        assert_eq!(freebusy.get_dtstart(), Some(start));
+        assert_eq!(freebusy.get_dtend(), Some(end));
+        assert!(freebusy.get_property("DTSTAMP").is_some());
+        assert_eq!(
+            freebusy
+                .get_named_properties("FREEBUSY")
+                .into_iter()
+                .map(Emitter::generate)
+                .collect::<String>(),
+            "FREEBUSY:20250105T000000Z/20250107T000000Z,20250113T140000Z/20250113T150000\r\n \
+             Z\r\n\
+             FREEBUSY;FBTYPE=BUSY-TENTATIVE:20250108T090000Z/20250108T100000Z\r\n"
+        );
+
+        let mut calendar = IcalCalendar::new();
+        calendar.add_property(ical::ical_property!("PRODID", "-//ical-rs//EN"));
+        calendar.add_property(ical::ical_property!("VERSION", "2.0"));
+        calendar.free_busys.push(freebusy);
+        assert_eq!(validator::validate(&calendar), vec![]);
+    }
+
+    #[rstest::rstest]
+    #[case("RDATE:20250108T090000Z\r\n", &[6, 8])]
+    // DTSTART is an instance even if the RRULE doesn't match it
+    #[case("RRULE:FREQ=WEEKLY;BYDAY=WE;COUNT=2\r\n", &[6, 8, 15])]
+    #[case("RRULE:FREQ=DAILY;COUNT=2\r\nRDATE:20250106T090000Z\r\n", &[6, 7])]
+    #[case("RDATE:20250108T090000Z\r\nEXDATE:20250106T090000Z\r\n", &[8])]
+    fn aggregate_recurrence_set(#[case] recurrence: &str, #[case] days: &[u32]) {
+        let input = format!(
+            "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nUID:1\r\nDTSTART:20250106T090000Z\r\n\
+            DURATION:PT1H\r\n{recurrence}END:VEVENT\r\nEND:VCALENDAR\r\n"
+        );
+        let objects: Vec<_> = ical::IcalObjectParser::new(input.as_bytes())
+            .collect::<Result<_, _>>()
+            .unwrap();
+        let busy = busy_periods(
+            &objects,
+            Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2025, 2, 1, 0, 0, 0).unwrap(),
+            &OlsonResolver,
+        )
+        .unwrap();
+        assert_eq!(
+            busy.iter()
+                .map(|period| period.period.start().utc())
+                .collect::<Vec<_>>(),
+            days.iter()
+                .map(|day| Utc.with_ymd_and_hms(2025, 1, *day, 9, 0, 0).unwrap())
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn aggregate_duration_out_of_range() {
+        // The end of the event doesn't fit into a date-time
+        let input = "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nUID:1\r\nDTSTART:20250106T090000Z\r\n\
+            DURATION:P99999999W\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n";
+        let objects: Vec<_> = ical::IcalObjectParser::new(input.as_bytes())
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(
+            busy_periods(
+                &objects,
+                Utc.with_ymd_and_hms(2025, 1, 5, 0, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2025, 1, 21, 0, 0, 0).unwrap(),
+                &OlsonResolver
+            )
+            .unwrap_err(),
+            ParserError::InvalidValue("DURATION")
+        );
+    }
+}
+
+pub mod slots {
+    extern crate ical;
+
+    use chrono::{DateTime, Duration, NaiveTime, TimeZone, Utc, Weekday};
+    use ical::parser::ParserError;
+    use ical::parser::ical::slots::{Slot, SlotFinder, WorkingHours};
+    use ical::types::{CalDateTimeError, OlsonResolver};
+
+    fn utc(day: u32, hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 1, day, hour, 0, 0).unwrap()
+    }
+
+    fn slot(day: u32, start: u32, end: u32) -> Slot {
+        Slot {
+            start: utc(day, start),
+            end: utc(day, end),
+        }
+    }
+
+    #[test]
+    fn working_hours() {
+        let input = include_str!("./resources/ical_freebusy_events.ics");
+        let freebusy = "BEGIN:VCALENDAR\r
+BEGIN:VFREEBUSY\r
+FREEBUSY:20250107T120000Z/PT1H\r
+FREEBUSY;FBTYPE=FREE:20250108T080000Z/PT8H\r
+END:VFREEBUSY\r
+END:VCALENDAR\r
+";
+
+        let mut finder =
+            SlotFinder::new(utc(6, 0), utc(14, 0)).with_working_hours(WorkingHours::new(
+                NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+                NaiveTime::from_hms_opt(17, 0, 0).unwrap(),
+                chrono_tz::Europe::Berlin,
+            ));
+        for calendar in ical::IcalParser::new(input.as_bytes()) {
+            finder
+                .add_calendar(&calendar.unwrap(), &OlsonResolver)
+                .unwrap();
+        }
+        let calendar = ical::IcalParser::new(freebusy.as_bytes())
+            .next()
+            .unwrap()
+            .unwrap();
+        finder.add_freebusy(&calendar.free_busys[0]);
+
+        assert_eq!(
+            finder.free_slots(Duration::hours(2)).unwrap(),
+            [
+                slot(7, 8, 12),
+                slot(7, 13, 16),
+                slot(8, 10, 16),
+                slot(9, 8, 16),
+                slot(10, 8, 16),
+                slot(13, 8, 14),
+            ]
+        );
+        assert_eq!(
+            finder.free_slots(Duration::hours(1)).unwrap()[7],
+            slot(13, 15, 16)
+        );
+    }
+
+    #[test]
+    fn overnight() {
+        let night_shift = WorkingHours::new(
+            NaiveTime::from_hms_opt(22, 0, 0).unwrap(),
+            NaiveTime::from_hms_opt(6, 0, 0).unwrap(),
+            chrono_tz::UTC,
+        );
+        let finder = SlotFinder::new(utc(6, 0), utc(8, 0)).with_working_hours(night_shift.clone());
+        assert_eq!(
+            finder.free_slots(Duration::hours(1)).unwrap(),
+            [
+                Slot {
+                    start: utc(6, 22),
+                    end: utc(7, 6),
+                },
+                Slot {
+                    start: utc(7, 22),
+                    end: utc(8, 0),
+                },
+            ]
+        );
+
+        // The night shift starting on Monday lasts until Tuesday morning
+        let finder = SlotFinder::new(utc(7, 0), utc(7, 12)).with_working_hours(night_shift);
+        assert_eq!(
+            finder.free_slots(Duration::zero()).unwrap(),
+            [slot(7, 0, 6)]
+        );
+    }
+
+    #[test]
+    fn dst_gap() {
+        let finder = SlotFinder::new(
+            Utc.with_ymd_and_hms(2025, 3, 28, 0, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2025, 4, 1, 0, 0, 0).unwrap(),
+        )
+        .with_working_hours(WorkingHours {
+            days: vec![Weekday::Sat, Weekday::Sun],
+            ..WorkingHours::new(
+                NaiveTime::from_hms_opt(2, 30, 0).unwrap(),
+                NaiveTime::from_hms_opt(6, 0, 0).unwrap(),
+                chrono_tz::Europe::Berlin,
+            )
+        });
+        assert_eq!(
+            finder.free_slots(Duration::hours(1)).unwrap_err(),
+            ParserError::InvalidDateTime(CalDateTimeError::LocalTimeGap)
+        );
+    }
+
+    #[test]
+    fn candidates() {
+        let input = include_str!("./resources/ical_freebusy_events.ics");
+        let objects: Vec<_> = ical::IcalObjectParser::new(input.as_bytes())
+            .map(Result::unwrap)
+            .collect();
+
+        let mut finder = SlotFinder::new(utc(8, 8), utc(8, 11));
+        finder.add_objects(&objects, &OlsonResolver).unwrap();
+
+        assert_eq!(
+            finder.free_slots(Duration::zero()).unwrap(),
+            [slot(8, 8, 9), slot(8, 10, 11)]
+        );
+        assert_eq!(
+            finder
+                .candidates(Duration::minutes(30), Duration::minutes(15))
+                .unwrap(),
+            [
+                Slot {
+                    start: utc(8, 8),
+                    end: utc(8, 8) + Duration::minutes(30),
+                },
+                Slot {
+                    start: utc(8, 8) + Duration::minutes(15),
+                    end: utc(8, 8) + Duration::minutes(45),
+                },
+                Slot {
+                    start: utc(8, 8) + Duration::minutes(30),
+                    end: utc(8, 9),
+                },
+                Slot {
+                    start: utc(8, 10),
+                    end: utc(8, 10) + Duration::minutes(30),
+                },
+                Slot {
+                    start: utc(8, 10) + Duration::minutes(15),
+                    end: utc(8, 10) + Duration::minutes(45),
+                },
+                Slot {
+                    start: utc(8, 10) + Duration::minutes(30),
+                    end: utc(8, 11),
+                },
+            ]
+        );
+    }
+}
+
+pub mod availability {
+    extern crate ical;
+
+    use chrono::{DateTime, TimeZone, Utc};
+    use ical::generator::Emitter;
+    use ical::parser::ical::availability::{Availability, AvailabilityEvaluator};
+    use ical::parser::ical::component::BusyType;
+    use ical::parser::{ParserError, VerifyOptions};
+    use ical::types::OlsonResolver;
+
+    fn utc(month: u32, day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, month, day, hour, minute, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn getters() {
+        let input = include_str!("./resources/ical_availability.ics");
+        let calendar = ical::IcalParser::new(input.as_bytes())
+            .next()
+            .unwrap()
+            .unwrap();
+        similar_asserts::assert_eq!(calendar.generate(), input);
+
+        let [office, vacation, offsite] = &calendar.availabilities[..] else {
+            panic!("expected three availabilities");
+        };
+        assert_eq!(office.get_uid(), "office");
+        assert_eq!(office.get_busytype(), BusyType::BusyUnavailable);
+        assert_eq!(office.get_priority(), None);
+        assert_eq!(office.available.len(), 2);
+        assert_eq!(office.available[1].get_uid(), "office-hours");
+        assert!(office.available[1].get_recurrence_id().is_some());
+        assert_eq!(office.get_tzids(), ["Europe/Berlin"]);
+        assert_eq!(vacation.get_busytype(), BusyType::Busy);
+        assert_eq!(vacation.get_priority(), Some(1));
+        assert_eq!(vacation.get_duration(), Some(chrono::Duration::days(5)));
+        assert_eq!(offsite.get_busytype(), BusyType::BusyTentative);
+        assert_eq!(
+            offsite.available[0].get_duration(),
+            Some(chrono::Duration::hours(2))
+        );
+    }
+
+    #[rstest::rstest]
+    // Weekly office hours
+    #[case(utc(1, 7, 9, 0), Availability::Available)]
+    #[case(utc(1, 7, 16, 0), Availability::Busy(BusyType::BusyUnavailable))]
+    #[case(utc(1, 11, 10, 0), Availability::Busy(BusyType::BusyUnavailable))]
+    // Shortened by an override
+    #[case(utc(1, 10, 10, 0), Availability::Available)]
+    #[case(utc(1, 10, 11, 0), Availability::Busy(BusyType::BusyUnavailable))]
+    // The vacation has the highest priority
+    #[case(utc(1, 14, 9, 0), Availability::Busy(BusyType::Busy))]
+    #[case(utc(1, 17, 22, 59), Availability::Busy(BusyType::Busy))]
+    #[case(utc(1, 17, 23, 0), Availability::Busy(BusyType::BusyUnavailable))]
+    // The offsite overrides the office hours
+    #[case(utc(1, 20, 9, 0), Availability::Busy(BusyType::BusyTentative))]
+    #[case(utc(1, 20, 12, 30), Availability::Available)]
+    #[case(utc(1, 22, 9, 0), Availability::Available)]
+    #[case(Utc.with_ymd_and_hms(2024, 12, 31, 12, 0, 0).unwrap(), Availability::Unknown)]
+    fn evaluate(#[case] time: DateTime<Utc>, #[case] expected: Availability) {
+        let input = include_str!("./resources/ical_availability.ics");
+        let calendar = ical::IcalParser::new(input.as_bytes())
+            .next()
+            .unwrap()
+            .unwrap();
+
+        let evaluator = AvailabilityEvaluator::from_calendar(&calendar, &OlsonResolver);
+        assert_eq!(
+            evaluator.is_available(time).unwrap(),
+            expected == Availability::Available
+        );
+        assert_eq!(evaluator.at(time).unwrap(), expected);
+    }
+
+    #[rstest::rstest]
+    #[case(
+        "UID:1\r\nDTSTART:20250101T000000Z\r\nDTEND:20250102T000000Z\r\nDURATION:P1D\r\n",
+        "",
+        ParserError::PropertyConflict("both DTEND and DURATION are defined")
+    )]
+    #[case(
+        "UID:1\r\nDURATION:P1D\r\n",
+        "",
+        ParserError::MissingProperty("DTSTART")
+    )]
+    #[case("", "", ParserError::MissingProperty("UID"))]
+    #[case(
+        "UID:1\r\n",
+        "BEGIN:AVAILABLE\r\nUID:2\r\nEND:AVAILABLE\r\n",
+        ParserError::MissingProperty("DTSTART")
+    )]
+    fn invalid(#[case] properties: &str, #[case] available: &str, #[case] error: ParserError) {
+        let input = format!(
+            "BEGIN:VCALENDAR\r\nBEGIN:VAVAILABILITY\r\n{properties}{available}END:VAVAILABILITY\r\nEND:VCALENDAR\r\n"
+        );
+        let result = ical::IcalParser::new(input.as_bytes()).next().unwrap();
+        assert_eq!(result.unwrap_err(), error);
+    }
+
+    #[test]
+    fn duration_out_of_range() {
+        let input = "BEGIN:VCALENDAR\r\nBEGIN:VAVAILABILITY\r\nUID:1\r\n\
+            DTSTART:20250101T000000Z\r\nDURATION:P99999999W\r\nEND:VAVAILABILITY\r\nEND:VCALENDAR\r\n";
+        let calendar = ical::IcalParser::new(input.as_bytes())
+            .next()
+            .unwrap()
+            .unwrap();
+        let evaluator = AvailabilityEvaluator::from_calendar(&calendar, &OlsonResolver);
+        assert_eq!(
+            evaluator.at(utc(1, 7, 9, 0)).unwrap_err(),
+            ParserError::InvalidValue("DURATION")
+        );
+    }
+
+    #[test]
+    fn out_of_range_priority() {
+        let input = "BEGIN:VCALENDAR\r\nBEGIN:VAVAILABILITY\r\nUID:1\r\nDTSTAMP:20250101T000000Z\r\nPRIORITY:10\r\nEND:VAVAILABILITY\r\nEND:VCALENDAR\r\n";
+        let result = ical::IcalParser::new(input.as_bytes())
+            .with_verify_options(VerifyOptions::strict())
+            .next()
+            .unwrap();
+        assert_eq!(result.unwrap_err(), ParserError::InvalidValue("PRIORITY"));
+
+        let calendar = ical::IcalParser::new(input.as_bytes())
+            .next()
+            .unwrap()
+            .unwrap();
+        assert_eq!(calendar.availabilities[0].get_priority(), None);
+    }
+}
+
+pub mod publishing {
+    extern crate ical;
+
+    use ical::generator::Emitter;
+    use ical::parser::ical::component::{
+        ParticipantType, PropertyContent, StructuredData, StyledDescription,
+    };
+    use ical::parser::{ParserError, VerifyOptions};
+
+    #[test]
+    fn getters() {
+        let input = include_str!("./resources/ical_publishing.ics");
+        let calendar = ical::IcalParser::new(input.as_bytes())
+            .next()
+            .unwrap()
+            .unwrap();
+        let event = &calendar.events[0];
+        similar_asserts::assert_eq!(
+            event.participants[0].generate(),
+            "BEGIN:PARTICIPANT\r
+UID:conductor\r
+PARTICIPANT-TYPE:PERFORMER\r
+CALENDAR-ADDRESS:mailto:conductor@example.com\r
+SUMMARY:Conductor\r
+STYLED-DESCRIPTION;VALUE=URI;DERIVED=TRUE:https://example.com/conductor\r
+BEGIN:VLOCATION\r
+UID:backstage\r
+NAME:Backstage\r
+END:VLOCATION\r
+END:PARTICIPANT\r
+"
+        );
+        assert_eq!(
+            event.get_styled_descriptions(),
+            [StyledDescription {
+                fmttype: Some("text/html"),
+                derived: false,
+                value: PropertyContent::Text("<p>The orchestra plays <b>Vivaldi</b>.</p>"),
+            }]
+        );
+        assert_eq!(
+            event.get_structured_data(),
+            [
+                StructuredData {
+                    fmttype: Some("application/ld+json"),
+                    schema: Some("https://schema.org/MusicEvent"),
+                    value: PropertyContent::Text("{\"@type\":\"MusicEvent\"}"),
+                },
+                StructuredData {
+                    fmttype: None,
+                    schema: None,
+                    value: PropertyContent::Uri("https://example.com/concert.json"),
+                },
+            ]
+        );
+
+        let [hall, parking] = &event.locations[..] else {
+            panic!("expected two locations");
+        };
+        assert_eq!(hall.get_uid(), "hall");
+        assert_eq!(hall.get_name(), Some("Concert hall"));
+        assert_eq!(hall.get_location_types(), ["auditorium", "arts"]);
+        assert_eq!(hall.get_url(), Some("https://example.com/hall"));
+        assert_eq!(parking.get_location_types(), ["parking"]);
+        assert_eq!(parking.get_description(), None);
+
+        assert_eq!(event.resources[0].get_name(), Some("Grand piano"));
+        assert_eq!(event.resources[0].get_resource_type(), Some("instrument"));
+
+        let conductor = &event.participants[0];
+        assert_eq!(conductor.get_participant_type(), ParticipantType::Performer);
+        assert_eq!(
+            conductor.get_calendar_address(),
+            Some("mailto:conductor@example.com")
+        );
+        assert_eq!(conductor.get_summary(), Some("Conductor"));
+        assert_eq!(
+            conductor.get_styled_descriptions(),
+            [StyledDescription {
+                fmttype: None,
+                derived: true,
+                value: PropertyContent::Uri("https://example.com/conductor"),
+            }]
+        );
+        assert_eq!(conductor.locations[0].get_name(), Some("Backstage"));
+
+        let todo = &calendar.todos[0];
+        assert_eq!(
+            todo.get_structured_data()[0].value,
+            PropertyContent::Binary("dGlja2V0cw==")
+        );
+        assert_eq!(
+            todo.participants[0].get_participant_type(),
+            ParticipantType::BookingContact
+        );
+    }
+
+    #[rstest::rstest]
+    #[case(
+        "BEGIN:PARTICIPANT\r\nUID:1\r\nEND:PARTICIPANT\r\n",
+        ParserError::MissingProperty("PARTICIPANT-TYPE")
+    )]
+    #[case(
+        "BEGIN:VLOCATION\r\nNAME:Hall\r\nEND:VLOCATION\r\n",
+        ParserError::MissingProperty("UID")
+    )]
+    #[case(
+        "BEGIN:VRESOURCE\r\nNAME:Piano\r\nEND:VRESOURCE\r\n",
+        ParserError::MissingProperty("UID")
+    )]
+    #[case(
+        "STRUCTURED-DATA;FMTTYPE=application/ld+json:{}\r\n",
+        ParserError::InvalidValue("STRUCTURED-DATA")
+    )]
+    #[case(
+        "STRUCTURED-DATA;VALUE=BINARY;FMTTYPE=text/plain;SCHEMA=x:eA==\r\n",
+        ParserError::InvalidValue("STRUCTURED-DATA")
+    )]
+    #[case(
+        "STYLED-DESCRIPTION;VALUE=BINARY;ENCODING=BASE64:eA==\r\n",
+        ParserError::InvalidValue("STYLED-DESCRIPTION")
+    )]
+    #[case(
+        "BEGIN:PARTICIPANT\r\nUID:1\r\nPARTICIPANT-TYPE:SPEAKER\r\nBEGIN:VALARM\r\nEND:VALARM\r\nEND:PARTICIPANT\r\n",
+        ParserError::InvalidComponent
+    )]
+    fn invalid(#[case] content: &str, #[case] error: ParserError) {
+        let input = format!(
+            "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nUID:1\r\nDTSTAMP:20250101T000000Z\r\nDTSTART:20250101T000000Z\r\n{content}END:VEVENT\r\nEND:VCALENDAR\r\n"
+        );
+        let result = ical::IcalParser::new(input.as_bytes())
+            .with_verify_options(VerifyOptions::strict())
+            .next()
+            .unwrap();
+        assert_eq!(result.unwrap_err(), error);
+    }
+
+    #[test]
+    fn lenient() {
+        // Malformed values are skipped instead of rejecting the event
+        let input = "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nUID:1\r\nDTSTART:20250101T000000Z\r\n\
+            STRUCTURED-DATA;FMTTYPE=application/ld+json:{}\r\n\
+            STYLED-DESCRIPTION;VALUE=BINARY;ENCODING=BASE64:eA==\r\n\
+            STYLED-DESCRIPTION;FMTTYPE=text/html:<p>Hi</p>\r\n\
+            BEGIN:VLOCATION\r\nUID:2\r\nSTRUCTURED-DATA;FMTTYPE=application/ld+json:{}\r\nEND:VLOCATION\r\n\
+            END:VEVENT\r\nEND:VCALENDAR\r\n";
+        let calendar = ical::IcalParser::new(input.as_bytes())
+            .next()
+            .unwrap()
+            .unwrap();
+        let event = &calendar.events[0];
+        assert_eq!(event.get_structured_data(), []);
+        assert_eq!(
+            event.get_styled_descriptions(),
+            [StyledDescription {
+                fmttype: Some("text/html"),
+                derived: false,
+                value: PropertyContent::Text("<p>Hi</p>"),
+            }]
+        );
+        assert_eq!(event.locations[0].get_structured_data(), []);
+    }
+}
+
+pub mod metadata {
+    extern crate ical;
+
+    use chrono::{Duration, TimeZone, Utc};
+    use ical::parser::ical::component::{Conference, Image, PropertyContent};
+    use ical::parser::{Component, ComponentMut, ParserError, VerifyOptions};
+
+    #[test]
+    fn getters() {
+        let input = include_str!("./resources/ical_metadata.ics");
+        let calendar = ical::IcalParser::new(input.as_bytes())
+            .next()
+            .unwrap()
+            .unwrap();
+        assert_eq!(calendar.get_name(), Some("Team calendar"));
+        assert_eq!(calendar.get_description(), Some("Meetings of the team"));
+        assert_eq!(
+            calendar.get_uid(),
+            Some("5FC53010-1267-4F8E-BC28-1D7AE55A7C99")
+        );
+        assert_eq!(calendar.get_url(), Some("https://example.com/team"));
+        assert_eq!(
+            calendar.get_last_modified(),
+            Some(Utc.with_ymd_and_hms(2025, 1, 2, 3, 4, 5).unwrap())
+        );
+        assert_eq!(calendar.get_refresh_interval(), Some(Duration::weeks(1)));
+        assert_eq!(calendar.get_source(), Some("https://example.com/team.ics"));
+        assert_eq!(calendar.get_color(), Some("turquoise"));
+        assert_eq!(
+            calendar.get_images(),
+            [Image {
+                fmttype: Some("image/png"),
+                display: vec!["BADGE", "THUMBNAIL"],
+                altrep: None,
+                value: PropertyContent::Uri("https://example.com/team.png"),
+            }]
+        );
+
+        let event = &calendar.events[0];
+        assert_eq!(event.get_color(), Some("red"));
+        assert_eq!(
+            event.get_images()[0].value,
+            PropertyContent::Binary("iVBORw0KGgo=")
+        );
+        assert_eq!(
+            event.get_conferences(),
+            [
+                Conference {
+                    uri: "https://example.com/standup",
+                    features: vec!["AUDIO", "VIDEO"],
+                    label: Some("Video call"),
+                },
+                Conference {
+                    uri: "tel:+1-412-555-0123,,,654321",
+                    features: vec!["PHONE"],
+                    label: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn fallbacks() {
+        let input = "BEGIN:VCALENDAR\r
+X-WR-CALNAME:Holidays\r
+X-WR-CALDESC:Public holidays\r
+X-APPLE-CALENDAR-COLOR:#FF2968\r
+END:VCALENDAR\r
+";
+        let calendar = ical::IcalParser::new(input.as_bytes())
+            .next()
+            .unwrap()
+            .unwrap();
+        assert_eq!(calendar.get_name(), Some("Holidays"));
+        assert_eq!(calendar.get_description(), Some("Public holidays"));
+        assert_eq!(calendar.get_color(), Some("#FF2968"));
+
+        // Setters keep the legacy properties in sync
+        let mut calendar = calendar.mutable();
+        calendar.set_name("Vacation");
+        calendar.set_color("#00FF00");
+        calendar.set_refresh_interval(Duration::hours(12));
+        let calendar = calendar.verify().unwrap();
+        assert_eq!(calendar.get_name(), Some("Vacation"));
+        assert_eq!(
+            calendar
+                .get_property("X-WR-CALNAME")
+                .unwrap()
+                .value
+                .as_deref(),
+            Some("Vacation")
+        );
+        assert_eq!(
+            calendar
+                .get_property("X-APPLE-CALENDAR-COLOR")
+                .unwrap()
+                .value
+                .as_deref(),
+            Some("#00FF00")
+        );
+        assert_eq!(
+            calendar
+                .get_property("REFRESH-INTERVAL")
+                .unwrap()
+                .value
+                .as_deref(),
+            Some("PT12H")
+        );
+        assert_eq!(calendar.get_description(), Some("Public holidays"));
+    }
+
+    #[rstest::rstest]
+    #[case("LAST-MODIFIED:yesterday\r\n", ParserError::InvalidDateTime(ical::types::CalDateTimeError::InvalidDatetimeFormat("yesterday".to_owned())))]
+    #[case(
+        "REFRESH-INTERVAL;VALUE=DURATION:daily\r\n",
+        ParserError::InvalidValue("REFRESH-INTERVAL")
+    )]
+    #[case(
+        "IMAGE;VALUE=BINARY:iVBORw0KGgo=\r\n",
+        ParserError::InvalidValue("IMAGE")
+    )]
+    fn invalid(#[case] content: &str, #[case] error: ParserError) {
+        let input = format!("BEGIN:VCALENDAR\r\n{content}END:VCALENDAR\r\n");
+        let result = ical::IcalParser::new(input.as_bytes())
+            .with_verify_options(VerifyOptions::strict())
+            .next()
+            .unwrap();
+        assert_eq!(result.unwrap_err(), error);
+
+        // Lenient parsing keeps the calendar and skips the malformed value
+        let calendar = ical::IcalParser::new(input.as_bytes())
+            .next()
+            .unwrap()
+            .unwrap();
+        assert_eq!(calendar.get_last_modified(), None);
+        assert_eq!(calendar.get_refresh_interval(), None);
+        assert_eq!(calendar.get_images(), []);
+    }
+
+    #[test]
+    fn invalid_conference() {
+        let input = "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nUID:1\r\nDTSTAMP:20250101T000000Z\r\n\
+            DTSTART:20250101T000000Z\r\nCONFERENCE;VALUE=TEXT:Room 1\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n";
+        let result = ical::IcalParser::new(input.as_bytes())
+            .with_verify_options(VerifyOptions::strict())
+            .next()
+            .unwrap();
+        assert_eq!(result.unwrap_err(), ParserError::InvalidValue("CONFERENCE"));
+
+        let calendar = ical::IcalParser::new(input.as_bytes())
+            .next()
+            .unwrap()
+            .unwrap();
+        assert_eq!(calendar.events[0].get_conferences(), []);
+    }
+}
+
+pub mod wr_timezone {
+    extern crate ical;
+
+    use chrono::{TimeZone, Utc};
+    use ical::generator::Emitter;
+    use ical::parser::ical::component::IcalTimeZone;
+    use ical::parser::ical::recurrence::{expand, resolve_timezones};
+    use ical::parser::ical::validator;
+    use ical::parser::{Component, ParserError};
+    use ical::types::OlsonResolver;
+
+    #[test]
+    fn apply() {
+        let input = include_str!("./resources/google_export.ics");
+        let calendar = ical::IcalParser::new(input.as_bytes())
+            .next()
+            .unwrap()
+            .unwrap()
+            .apply_wr_timezone(&OlsonResolver)
+            .unwrap();
+        let output = calendar.generate();
+        assert_eq!(validator::validate_input(&output), vec![]);
+
+        let [weekly, call, holiday] = &calendar.events[..] else {
+            panic!("expected three events");
+        };
+        similar_asserts::assert_eq!(
+            weekly.generate(),
+            "BEGIN:VEVENT\r
+UID:weekly@google.com\r
+DTSTAMP:20250101T000000Z\r
+DTSTART;TZID=Europe/Berlin:20250106T090000\r
+DTEND;TZID=Europe/Berlin:20250106T093000\r
+RRULE:FREQ=WEEKLY;UNTIL=20250331T070000Z;BYDAY=MO\r
+EXDATE;TZID=Europe/Berlin:20250113T090000,20250120T090000\r
+SUMMARY:Weekly sync\r
+END:VEVENT\r
+"
+        );
+        assert_eq!(call.get_dtstart().unwrap().get_tzid(), None);
+        assert_eq!(holiday.get_dtstart().unwrap().get_tzid(), None);
+        assert_eq!(
+            calendar.todos[0].get_property("DUE").unwrap().get_tzid(),
+            Some("Europe/Berlin")
+        );
+        similar_asserts::assert_eq!(
+            calendar.timezones[0].generate(),
+            "BEGIN:VTIMEZONE\r
+TZID:Europe/Berlin\r
+BEGIN:DAYLIGHT\r
+TZOFFSETFROM:+0100\r
+TZOFFSETTO:+0200\r
+TZNAME:CEST\r
+DTSTART:20240331T020000\r
+RRULE:FREQ=YEARLY;BYMONTH=3;BYDAY=-1SU\r
+END:DAYLIGHT\r
+BEGIN:STANDARD\r
+TZOFFSETFROM:+0200\r
+TZOFFSETTO:+0100\r
+TZNAME:CET\r
+DTSTART:20241027T030000\r
+RRULE:FREQ=YEARLY;BYMONTH=10;BYDAY=-1SU\r
+END:STANDARD\r
+END:VTIMEZONE\r
+"
+        );
+        // The observances cover the first rewritten date-time
+        let observance_start = calendar.timezones[0]
+            .transitions
+            .iter()
+            .filter_map(|transition| transition.get_property("DTSTART"))
+            .filter_map(|prop| prop.value.as_deref())
+            .min()
+            .unwrap();
+        assert!(observance_start <= "20250106T090000");
+
+        let timezones = resolve_timezones(&weekly.properties, &calendar.timezones, &OlsonResolver);
+        let starts: Vec<_> = expand(
+            [weekly],
+            &timezones,
+            Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2025, 12, 31, 0, 0, 0).unwrap(),
+        )
+        .unwrap()
+        .into_iter()
+        .map(|instance| instance.start.unwrap().utc())
+        .collect();
+        assert_eq!(starts.len(), 11);
+        assert_eq!(
+            starts[0],
+            Utc.with_ymd_and_hms(2025, 1, 6, 8, 0, 0).unwrap()
+        );
+        assert_eq!(
+            starts[1],
+            Utc.with_ymd_and_hms(2025, 1, 27, 8, 0, 0).unwrap()
+        );
+        // The last instance is in summer time
+        assert_eq!(
+            starts[10],
+            Utc.with_ymd_and_hms(2025, 3, 31, 7, 0, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn existing_timezone() {
+        let input = "BEGIN:VCALENDAR\r
+X-WR-TIMEZONE:Berlin\r
+BEGIN:VTIMEZONE\r
+TZID:Berlin\r
+X-LIC-LOCATION:Europe/Berlin\r
+END:VTIMEZONE\r
+BEGIN:VEVENT\r
+UID:1\r
+DTSTART:20250106T090000\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+        let calendar = ical::IcalParser::new(input.as_bytes())
+            .next()
+            .unwrap()
+            .unwrap()
+            .apply_wr_timezone(&OlsonResolver)
+            .unwrap();
+        assert_eq!(calendar.timezones.len(), 1);
+        assert_eq!(
+            calendar.events[0].get_dtstart().unwrap().get_tzid(),
+            Some("Berlin")
+        );
+    }
+
+    #[test]
+    fn without_wr_timezone() {
+        let input = include_str!("./resources/ical_events.ics");
+        let calendar = ical::IcalParser::new(input.as_bytes())
+            .next()
+            .unwrap()
+            .unwrap();
+        let output = calendar.generate();
+        assert_eq!(
+            calendar
+                .apply_wr_timezone(&OlsonResolver)
+                .unwrap()
+                .generate(),
+            output
+        );
+    }
+
+    #[test]
+    fn unknown_timezone() {
+        let input = "BEGIN:VCALENDAR\r\nX-WR-TIMEZONE:Mars/Olympus\r\nEND:VCALENDAR\r\n";
+        let calendar = ical::IcalParser::new(input.as_bytes())
+            .next()
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            calendar.apply_wr_timezone(&OlsonResolver).unwrap_err(),
+            ParserError::InvalidValue("X-WR-TIMEZONE")
+        );
+    }
+
+    #[test]
+    fn fixed_offset_timezone() {
+        let timezone = IcalTimeZone::from_tz(chrono_tz::Asia::Tokyo, 2024..=2025);
+        similar_asserts::assert_eq!(
+            timezone.generate(),
+            "BEGIN:VTIMEZONE\r
+TZID:Asia/Tokyo\r
+BEGIN:STANDARD\r
+TZOFFSETFROM:+0900\r
+TZOFFSETTO:+0900\r
+TZNAME:JST\r
+DTSTART:20240101T090000\r
+END:STANDARD\r
+END:VTIMEZONE\r
+"
+        );
+    }
+
+    #[test]
+    fn range_from_data() {
+        // The observances only depend on the date-times of the calendar, not on the current year
+        let input = "BEGIN:VCALENDAR\r
+X-WR-TIMEZONE:Europe/Moscow\r
+BEGIN:VEVENT\r
+UID:1\r
+DTSTART:20100601T090000\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+        let calendar = ical::IcalParser::new(input.as_bytes())
+            .next()
+            .unwrap()
+            .unwrap()
+            .apply_wr_timezone(&OlsonResolver)
+            .unwrap();
+        let starts: Vec<_> = calendar.timezones[0]
+            .transitions
+            .iter()
+            .filter_map(|transition| transition.get_property("DTSTART"))
+            .filter_map(|prop| prop.value.as_deref())
+            .collect();
+        assert_eq!(starts, ["20090329T020000", "20091025T030000"]);
+    }
+
+    #[test]
+    fn changed_rules() {
+        // Moscow dropped daylight saving time in 2011 and changed its offset again in 2014
+        let input = "BEGIN:VCALENDAR\r
+PRODID:-//ical-rs//EN\r
+VERSION:2.0\r
+X-WR-TIMEZONE:Europe/Moscow\r
+BEGIN:VEVENT\r
+UID:1\r
+DTSTAMP:20100101T000000Z\r
+DTSTART:20100601T090000\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:2\r
+DTSTAMP:20150101T000000Z\r
+DTSTART:20150601T090000\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+        let calendar = ical::IcalParser::new(input.as_bytes())
+            .next()
+            .unwrap()
+            .unwrap()
+            .apply_wr_timezone(&OlsonResolver)
+            .unwrap();
+        assert_eq!(validator::validate_input(&calendar.generate()), vec![]);
+        similar_asserts::assert_eq!(
+            calendar.timezones[0].generate(),
+            "BEGIN:VTIMEZONE\r
+TZID:Europe/Moscow\r
+BEGIN:DAYLIGHT\r
+TZOFFSETFROM:+0300\r
+TZOFFSETTO:+0400\r
+TZNAME:MSD\r
+DTSTART:20090329T020000\r
+RRULE:FREQ=YEARLY;BYMONTH=3;BYDAY=-1SU;UNTIL=20100327T230000Z\r
+END:DAYLIGHT\r
+BEGIN:STANDARD\r
+TZOFFSETFROM:+0400\r
+TZOFFSETTO:+0300\r
+TZNAME:MSK\r
+DTSTART:20091025T030000\r
+RRULE:FREQ=YEARLY;BYMONTH=10;BYDAY=-1SU;UNTIL=20101030T230000Z\r
+END:STANDARD\r
+BEGIN:STANDARD\r
+TZOFFSETFROM:+0300\r
+TZOFFSETTO:+0400\r
+TZNAME:MSK\r
+DTSTART:20110327T020000\r
+END:STANDARD\r
+BEGIN:STANDARD\r
+TZOFFSETFROM:+0400\r
+TZOFFSETTO:+0300\r
+TZNAME:MSK\r
+DTSTART:20141026T020000\r
+END:STANDARD\r
+END:VTIMEZONE\r
+"
+        );
+
+        // The rules of the United States changed in 2007
+        let timezone = IcalTimeZone::from_tz(chrono_tz::America::New_York, 2005..=2008);
+        let rules: Vec<_> = timezone
+            .transitions
+            .iter()
+            .map(|transition| {
+                ["DTSTART", "RRULE"].map(|name| {
+                    transition
+                        .get_property(name)
+                        .and_then(|prop| prop.value.clone())
+                })
+            })
+            .collect();
+        assert_eq!(
+            rules,
+            [
+                [
+                    "20050403T020000",
+                    "FREQ=YEARLY;BYMONTH=4;BYDAY=1SU;UNTIL=20060402T070000Z"
+                ],
+                [
+                    "20051030T020000",
+                    "FREQ=YEARLY;BYMONTH=10;BYDAY=-1SU;UNTIL=20061029T060000Z"
+                ],
+                ["20070311T020000", "FREQ=YEARLY;BYMONTH=3;BYDAY=2SU"],
+                ["20071104T020000", "FREQ=YEARLY;BYMONTH=11;BYDAY=1SU"],
+            ]
+            .map(|rule| rule.map(|value| Some(value.to_owned())))
+        );
+    }
+}
+
+pub mod itip {
+    extern crate ical;
+
+    use ical::generator::Emitter;
+    use ical::parser::ical::component::{CalendarInnerData, IcalCalendarObject};
+    use ical::parser::ical::itip;
+    use ical::parser::{Component, ParserError, VerifyOptions};
+    use ical::property::Property;
+    use ical::types::OlsonResolver;
+
+    fn organizer_object() -> IcalCalendarObject {
+        let input = include_str!("./resources/ical_itip.ics");
+        ical::IcalObjectParser::new(input.as_bytes())
+            .next()
+            .unwrap()
+            .unwrap()
+    }
+
+    /// The generated message without its DTSTAMP, which is the time it was created.
+    fn generate(message: &IcalCalendarObject) -> String {
+        message
+            .generate()
+            .split_inclusive("\r\n")
+            .filter(|line| !line.starts_with("DTSTAMP:"))
+            .collect()
+    }
+
+    /// Read a generated message back like a received one.
+    fn parse(message: &IcalCalendarObject) -> IcalCalendarObject {
+        let input = message.generate();
+        ical::IcalObjectParser::new(input.as_bytes())
+            .with_verify_options(VerifyOptions::itip())
+            .next()
+            .unwrap()
+            .unwrap()
+    }
+
+    fn recurrence_id(value: &str) -> Property {
+        ical::ical_property!(
+            "RECURRENCE-ID",
+            value,
+            ical::ical_param!("TZID", "Europe/Berlin")
+        )
+    }
+
+    #[test]
+    fn request() {
+        let object = organizer_object();
+        let message = itip::request(&object).unwrap();
+        assert_eq!(
+            message.get_property("METHOD").unwrap().value.as_deref(),
+            Some("REQUEST")
+        );
+        let CalendarInnerData::Event(main, overrides) = message.get_inner() else {
+            panic!("expected an event");
+        };
+        assert_eq!(overrides.len(), 1);
+        assert_eq!(main.get_named_properties("ATTENDEE").len(), 2);
+        assert_eq!(
+            main.get_property("SEQUENCE").unwrap().value.as_deref(),
+            Some("1")
+        );
+        assert_ne!(
+            main.get_property("DTSTAMP").unwrap().value.as_deref(),
+            Some("20250101T000000Z")
+        );
+    }
+
+    #[test]
+    fn request_without_attendees() {
+        let input = "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nUID:1\r\nDTSTART:20250106T100000Z\r\n\
+            ORGANIZER:mailto:alice@example.com\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n";
+        let object = ical::IcalObjectParser::new(input.as_bytes())
+            .next()
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            itip::request(&object).unwrap_err(),
+            ParserError::MissingProperty("ATTENDEE")
+        );
+        assert_eq!(
+            itip::refresh(&object, "mailto:bob@example.com")
+                .unwrap()
+                .get_property("METHOD")
+                .unwrap()
+                .value
+                .as_deref(),
+            Some("REFRESH")
+        );
+    }
+
+    #[test]
+    fn cancel_series() {
+        let mut object = organizer_object();
+        let message = itip::cancel(&mut object, None, &OlsonResolver).unwrap();
+        similar_asserts::assert_eq!(
+            generate(&message),
+            "BEGIN:VCALENDAR\r
+PRODID:-//ical-rs//EN\r
+VERSION:2.0\r
+METHOD:CANCEL\r
+BEGIN:VEVENT\r
+UID:planning@example.com\r
+DTSTART;TZID=Europe/Berlin:20250106T100000\r
+DTEND;TZID=Europe/Berlin:20250106T110000\r
+ORGANIZER;CN=Alice:mailto:alice@example.com\r
+ATTENDEE;CN=Bob;PARTSTAT=ACCEPTED:mailto:bob@example.com\r
+ATTENDEE;CN=Carol;PARTSTAT=NEEDS-ACTION:mailto:carol@example.com\r
+SUMMARY:Planning\r
+STATUS:CANCELLED\r
+SEQUENCE:2\r
+END:VEVENT\r
+END:VCALENDAR\r
+"
+        );
+
+        let CalendarInnerData::Event(main, overrides) = object.get_inner() else {
+            panic!("expected an event");
+        };
+        for event in std::iter::once(main).chain(overrides) {
+            assert_eq!(
+                event.get_property("STATUS").unwrap().value.as_deref(),
+                Some("CANCELLED")
+            );
+            assert_eq!(
+                event.get_property("SEQUENCE").unwrap().value.as_deref(),
+                Some("2")
+            );
+        }
+    }
+
+    #[test]
+    fn cancel_instance() {
+        let mut object = organizer_object();
+        let message = itip::cancel(
+            &mut object,
+            Some(&recurrence_id("20250120T100000")),
+            &OlsonResolver,
+        )
+        .unwrap();
+        similar_asserts::assert_eq!(
+            generate(&message),
+            "BEGIN:VCALENDAR\r
+PRODID:-//ical-rs//EN\r
+VERSION:2.0\r
+METHOD:CANCEL\r
+BEGIN:VEVENT\r
+UID:planning@example.com\r
+ORGANIZER;CN=Alice:mailto:alice@example.com\r
+ATTENDEE;CN=Bob;PARTSTAT=ACCEPTED:mailto:bob@example.com\r
+ATTENDEE;CN=Carol;PARTSTAT=NEEDS-ACTION:mailto:carol@example.com\r
+SUMMARY:Planning\r
+SEQUENCE:2\r
+RECURRENCE-ID;TZID=Europe/Berlin:20250120T100000\r
+DTSTART;TZID=Europe/Berlin:20250120T100000\r
+STATUS:CANCELLED\r
+END:VEVENT\r
+END:VCALENDAR\r
+"
+        );
+
+        let CalendarInnerData::Event(main, _) = object.get_inner() else {
+            panic!("expected an event");
+        };
+        assert_eq!(
+            main.get_property("EXDATE"),
+            Some(&ical::ical_property!(
+                "EXDATE",
+                "20250120T100000",
+                ical::ical_param!("TZID", "Europe/Berlin")
+            ))
+        );
+        assert_eq!(main.get_property("STATUS"), None);
+    }
+
+    #[test]
+    fn cancel_override() {
+        let mut object = organizer_object();
+        let message = itip::cancel(
+            &mut object,
+            Some(&recurrence_id("20250113T100000")),
+            &OlsonResolver,
+        )
+        .unwrap();
+        let CalendarInnerData::Event(cancelled, _) = message.get_inner() else {
+            panic!("expected an event");
+        };
+        // Only the attendees of the override are notified
+        assert_eq!(cancelled.get_named_properties("ATTENDEE").len(), 1);
+        assert_eq!(
+            cancelled.get_dtstart().unwrap().value.as_deref(),
+            Some("20250113T140000")
+        );
+
+        let CalendarInnerData::Event(main, overrides) = object.get_inner() else {
+            panic!("expected an event");
+        };
+        assert_eq!(main.get_property("EXDATE"), None);
+        assert_eq!(
+            overrides[0]
+                .get_property("STATUS")
+                .unwrap()
+                .value
+                .as_deref(),
+            Some("CANCELLED")
+        );
+    }
+
+    #[test]
+    fn cancel_by_time() {
+        // A UTC RECURRENCE-ID finds the override stored with a TZID
+        let mut object = organizer_object();
+        itip::cancel(
+            &mut object,
+            Some(&ical::ical_property!("RECURRENCE-ID", "20250113T090000Z")),
+            &OlsonResolver,
+        )
+        .unwrap();
+        let CalendarInnerData::Event(main, overrides) = object.get_inner() else {
+            panic!("expected an event");
+        };
+        assert_eq!(main.get_property("EXDATE"), None);
+        assert_eq!(
+            overrides[0]
+                .get_property("STATUS")
+                .unwrap()
+                .value
+                .as_deref(),
+            Some("CANCELLED")
+        );
+
+        // Instances that aren't part of the series leave the object unchanged
+        let mut object = organizer_object();
+        let before = generate(&object);
+        for value in ["20250120T110000", "20250203T100000"] {
+            assert_eq!(
+                itip::cancel(&mut object, Some(&recurrence_id(value)), &OlsonResolver).unwrap_err(),
+                ParserError::InvalidValue("RECURRENCE-ID")
+            );
+        }
+        assert_eq!(generate(&object), before);
+    }
+
+    #[test]
+    fn add() {
+        let mut object = organizer_object();
+        let start = ical::ical_property!(
+            "DTSTART",
+            "20250201T090000",
+            ical::ical_param!("TZID", "Europe/Berlin")
+        );
+        let message = itip::add(&mut object, &start, &OlsonResolver).unwrap();
+        let CalendarInnerData::Event(added, overrides) = message.get_inner() else {
+            panic!("expected an event");
+        };
+        assert!(overrides.is_empty());
+        assert_eq!(added.get_dtstart(), Some(&start));
+        assert_eq!(
+            added.get_dtend().unwrap().value.as_deref(),
+            Some("20250201T100000")
+        );
+        assert_eq!(added.get_rrule(), None);
+        assert_eq!(
+            added.get_property("SEQUENCE").unwrap().value.as_deref(),
+            Some("2")
+        );
+
+        let CalendarInnerData::Event(main, _) = object.get_inner() else {
+            panic!("expected an event");
+        };
+        assert_eq!(
+            main.get_property("RDATE").unwrap().value.as_deref(),
+            Some("20250201T090000")
+        );
+        assert_eq!(
+            main.get_property("SEQUENCE").unwrap().value.as_deref(),
+            Some("2")
+        );
+    }
+
+    #[test]
+    fn sequence_overflow() {
+        // The SEQUENCE stays within the range of INTEGER values
+        for value in ["4294967295", "2147483647"] {
+            let input = include_str!("./resources/ical_itip.ics")
+                .replace("SEQUENCE:1\r\n", &format!("SEQUENCE:{value}\r\n"));
+            let mut object = ical::IcalObjectParser::new(input.as_bytes())
+                .next()
+                .unwrap()
+                .unwrap();
+            let message = itip::cancel(&mut object, None, &OlsonResolver).unwrap();
+            let CalendarInnerData::Event(cancelled, _) = message.get_inner() else {
+                panic!("expected an event");
+            };
+            assert_eq!(
+                cancelled.get_property("SEQUENCE").unwrap().value.as_deref(),
+                Some("2147483647")
+            );
+        }
+    }
+
+    #[test]
+    fn refresh() {
+        let object = organizer_object();
+        let message = itip::refresh(&object, "MAILTO:Carol@example.com").unwrap();
+        similar_asserts::assert_eq!(
+            generate(&message),
+            "BEGIN:VCALENDAR\r
+PRODID:-//ical-rs//EN\r
+VERSION:2.0\r
+METHOD:REFRESH\r
+BEGIN:VEVENT\r
+UID:planning@example.com\r
+ORGANIZER;CN=Alice:mailto:alice@example.com\r
+ATTENDEE;CN=Carol;PARTSTAT=NEEDS-ACTION:mailto:carol@example.com\r
+END:VEVENT\r
+END:VCALENDAR\r
+"
+        );
+
+        assert_eq!(parse(&message).generate(), message.generate());
+    }
+
+    #[test]
+    fn counter() {
+        let object = organizer_object();
+        assert_eq!(
+            itip::counter(&object, "mailto:dave@example.com").unwrap_err(),
+            ParserError::InvalidValue("ATTENDEE")
+        );
+        let message = itip::counter(&object, "mailto:bob@example.com").unwrap();
+        assert_eq!(
+            message.get_property("METHOD").unwrap().value.as_deref(),
+            Some("COUNTER")
+        );
+    }
+
+    fn reply(components: &str) -> IcalCalendarObject {
+        let input = format!(
+            "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nMETHOD:REPLY\r\n{components}END:VCALENDAR\r\n"
+        );
+        ical::IcalObjectParser::new(input.as_bytes())
+            .with_verify_options(VerifyOptions::itip())
+            .next()
+            .unwrap()
+            .unwrap()
+    }
+
+    fn reply_component(attendee: &str, extra: &str) -> String {
+        format!(
+            "BEGIN:VEVENT\r\nUID:planning@example.com\r\nDTSTART;TZID=Europe/Berlin:20250106T100000\r\n\
+            ORGANIZER:mailto:alice@example.com\r\nATTENDEE;{attendee}\r\n{extra}END:VEVENT\r\n"
+        )
+    }
+
+    #[test]
+    fn apply_reply() {
+        let mut object = organizer_object();
+        let declined = reply(&reply_component(
+            "PARTSTAT=DECLINED:mailto:bob@example.com",
+            "DTSTAMP:20250102T000000Z\r\nSEQUENCE:1\r\n",
+        ));
+        assert_eq!(
+            itip::apply_reply(&mut object, &declined, &OlsonResolver).unwrap(),
+            vec![itip::ReplyChange {
+                attendee: "mailto:bob@example.com".to_owned(),
+                recurrence_id: None,
+                outcome: itip::ReplyOutcome::Updated {
+                    from: Some("ACCEPTED".to_owned()),
+                    to: "DECLINED".to_owned(),
+                    created_override: false,
+                },
+            }]
+        );
+        let CalendarInnerData::Event(main, overrides) = object.get_inner() else {
+            panic!("expected an event");
+        };
+        assert_eq!(
+            main.get_named_properties("ATTENDEE")[0],
+            &ical::ical_property!(
+                "ATTENDEE",
+                "mailto:bob@example.com",
+                ical::ical_param!("CN", "Bob"),
+                ical::ical_param!("PARTSTAT", "DECLINED"),
+                ical::ical_param!("X-CALENDARSERVER-DTSTAMP", "20250102T000000Z")
+            )
+        );
+        // The overrides follow the reply to the series
+        assert_eq!(
+            overrides[0].get_named_properties("ATTENDEE")[0],
+            main.get_named_properties("ATTENDEE")[0]
+        );
+
+        // The same reply again is ignored, a newer one is applied
+        let outcome = |changes: Vec<itip::ReplyChange>| changes[0].outcome.clone();
+        assert_eq!(
+            outcome(itip::apply_reply(&mut object, &declined, &OlsonResolver).unwrap()),
+            itip::ReplyOutcome::Stale
+        );
+        let instance = reply(&reply_component(
+            "PARTSTAT=TENTATIVE:mailto:bob@example.com",
+            "DTSTAMP:20250104T000000Z\r\nRECURRENCE-ID;TZID=Europe/Berlin:20250113T100000\r\nSEQUENCE:1\r\n",
+        ));
+        itip::apply_reply(&mut object, &instance, &OlsonResolver).unwrap();
+        let newer = reply(&reply_component(
+            "PARTSTAT=DECLINED:mailto:bob@example.com",
+            "DTSTAMP:20250103T000000Z\r\nSEQUENCE:1\r\n",
+        ));
+        assert_eq!(
+            outcome(itip::apply_reply(&mut object, &newer, &OlsonResolver).unwrap()),
+            itip::ReplyOutcome::Unchanged
+        );
+
+        // An override keeps a reply of its own that is newer than the one to the series
+        let CalendarInnerData::Event(_, overrides) = object.get_inner() else {
+            panic!("expected an event");
+        };
+        assert_eq!(
+            overrides[0].get_named_properties("ATTENDEE")[0].get_param("PARTSTAT"),
+            Some("TENTATIVE")
+        );
+    }
+
+    #[test]
+    fn apply_outdated_reply() {
+        let mut object = organizer_object();
+        let outdated = reply(&reply_component(
+            "PARTSTAT=TENTATIVE:mailto:carol@example.com",
+            "DTSTAMP:20250102T000000Z\r\nSEQUENCE:0\r\n",
+        ));
+        assert_eq!(
+            itip::apply_reply(&mut object, &outdated, &OlsonResolver).unwrap()[0].outcome,
+            itip::ReplyOutcome::Stale
+        );
+        let CalendarInnerData::Event(main, _) = object.get_inner() else {
+            panic!("expected an event");
+        };
+        assert_eq!(
+            main.get_named_properties("ATTENDEE")[1].get_param("PARTSTAT"),
+            Some("NEEDS-ACTION")
+        );
+    }
+
+    #[test]
+    fn apply_instance_reply() {
+        let mut object = organizer_object();
+        let replies = reply(&format!(
+            "{}{}",
+            reply_component(
+                "PARTSTAT=ACCEPTED:mailto:carol@example.com",
+                "DTSTAMP:20250102T000000Z\r\nRECURRENCE-ID;TZID=Europe/Berlin:20250127T100000\r\nSEQUENCE:1\r\n",
+            ),
+            reply_component(
+                "PARTSTAT=TENTATIVE:mailto:bob@example.com",
+                "DTSTAMP:20250102T000000Z\r\nRECURRENCE-ID;TZID=Europe/Berlin:20250113T100000\r\nSEQUENCE:1\r\n",
+            )
+        ));
+        let changes = itip::apply_reply(&mut object, &replies, &OlsonResolver).unwrap();
+        assert_eq!(
+            changes
+                .iter()
+                .map(|change| (change.recurrence_id.as_deref(), change.outcome.clone()))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    Some("20250127T100000"),
+                    itip::ReplyOutcome::Updated {
+                        from: Some("NEEDS-ACTION".to_owned()),
+                        to: "ACCEPTED".to_owned(),
+                        created_override: true,
+                    }
+                ),
+                (
+                    Some("20250113T100000"),
+                    itip::ReplyOutcome::Updated {
+                        from: Some("ACCEPTED".to_owned()),
+                        to: "TENTATIVE".to_owned(),
+                        created_override: false,
+                    }
+                ),
+            ]
+        );
+
+        let CalendarInnerData::Event(main, overrides) = object.get_inner() else {
+            panic!("expected an event");
+        };
+        assert_eq!(
+            main.get_named_properties("ATTENDEE")[1].get_param("PARTSTAT"),
+            Some("NEEDS-ACTION")
+        );
+        assert_eq!(overrides.len(), 2);
+        assert_eq!(
+            overrides[0].get_named_properties("ATTENDEE")[0].get_param("PARTSTAT"),
+            Some("TENTATIVE")
+        );
+        let created = &overrides[1];
+        assert_eq!(
+            created.get_recurrence_id().unwrap().value.as_deref(),
+            Some("20250127T100000")
+        );
+        assert_eq!(
+            created.get_dtstart().unwrap().value.as_deref(),
+            Some("20250127T100000")
+        );
+        assert_eq!(
+            created.get_dtend().unwrap().value.as_deref(),
+            Some("20250127T110000")
+        );
+        assert_eq!(created.get_rrule(), None);
+        assert_eq!(
+            created.get_named_properties("ATTENDEE")[1].get_param("PARTSTAT"),
+            Some("ACCEPTED")
+        );
+    }
+
+    #[test]
+    fn apply_reply_without_dtstart() {
+        // DTSTART is optional in a REPLY
+        let mut object = organizer_object();
+        let declined = reply(
+            "BEGIN:VEVENT\r\nUID:planning@example.com\r\nDTSTAMP:20250102T000000Z\r\n\
+            SEQUENCE:1\r\nRECURRENCE-ID;TZID=Europe/Berlin:20250120T100000\r\n\
+            ORGANIZER:mailto:alice@example.com\r\n\
+            ATTENDEE;PARTSTAT=DECLINED:mailto:bob@example.com\r\nEND:VEVENT\r\n",
+        );
+        assert_eq!(
+            itip::apply_reply(&mut object, &declined, &OlsonResolver).unwrap(),
+            vec![itip::ReplyChange {
+                attendee: "mailto:bob@example.com".to_owned(),
+                recurrence_id: Some("20250120T100000".to_owned()),
+                outcome: itip::ReplyOutcome::Updated {
+                    from: Some("ACCEPTED".to_owned()),
+                    to: "DECLINED".to_owned(),
+                    created_override: true,
+                },
+            }]
+        );
+    }
+
+    #[test]
+    fn apply_reply_recurrence_ids() {
+        let mut object = organizer_object();
+        let utc = reply(&reply_component(
+            "PARTSTAT=DECLINED:mailto:bob@example.com",
+            "DTSTAMP:20250102T000000Z\r\nRECURRENCE-ID:20250113T090000Z\r\nSEQUENCE:1\r\n",
+        ));
+        let changes = itip::apply_reply(&mut object, &utc, &OlsonResolver).unwrap();
+        assert_eq!(
+            changes[0].outcome,
+            itip::ReplyOutcome::Updated {
+                from: Some("ACCEPTED".to_owned()),
+                to: "DECLINED".to_owned(),
+                created_override: false,
+            }
+        );
+        let CalendarInnerData::Event(_, overrides) = object.get_inner() else {
+            panic!("expected an event");
+        };
+        assert_eq!(overrides.len(), 1);
+        assert_eq!(
+            overrides[0].get_named_properties("ATTENDEE")[0].get_param("PARTSTAT"),
+            Some("DECLINED")
+        );
+
+        // Nothing is applied unless every component is an instance of the event
+        let before = generate(&object);
+        for recurrence_id in ["20250120T110000", "20250203T100000"] {
+            let replies = reply(&format!(
+                "{}{}",
+                reply_component(
+                    "PARTSTAT=ACCEPTED:mailto:carol@example.com",
+                    "DTSTAMP:20250102T000000Z\r\nRECURRENCE-ID;TZID=Europe/Berlin:20250120T100000\r\n",
+                ),
+                reply_component(
+                    "PARTSTAT=ACCEPTED:mailto:carol@example.com",
+                    &format!(
+                        "DTSTAMP:20250102T000000Z\r\nRECURRENCE-ID;TZID=Europe/Berlin:{recurrence_id}\r\n"
+                    ),
+                )
+            ));
+            assert_eq!(
+                itip::apply_reply(&mut object, &replies, &OlsonResolver).unwrap_err(),
+                ParserError::InvalidValue("RECURRENCE-ID")
+            );
+            assert_eq!(generate(&object), before);
+        }
+    }
+
+    #[test]
+    fn apply_invalid_reply() {
+        let mut object = organizer_object();
+        let unknown = reply(&reply_component(
+            "PARTSTAT=ACCEPTED:mailto:dave@example.com",
+            "DTSTAMP:20250102T000000Z\r\n",
+        ));
+        assert_eq!(
+            itip::apply_reply(&mut object, &unknown, &OlsonResolver).unwrap_err(),
+            ParserError::InvalidValue("ATTENDEE")
+        );
+
+        let other = reply(
+            "BEGIN:VEVENT\r\nUID:other\r\nDTSTAMP:20250102T000000Z\r\nDTSTART:20250106T090000Z\r\n\
+            ATTENDEE:mailto:bob@example.com\r\nEND:VEVENT\r\n",
+        );
+        assert_eq!(
+            itip::apply_reply(&mut object, &other, &OlsonResolver).unwrap_err(),
+            ParserError::InvalidValue("UID")
+        );
+
+        let request = itip::request(&object).unwrap();
+        assert_eq!(
+            itip::apply_reply(&mut object, &request, &OlsonResolver).unwrap_err(),
+            ParserError::InvalidValue("METHOD")
+        );
+    }
+
+    #[test]
+    fn decline_counter() {
+        let object = organizer_object();
+        let message = itip::decline_counter(&object, "mailto:bob@example.com").unwrap();
+        similar_asserts::assert_eq!(
+            generate(&message),
+            "BEGIN:VCALENDAR\r
+PRODID:-//ical-rs//EN\r
+VERSION:2.0\r
+METHOD:DECLINECOUNTER\r
+BEGIN:VEVENT\r
+UID:planning@example.com\r
+SEQUENCE:1\r
+ORGANIZER;CN=Alice:mailto:alice@example.com\r
+ATTENDEE;CN=Bob;PARTSTAT=ACCEPTED:mailto:bob@example.com\r
+END:VEVENT\r
+END:VCALENDAR\r
+"
+        );
+        assert_eq!(parse(&message).generate(), message.generate());
+    }
+}
+
+pub mod attendee {
+    extern crate ical;
+
+    use ical::parser::ical::component::{
+        Attendee, CalendarUserType, IcalEvent, Organizer, ParticipationRole, ParticipationStatus,
+        ScheduleAgent,
+    };
+    use ical::parser::{Component, ComponentMut, ParserError, VerifyOptions};
+    use ical::property::Property;
+
+    const INPUT: &str = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+BEGIN:VEVENT\r
+UID:1\r
+DTSTAMP:20250101T000000Z\r
+DTSTART:20250106T100000Z\r
+ORGANIZER;CN=Alice;SENT-BY=\"mailto:assistant@example.com\";SCHEDULE-AGENT=CLIENT:mailto:alice@example.com\r
+ATTENDEE;CN=\"Bob, Jr.\";CUTYPE=INDIVIDUAL;ROLE=CHAIR;PARTSTAT=ACCEPTED;RSVP=TRUE;MEMBER=\"mailto:team@example.com\";SCHEDULE-STATUS=2.0;LANGUAGE=en:mailto:bob@example.com\r
+ATTENDEE;CUTYPE=ROOM;ROLE=NON-PARTICIPANT;PARTSTAT=X-UNKNOWN:mailto:room@example.com\r
+ATTENDEE;PARTSTAT=DELEGATED;DELEGATED-TO=\"mailto:dave@example.com\",\"mailto:erin@example.com\":mailto:carol@example.com\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+
+    fn event(input: &str) -> Result<IcalEvent, ParserError> {
+        event_with(input, VerifyOptions::strict())
+    }
+
+    fn event_with(input: &str, options: VerifyOptions) -> Result<IcalEvent, ParserError> {
+        let mut calendar = ical::IcalParser::new(input.as_bytes())
+            .with_verify_options(options)
+            .next()
+            .unwrap()?;
+        Ok(calendar.events.remove(0))
+    }
+
+    #[test]
+    fn get() {
+        let event = event(INPUT).unwrap();
+        assert_eq!(
+            event.get_organizer(),
+            Some(Organizer {
+                cn: Some("Alice".to_owned()),
+                sent_by: Some("mailto:assistant@example.com".to_owned()),
+                schedule_agent: Some(ScheduleAgent::Client),
+                ..Organizer::new("mailto:alice@example.com")
+            })
+        );
+
+        let attendees = event.get_attendees();
+        assert_eq!(
+            attendees[0],
+            Attendee {
+                cn: Some("Bob, Jr.".to_owned()),
+                cutype: Some(CalendarUserType::Individual),
+                role: Some(ParticipationRole::Chair),
+                partstat: Some(ParticipationStatus::Accepted),
+                rsvp: Some(true),
+                member: vec!["mailto:team@example.com".to_owned()],
+                schedule_status: vec!["2.0".to_owned()],
+                params: vec![("LANGUAGE".to_owned(), vec!["en".to_owned()])],
+                ..Attendee::new("mailto:bob@example.com")
+            }
+        );
+        assert_eq!(attendees[0].email(), Some("bob@example.com"));
+        assert!(attendees[0].is("BOB@example.com"));
+        assert_eq!(attendees[1].cutype, Some(CalendarUserType::Room));
+        assert_eq!(
+            attendees[1].partstat,
+            Some(ParticipationStatus::Other("X-UNKNOWN".to_owned()))
+        );
+        assert_eq!(
+            attendees[2].delegated_to,
+            vec!["mailto:dave@example.com", "mailto:erin@example.com"]
+        );
+        assert_eq!(attendees[2].role, None);
+    }
+
+    #[test]
+    fn round_trip() {
+        let event = event(INPUT).unwrap();
+        for prop in event.get_named_properties("ATTENDEE") {
+            let attendee = Attendee::try_from(prop).unwrap();
+            assert_eq!(&Property::from(attendee), prop);
+        }
+        let prop = event.get_property("ORGANIZER").unwrap();
+        let organizer = Organizer::try_from(prop).unwrap();
+        assert_eq!(&Property::from(organizer), prop);
+
+        assert_eq!(
+            Attendee::try_from(prop).unwrap_err(),
+            ParserError::InvalidValue("ATTENDEE")
+        );
+    }
+
+    #[test]
+    fn round_trip_extra_values() {
+        let input = INPUT.replace(
+            "ATTENDEE;CUTYPE=ROOM;ROLE=NON-PARTICIPANT;PARTSTAT=X-UNKNOWN:mailto:room@example.com",
+            "ATTENDEE;CN=a;LANGUAGE=en;CN=b;ROLE=CHAIR,OPT-PARTICIPANT;PARTSTAT=accepted;RSVP=true;SENT-BY=\"mailto:x@example.com\",\"mailto:y@example.com\":mailto:room@example.com",
+        );
+        let event = event(&input).unwrap();
+        let prop = event.get_named_properties("ATTENDEE")[1];
+        let attendee = Attendee::try_from(prop).unwrap();
+        assert_eq!(
+            attendee,
+            Attendee {
+                cn: Some("a".to_owned()),
+                partstat: Some(ParticipationStatus::Accepted),
+                rsvp: Some(true),
+                params: vec![
+                    ical::ical_param!("LANGUAGE", "en"),
+                    ical::ical_param!("CN", "b"),
+                    ical::ical_param!("ROLE", "CHAIR", "OPT-PARTICIPANT"),
+                    ical::ical_param!("SENT-BY", "mailto:x@example.com", "mailto:y@example.com"),
+                ],
+                ..Attendee::new("mailto:room@example.com")
+            }
+        );
+        assert_eq!(
+            Property::from(attendee),
+            ical::ical_property!(
+                "ATTENDEE",
+                "mailto:room@example.com",
+                ical::ical_param!("CN", "a"),
+                ical::ical_param!("PARTSTAT", "ACCEPTED"),
+                ical::ical_param!("RSVP", "TRUE"),
+                ical::ical_param!("LANGUAGE", "en"),
+                ical::ical_param!("CN", "b"),
+                ical::ical_param!("ROLE", "CHAIR", "OPT-PARTICIPANT"),
+                ical::ical_param!("SENT-BY", "mailto:x@example.com", "mailto:y@example.com")
+            )
+        );
+
+        let prop = ical::ical_property!(
+            "ORGANIZER",
+            "mailto:alice@example.com",
+            ical::ical_param!("CN", "Alice", "Al"),
+            ical::ical_param!("SCHEDULE-AGENT", "CLIENT"),
+            ical::ical_param!("SCHEDULE-AGENT", "SERVER")
+        );
+        let organizer = Organizer::try_from(&prop).unwrap();
+        assert_eq!(organizer.cn, None);
+        assert_eq!(organizer.schedule_agent, Some(ScheduleAgent::Client));
+        assert_eq!(
+            Property::from(organizer),
+            ical::ical_property!(
+                "ORGANIZER",
+                "mailto:alice@example.com",
+                ical::ical_param!("SCHEDULE-AGENT", "CLIENT"),
+                ical::ical_param!("CN", "Alice", "Al"),
+                ical::ical_param!("SCHEDULE-AGENT", "SERVER")
+            )
+        );
+    }
+
+    #[test]
+    fn invalid_rsvp() {
+        let input = INPUT.replace("RSVP=TRUE", "RSVP=MAYBE");
+        assert_eq!(
+            event(&input).unwrap_err(),
+            ParserError::InvalidValue("ATTENDEE")
+        );
+
+        // Lenient parsing keeps the raw value
+        let event = event_with(&input, VerifyOptions::lenient()).unwrap();
+        let bob = &event.get_attendees()[0];
+        assert_eq!(bob.rsvp, None);
+        assert!(
+            bob.params
+                .contains(&("RSVP".to_owned(), vec!["MAYBE".to_owned()]))
+        );
+        let prop = event.get_named_properties("ATTENDEE")[0];
+        assert_eq!(
+            Attendee::try_from(prop).unwrap_err(),
+            ParserError::InvalidValue("ATTENDEE")
+        );
+    }
+
+    #[test]
+    fn missing_address() {
+        let input = INPUT.replace(":mailto:room@example.com", ":");
+        assert_eq!(
+            event(&input).unwrap_err(),
+            ParserError::InvalidValue("ATTENDEE")
+        );
+
+        let event = event_with(&input, VerifyOptions::lenient()).unwrap();
+        assert_eq!(
+            event
+                .get_attendees()
+                .iter()
+                .map(|attendee| attendee.address.as_str())
+                .collect::<Vec<_>>(),
+            vec!["mailto:bob@example.com", "mailto:carol@example.com"]
+        );
+        assert_eq!(event.get_named_properties("ATTENDEE").len(), 3);
+    }
+
+    #[test]
+    fn edit() {
+        let mut event = event(INPUT).unwrap().mutable();
+        event.add_attendee(Attendee {
+            role: Some(ParticipationRole::OptParticipant),
+            rsvp: Some(true),
+            ..Attendee::new("mailto:frank@example.com")
+        });
+        assert!(event.update_attendee(Attendee {
+            partstat: Some(ParticipationStatus::Tentative),
+            ..Attendee::new("MAILTO:bob@example.com")
+        }));
+        assert!(!event.update_attendee(Attendee::new("mailto:nobody@example.com")));
+        assert!(event.remove_attendee("room@example.com"));
+        assert!(!event.remove_attendee("room@example.com"));
+        event.set_organizer(Organizer::new("mailto:carol@example.com"));
+
+        let event = event.verify().unwrap();
+        assert_eq!(
+            event
+                .get_attendees()
+                .iter()
+                .map(|attendee| (attendee.address.as_str(), attendee.partstat.clone()))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    "MAILTO:bob@example.com",
+                    Some(ParticipationStatus::Tentative)
+                ),
+                (
+                    "mailto:carol@example.com",
+                    Some(ParticipationStatus::Delegated)
+                ),
+                ("mailto:frank@example.com", None),
+            ]
+        );
+        assert_eq!(
+            event.get_named_properties("ATTENDEE")[2],
+            &ical::ical_property!(
+                "ATTENDEE",
+                "mailto:frank@example.com",
+                ical::ical_param!("ROLE", "OPT-PARTICIPANT"),
+                ical::ical_param!("RSVP", "TRUE")
+            )
+        );
+        assert_eq!(
+            event.get_organizer(),
+            Some(Organizer::new("mailto:carol@example.com"))
+        );
+    }
+}
+
+pub mod imip {
+    extern crate ical;
+
+    use ical::generator::Emitter;
+    use ical::parser::ical::imip::{self, ImipError};
+    use ical::parser::ical::itip;
+    use ical::parser::{Component, LimitError, ParserError, ParserLimits};
+    use ical::property::Property;
+    use ical::types::OlsonResolver;
+
+    /// The calendars of a message that are all expected to parse.
+    fn calendars(message: &[u8]) -> Vec<imip::CalendarPart> {
+        imip::parse(message, &ParserLimits::default())
+            .unwrap()
+            .into_iter()
+            .collect::<Result<_, _>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn parse() {
+        let input = include_bytes!("./resources/imip_request.eml");
+        let parts = calendars(input);
+        assert_eq!(parts.len(), 2);
+
+        assert_eq!(parts[0].method.as_deref(), Some("REQUEST"));
+        let event = &parts[0].calendar.events[0];
+        assert_eq!(event.get_uid(), "planning@example.com");
+        assert_eq!(
+            event.get_property("SUMMARY").unwrap().value.as_deref(),
+            Some("Réunion de planification avec une description assez longue pour être coupée")
+        );
+        assert_eq!(event.get_attendees()[0].rsvp, Some(true));
+
+        // The reply has no METHOD property, so the part's method parameter is used
+        assert_eq!(parts[1].method.as_deref(), Some("REPLY"));
+        assert_eq!(
+            parts[1].calendar.events[0]
+                .get_property("COMMENT")
+                .unwrap()
+                .value
+                .as_deref(),
+            Some("Très bien")
+        );
+    }
+
+    #[test]
+    fn parse_single_part() {
+        let input = "Subject: Invitation\r\nContent-Type: text/calendar\r\n\r\n\
+            BEGIN:VCALENDAR\r\nMETHOD:CANCEL\r\nEND:VCALENDAR\r\n";
+        let parts = calendars(input.as_bytes());
+        assert_eq!(parts[0].method.as_deref(), Some("CANCEL"));
+
+        let input = "Subject: Hello\r\n\r\nBEGIN:VCALENDAR\r\nEND:VCALENDAR\r\n";
+        assert!(calendars(input.as_bytes()).is_empty());
+    }
+
+    #[test]
+    fn parse_nested_boundaries() {
+        // The inner boundary starts with the outer one
+        let input = "Content-Type: multipart/mixed; boundary=abc\r\n\r\n\
+            --abc\r\n\
+            Content-Type: multipart/alternative; boundary=abc-2\r\n\r\n\
+            --abc-2\r\n\
+            Content-Type: text/plain\r\n\r\n\
+            Hello\r\n\
+            --abc-2\r\n\
+            Content-Type: text/calendar; method=REQUEST\r\n\r\n\
+            BEGIN:VCALENDAR\r\nMETHOD:REQUEST\r\nEND:VCALENDAR\r\n\
+            --abc-2--\r\n\
+            --abc \r\n\
+            Content-Type: text/calendar; method=CANCEL\r\n\r\n\
+            BEGIN:VCALENDAR\r\nMETHOD:CANCEL\r\nEND:VCALENDAR\r\n\
+            --abc--\r\n";
+        let parts = calendars(input.as_bytes());
+        assert_eq!(
+            parts
+                .iter()
+                .map(|part| part.method.as_deref())
+                .collect::<Vec<_>>(),
+            vec![Some("REQUEST"), Some("CANCEL")]
+        );
+    }
+
+    #[test]
+    fn parse_invalid() {
+        for (input, error) in [
+            (
+                "Content-Type: multipart/mixed\r\n\r\n",
+                "missing multipart boundary",
+            ),
+            (
+                "Content-Type: multipart/mixed; boundary=b\r\n\r\n--b\r\n\r\ntext\r\n",
+                "missing closing multipart boundary",
+            ),
+            (
+                "Content-Type: text/calendar\r\nContent-Transfer-Encoding: base64\r\n\r\n!!!!\r\n",
+                "invalid base64",
+            ),
+        ] {
+            assert_eq!(
+                imip::parse(input.as_bytes(), &ParserLimits::default()).unwrap_err(),
+                ImipError::InvalidMime(error)
+            );
+        }
+    }
+
+    #[test]
+    fn parse_deeply_nested() {
+        let nested = |depth: usize| {
+            let mut message = "Content-Type: text/calendar\r\n\r\n\
+                BEGIN:VCALENDAR\r\nMETHOD:REQUEST\r\nEND:VCALENDAR\r\n"
+                .to_owned();
+            for level in (0..depth).rev() {
+                message = format!(
+                    "Content-Type: multipart/mixed; boundary=b{level}\r\n\r\n\
+                    --b{level}\r\n{message}\r\n--b{level}--\r\n"
+                );
+            }
+            message
+        };
+        let parts = calendars(nested(imip::MAX_MULTIPART_DEPTH).as_bytes());
+        assert_eq!(parts[0].method.as_deref(), Some("REQUEST"));
+        for depth in [imip::MAX_MULTIPART_DEPTH + 1, 1000] {
+            assert_eq!(
+                imip::parse(nested(depth).as_bytes(), &ParserLimits::default()).unwrap_err(),
+                ImipError::InvalidMime("too deeply nested multipart")
+            );
+        }
+    }
+
+    #[test]
+    fn parse_with_limits() {
+        let input = "Content-Type: text/calendar\r\n\r\n\
+            BEGIN:VCALENDAR\r\nMETHOD:REQUEST\r\nPRODID:-//Example//EN\r\nEND:VCALENDAR\r\n";
+        let limits = ParserLimits {
+            max_properties: Some(1),
+            ..Default::default()
+        };
+        let parts = imip::parse(input.as_bytes(), &limits).unwrap();
+        assert_eq!(
+            parts[0].as_ref().unwrap_err(),
+            &ParserError::LimitExceeded(LimitError::TooManyProperties(1))
+        );
+    }
+
+    #[test]
+    fn parse_invalid_part() {
+        // A calendar that fails to parse doesn't hide the other parts
+        let input = "Content-Type: multipart/mixed; boundary=b\r\n\r\n\
+            --b\r\n\
+            Content-Type: text/calendar\r\n\r\n\
+            BEGIN:VCALENDAR\r\nMETHOD:REQUEST\r\nBEGIN:VEVENT\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n\
+            --b\r\n\
+            Content-Type: text/calendar\r\n\r\n\
+            BEGIN:VCALENDAR\r\nMETHOD:CANCEL\r\nEND:VCALENDAR\r\n\
+            --b--\r\n";
+        let parts = imip::parse(input.as_bytes(), &ParserLimits::default()).unwrap();
+        assert_eq!(parts.len(), 2);
+        assert_eq!(
+            parts[0].as_ref().unwrap_err(),
+            &ParserError::MissingProperty("UID")
+        );
+        assert_eq!(parts[1].as_ref().unwrap().method.as_deref(), Some("CANCEL"));
+    }
+
+    #[test]
+    fn parse_quoted_printable() {
+        // `=+1` isn't a hex escape and stays as it is
+        let input = "Content-Type: text/calendar\r\n\
+            Content-Transfer-Encoding: quoted-printable\r\n\r\n\
+            BEGIN:VCALENDAR\r\nMETHOD:REQUEST\r\nCOMMENT:a=3Db=+1 =\r\nc\r\nEND:VCALENDAR\r\n";
+        let parts = calendars(input.as_bytes());
+        assert_eq!(
+            parts[0]
+                .calendar
+                .get_property("COMMENT")
+                .unwrap()
+                .value
+                .as_deref(),
+            Some("a=b=+1 c")
+        );
+    }
+
+    #[test]
+    fn build() {
+        let input = include_str!("./resources/ical_itip.ics");
+        let object = ical::IcalObjectParser::new(input.as_bytes())
+            .next()
+            .unwrap()
+            .unwrap();
+        let request = itip::request(&object).unwrap();
+        let text = "Alice invites you to \"Planning\" on Monday at 10:00 – please reply, as the \
+            room = small.";
+        let body = imip::build(&request, text, "=_boundary").unwrap();
+        assert!(body.starts_with(
+            "MIME-Version: 1.0\r
+Content-Type: multipart/alternative; boundary=\"=_boundary\"\r
+\r
+--=_boundary\r
+Content-Type: text/plain; charset=UTF-8\r
+Content-Transfer-Encoding: quoted-printable\r
+\r
+Alice invites you to \"Planning\" on Monday at 10:00 =E2=80=93 please reply, =\r
+as the room =3D small.\r
+--=_boundary\r
+Content-Type: text/calendar; charset=UTF-8; method=REQUEST\r
+Content-Transfer-Encoding: base64\r
+\r
+"
+        ));
+        assert!(body.ends_with("\r\n--=_boundary--\r\n"));
+        assert!(body.lines().all(|line| line.len() <= 76));
+
+        let parts = calendars(body.as_bytes());
+        assert_eq!(parts.len(), 1);
+        assert_eq!(parts[0].method.as_deref(), Some("REQUEST"));
+        assert_eq!(parts[0].calendar.generate(), request.generate());
+    }
+
+    #[test]
+    fn build_every_method() {
+        let input = include_str!("./resources/ical_itip.ics");
+        let organizer_object = || {
+            ical::IcalObjectParser::new(input.as_bytes())
+                .next()
+                .unwrap()
+                .unwrap()
+        };
+        let start = ical::ical_property!(
+            "DTSTART",
+            "20250201T090000",
+            ical::ical_param!("TZID", "Europe/Berlin")
+        );
+        let recurrence_id = ical::ical_property!(
+            "RECURRENCE-ID",
+            "20250120T100000",
+            ical::ical_param!("TZID", "Europe/Berlin")
+        );
+        let attendee = "mailto:bob@example.com";
+        let messages = [
+            itip::request(&organizer_object()).unwrap(),
+            itip::cancel(&mut organizer_object(), None, &OlsonResolver).unwrap(),
+            itip::cancel(
+                &mut organizer_object(),
+                Some(&recurrence_id),
+                &OlsonResolver,
+            )
+            .unwrap(),
+            itip::add(&mut organizer_object(), &start, &OlsonResolver).unwrap(),
+            itip::refresh(&organizer_object(), attendee).unwrap(),
+            itip::counter(&organizer_object(), attendee).unwrap(),
+            itip::decline_counter(&organizer_object(), attendee).unwrap(),
+        ];
+        for message in messages {
+            let method = message.get_property("METHOD").unwrap().value.clone();
+            let body = imip::build(&message, "", "b").unwrap();
+            let parts = calendars(body.as_bytes());
+            assert_eq!(parts.len(), 1);
+            assert_eq!(parts[0].method, method);
+            assert_eq!(parts[0].calendar.generate(), message.generate());
+        }
+    }
+
+    #[test]
+    fn build_without_method() {
+        let input = include_str!("./resources/ical_itip.ics");
+        let calendar = ical::IcalParser::new(input.as_bytes())
+            .next()
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            imip::build(&calendar, "", "b").unwrap_err(),
+            ImipError::Parser(ParserError::MissingProperty("METHOD"))
+        );
+    }
+}
+
+pub mod edit {
+    extern crate ical;
+
+    use chrono::{TimeZone, Utc};
+    use ical::parser::ical::component::{EditSummary, IcalEvent};
+    use ical::parser::{Component, ComponentMut};
+    use ical::property::Property;
+
+    const INPUT: &str = "BEGIN:VCALENDAR\r
+BEGIN:VEVENT\r
+UID:1\r
+DTSTAMP:20250101T000000Z\r
+DTSTART:20250106T100000Z\r
+RRULE:FREQ=WEEKLY\r
+SEQUENCE:3\r
+SUMMARY:Planning\r
+LOCATION:Room 1\r
+END:VEVENT\r
+BEGIN:VJOURNAL\r
+UID:2\r
+DTSTAMP:20250101T000000Z\r
+SUMMARY:Notes\r
+END:VJOURNAL\r
+END:VCALENDAR\r
+";
+
+    fn event() -> IcalEvent<false> {
+        let mut calendar = ical::IcalParser::new(INPUT.as_bytes())
+            .next()
+            .unwrap()
+            .unwrap();
+        calendar.events.remove(0).mutable()
+    }
+
+    fn value<'c, C: Component>(component: &'c C, name: &str) -> Option<&'c str> {
+        component
+            .get_property(name)
+            .and_then(|prop| prop.value.as_deref())
+    }
+
+    #[test]
+    fn significant_change() {
+        let mut event = event();
+        let mut session = event.edit();
+        session.set_property(ical::ical_property!("LOCATION", "Room 2"));
+        session.set_property(ical::ical_property!("DESCRIPTION", "Agenda"));
+        let summary = session.commit(Utc.with_ymd_and_hms(2025, 2, 1, 12, 0, 0).unwrap());
+
+        assert_eq!(
+            summary,
+            EditSummary {
+                changed: vec!["LOCATION".to_owned(), "DESCRIPTION".to_owned()],
+                significant: true,
+            }
+        );
+        assert_eq!(value(&event, "SEQUENCE"), Some("4"));
+        assert_eq!(value(&event, "DTSTAMP"), Some("20250201T120000Z"));
+        assert_eq!(value(&event, "LAST-MODIFIED"), Some("20250201T120000Z"));
+        event.verify().unwrap();
+    }
+
+    #[test]
+    fn insignificant_change() {
+        let mut event = event();
+        let mut session = event.edit();
+        session.set_property(ical::ical_property!("SUMMARY", "Weekly planning"));
+        let summary = session.commit(Utc.with_ymd_and_hms(2025, 2, 1, 12, 0, 0).unwrap());
+
+        assert!(!summary.significant);
+        assert_eq!(value(&event, "SEQUENCE"), Some("3"));
+        assert_eq!(value(&event, "DTSTAMP"), Some("20250201T120000Z"));
+    }
+
+    #[test]
+    fn unchanged() {
+        let mut event = event();
+        let mut session = event.edit();
+        // Setting a property to its current value moves it, but doesn't change it
+        session.set_property(ical::ical_property!("SUMMARY", "Planning"));
+        session.remove_property("COMMENT");
+        let summary = session.commit(Utc.with_ymd_and_hms(2025, 2, 1, 12, 0, 0).unwrap());
+
+        assert_eq!(summary, EditSummary::default());
+        assert_eq!(value(&event, "DTSTAMP"), Some("20250101T000000Z"));
+        assert_eq!(value(&event, "LAST-MODIFIED"), None);
+    }
+
+    #[test]
+    fn direct_change() {
+        let mut event = event();
+        let mut session = event.edit();
+        session.get_property_mut("RRULE").unwrap().value = Some("FREQ=DAILY".to_owned());
+        session.set_property(ical::ical_property!("SEQUENCE", "10"));
+        let summary = session.commit(Utc.with_ymd_and_hms(2025, 2, 1, 12, 0, 0).unwrap());
+
+        assert_eq!(summary.changed, vec!["RRULE", "SEQUENCE"]);
+        assert!(summary.significant);
+        // The SEQUENCE set during the session is kept
+        assert_eq!(value(&event, "SEQUENCE"), Some("10"));
+        assert_eq!(value(&event, "LAST-MODIFIED"), Some("20250201T120000Z"));
+    }
+
+    #[test]
+    fn journal() {
+        let mut calendar = ical::IcalParser::new(INPUT.as_bytes())
+            .next()
+            .unwrap()
+            .unwrap();
+        let mut journal = calendar.journals.remove(0).mutable();
+        let mut session = journal.edit();
+        session.set_property(ical::ical_property!("STATUS", "FINAL"));
+        session.commit(Utc.with_ymd_and_hms(2025, 2, 1, 12, 0, 0).unwrap());
+
+        assert_eq!(value(&journal, "SEQUENCE"), Some("1"));
+        assert_eq!(value(&journal, "DTSTAMP"), Some("20250201T120000Z"));
+        journal.verify().unwrap();
+    }
+}
+
+pub mod occurrence {
+    extern crate ical;
+
+    use chrono::{DateTime, TimeZone, Utc};
+    use ical::generator::Emitter;
+    use ical::parser::ical::component::{CalendarInnerData, IcalCalendarObject, IcalEvent};
+    use ical::parser::ical::recurrence::{expand, resolve_timezones};
+    use ical::parser::{Component, ParserError};
+    use ical::property::Property;
+    use ical::types::OlsonResolver;
+
+    const INPUT: &str = "BEGIN:VCALENDAR\r
+PRODID:-//ical-rs//EN\r
+VERSION:2.0\r
+BEGIN:VEVENT\r
+UID:standup@example.com\r
+DTSTAMP:20250101T000000Z\r
+DTSTART;TZID=Europe/Berlin:20250106T100000\r
+DTEND;TZID=Europe/Berlin:20250106T103000\r
+RRULE:FREQ=WEEKLY;COUNT=6\r
+EXDATE;TZID=Europe/Berlin:20250203T100000\r
+RDATE;TZID=Europe/Berlin:20250301T100000\r
+SEQUENCE:2\r
+SUMMARY:Standup\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:standup@example.com\r
+DTSTAMP:20250101T000000Z\r
+RECURRENCE-ID;TZID=Europe/Berlin:20250113T100000\r
+DTSTART;TZID=Europe/Berlin:20250113T110000\r
+DTEND;TZID=Europe/Berlin:20250113T113000\r
+SEQUENCE:2\r
+SUMMARY:Standup\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:standup@example.com\r
+DTSTAMP:20250101T000000Z\r
+RECURRENCE-ID;TZID=Europe/Berlin:20250127T100000\r
+DTSTART;TZID=Europe/Berlin:20250127T100000\r
+DTEND;TZID=Europe/Berlin:20250127T110000\r
+SEQUENCE:2\r
+SUMMARY:Long standup\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+
+    fn object() -> IcalCalendarObject {
+        ical::IcalObjectParser::new(INPUT.as_bytes())
+            .next()
+            .unwrap()
+            .unwrap()
+    }
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 1, 15, 8, 0, 0).unwrap()
+    }
+
+    fn berlin(name: &str, value: &str) -> Property {
+        Property {
+            name: name.to_owned(),
+            params: vec![("TZID".to_owned(), vec!["Europe/Berlin".to_owned()])],
+            value: Some(value.to_owned()),
+        }
+    }
+
+    fn events(object: &IcalCalendarObject) -> (&IcalEvent, &Vec<IcalEvent>) {
+        let CalendarInnerData::Event(main, overrides) = object.get_inner() else {
+            panic!("expected an event");
+        };
+        (main, overrides)
+    }
+
+    fn values<'c, C: Component>(component: &'c C, name: &str) -> Vec<&'c str> {
+        component
+            .get_named_properties(name)
+            .into_iter()
+            .filter_map(|prop| prop.value.as_deref())
+            .collect()
+    }
+
+    /// The starts of all instances in UTC.
+    fn starts(object: &IcalCalendarObject) -> Vec<String> {
+        let (main, overrides) = events(object);
+        let components: Vec<_> = std::iter::once(main).chain(overrides).collect();
+        let timezones = resolve_timezones(
+            components.iter().flat_map(|event| &event.properties),
+            object.get_timezones(),
+            &OlsonResolver,
+        );
+        expand(
+            components,
+            &timezones,
+            Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap(),
+        )
+        .unwrap()
+        .into_iter()
+        .map(|instance| {
+            instance
+                .start
+                .unwrap()
+                .utc()
+                .format("%m-%d %H:%M")
+                .to_string()
+        })
+        .collect()
+    }
+
+    #[test]
+    fn modify_occurrence() {
+        let mut object = object();
+        let summary = object
+            .modify_occurrence(
+                &berlin("RECURRENCE-ID", "20250120T100000"),
+                [
+                    berlin("DTSTART", "20250120T120000"),
+                    berlin("DTEND", "20250120T123000"),
+                ],
+                now(),
+                &OlsonResolver,
+            )
+            .unwrap();
+        assert_eq!(summary.changed, vec!["DTEND", "DTSTART"]);
+        assert!(summary.significant);
+
+        let (main, overrides) = events(&object);
+        assert_eq!(values(main, "SEQUENCE"), vec!["2"]);
+        let created = &overrides[2];
+        assert_eq!(created.get_uid(), "standup@example.com");
+        assert_eq!(
+            created.get_recurrence_id(),
+            Some(&berlin("RECURRENCE-ID", "20250120T100000"))
+        );
+        assert_eq!(created.get_rrule(), None);
+        assert_eq!(values(created, "EXDATE"), Vec::<&str>::new());
+        assert_eq!(values(created, "SUMMARY"), vec!["Standup"]);
+        assert_eq!(values(created, "SEQUENCE"), vec!["3"]);
+        assert_eq!(values(created, "LAST-MODIFIED"), vec!["20250115T080000Z"]);
+        assert_eq!(
+            starts(&object),
+            vec![
+                "01-06 09:00",
+                "01-13 10:00",
+                "01-20 11:00",
+                "01-27 09:00",
+                "02-10 09:00",
+                "03-01 09:00"
+            ]
+        );
+
+        // An existing override is edited in place
+        let summary = object
+            .modify_occurrence(
+                &berlin("RECURRENCE-ID", "20250113T100000"),
+                [ical::ical_property!("SUMMARY", "Retro")],
+                now(),
+                &OlsonResolver,
+            )
+            .unwrap();
+        assert!(!summary.significant);
+        let (_, overrides) = events(&object);
+        assert_eq!(overrides.len(), 3);
+        assert_eq!(values(&overrides[0], "SUMMARY"), vec!["Retro"]);
+        assert_eq!(values(&overrides[0], "SEQUENCE"), vec!["2"]);
+    }
+
+    #[test]
+    fn modify_missing_occurrence() {
+        let mut object = object();
+        for value in ["20250121T100000", "20250203T100000", "20250317T100000"] {
+            assert_eq!(
+                object
+                    .modify_occurrence(&berlin("RECURRENCE-ID", value), [], now(), &OlsonResolver)
+                    .unwrap_err(),
+                ParserError::InvalidValue("RECURRENCE-ID")
+            );
+        }
+    }
+
+    #[test]
+    fn delete_occurrence() {
+        let mut object = object();
+        object
+            .delete_occurrence(
+                &berlin("RECURRENCE-ID", "20250120T100000"),
+                now(),
+                &OlsonResolver,
+            )
+            .unwrap();
+        object
+            .delete_occurrence(
+                &berlin("RECURRENCE-ID", "20250113T100000"),
+                now(),
+                &OlsonResolver,
+            )
+            .unwrap();
+
+        let (main, overrides) = events(&object);
+        assert_eq!(overrides.len(), 1);
+        assert_eq!(
+            main.get_named_properties("EXDATE"),
+            vec![
+                &berlin("EXDATE", "20250203T100000"),
+                &berlin("EXDATE", "20250120T100000"),
+                &berlin("EXDATE", "20250113T100000"),
+            ]
+        );
+        assert_eq!(values(main, "SEQUENCE"), vec!["4"]);
+        assert_eq!(
+            starts(&object),
+            vec!["01-06 09:00", "01-27 09:00", "02-10 09:00", "03-01 09:00"]
+        );
+
+        // The occurrence is gone now
+        assert_eq!(
+            object
+                .delete_occurrence(
+                    &berlin("RECURRENCE-ID", "20250120T100000"),
+                    now(),
+                    &OlsonResolver,
+                )
+                .unwrap_err(),
+            ParserError::InvalidValue("RECURRENCE-ID")
+        );
+    }
+
+    #[test]
+    fn split_series() {
+        let mut object = object();
+        let following = object
+            .split_series(
+                &berlin("RECURRENCE-ID", "20250127T100000"),
+                "standup-2@example.com",
+                now(),
+                &OlsonResolver,
+            )
+            .unwrap();
+
+        let (main, overrides) = events(&object);
+        assert_eq!(
+            values(main, "RRULE"),
+            vec!["FREQ=WEEKLY;UNTIL=20250127T085959Z"]
+        );
+        assert_eq!(values(main, "EXDATE"), Vec::<&str>::new());
+        assert_eq!(values(main, "RDATE"), Vec::<&str>::new());
+        assert_eq!(values(main, "SEQUENCE"), vec!["3"]);
+        assert_eq!(overrides.len(), 1);
+        assert_eq!(
+            starts(&object),
+            vec!["01-06 09:00", "01-13 10:00", "01-20 09:00"]
+        );
+
+        let (main, overrides) = events(&following);
+        assert_eq!(following.get_uid(), "standup-2@example.com");
+        assert_eq!(
+            main.get_dtstart(),
+            Some(&berlin("DTSTART", "20250127T100000"))
+        );
+        assert_eq!(main.get_dtend(), Some(&berlin("DTEND", "20250127T103000")));
+        assert_eq!(values(main, "RRULE"), vec!["FREQ=WEEKLY;COUNT=3"]);
+        assert_eq!(values(main, "EXDATE"), vec!["20250203T100000"]);
+        assert_eq!(values(main, "RDATE"), vec!["20250301T100000"]);
+        assert_eq!(values(main, "SEQUENCE"), Vec::<&str>::new());
+        assert_eq!(values(main, "DTSTAMP"), vec!["20250115T080000Z"]);
+        assert_eq!(overrides.len(), 1);
+        assert_eq!(overrides[0].get_uid(), "standup-2@example.com");
+        assert_eq!(
+            starts(&following),
+            vec!["01-27 09:00", "02-10 09:00", "03-01 09:00"]
+        );
+    }
+
+    #[test]
+    fn split_series_utc() {
+        let mut object = object();
+        let following = object
+            .split_series(
+                &ical::ical_property!("RECURRENCE-ID", "20250127T090000Z"),
+                "standup-2@example.com",
+                now(),
+                &OlsonResolver,
+            )
+            .unwrap();
+
+        // The new series stays in the zone of the original one
+        let (main, _) = events(&following);
+        assert_eq!(
+            main.get_dtstart(),
+            Some(&berlin("DTSTART", "20250127T100000"))
+        );
+        assert_eq!(main.get_dtend(), Some(&berlin("DTEND", "20250127T103000")));
+        assert_eq!(
+            starts(&following),
+            vec!["01-27 09:00", "02-10 09:00", "03-01 09:00"]
+        );
+        assert_eq!(
+            starts(&object),
+            vec!["01-06 09:00", "01-13 10:00", "01-20 09:00"]
+        );
+    }
+
+    #[test]
+    fn split_at_first_occurrence() {
+        let mut object = object();
+        assert_eq!(
+            object
+                .split_series(
+                    &berlin("RECURRENCE-ID", "20250106T100000"),
+                    "standup-2@example.com",
+                    now(),
+                    &OlsonResolver,
+                )
+                .unwrap_err(),
+            ParserError::PropertyConflict("cannot split a series at its first occurrence")
+        );
+    }
+
+    #[test]
+    fn split_failing_override() {
+        // The RECURRENCE-ID of the last override can't be resolved
+        let input = INPUT.replace(
+            "RECURRENCE-ID;TZID=Europe/Berlin:20250127T100000",
+            "RECURRENCE-ID;TZID=Mars/Olympus:20250127T100000",
+        );
+        let mut object = ical::IcalObjectParser::new(input.as_bytes())
+            .next()
+            .unwrap()
+            .unwrap();
+        let before = object.generate();
+        assert!(
+            object
+                .split_series(
+                    &berlin("RECURRENCE-ID", "20250120T100000"),
+                    "standup-2@example.com",
+                    now(),
+                    &OlsonResolver,
+                )
+                .is_err()
+        );
+        similar_asserts::assert_eq!(object.generate(), before);
+    }
 }
 
 pub mod calendar_object {